@@ -1,11 +1,13 @@
 use std::path::{Path, PathBuf};
-use image::{RgbaImage, Rgba, imageops};
+use image::{RgbaImage, imageops};
 use eframe::egui;
 
 use leaf_complex_rust_lib::{
     Config, morphology, shape_analysis, point_analysis, 
-    feature_extraction, thornfiddle, load_image,
+    feature_extraction, thornfiddle, output, holes, load_image, LeafContour, LeafMask, SignalStart,
+    OrientationMethod, normalize_orientation, detect_petiole, mark_petiole, calculate_entropy_metrics,
 };
+use leaf_complex_rust_lib::leaf_mask::{LEAF, EC_OPENED, MC_REMOVED, LOBE};
 
 
 // Import from state.rs, not defining our own
@@ -74,8 +76,43 @@ impl AnalysisEngine {
             image
         };
         
+        // Base at the bottom centre, as in the CLI pipeline
+        let processed_image = if config.orientation_method == OrientationMethod::None {
+            processed_image
+        } else {
+            let thresholds = config.alpha_policy().resolve(&processed_image);
+            let region = LeafMask::from_image(&processed_image, &thresholds).region(true);
+            let petiole_base = if config.orientation_method == OrientationMethod::Petiole {
+                detect_petiole(&region, config.petiole_max_width_ratio, config.petiole_min_elongation)
+                    .ok()
+                    .flatten()
+                    .map(|petiole| petiole.base)
+            } else {
+                None
+            };
+            normalize_orientation(&processed_image, &region, config.orientation_method, petiole_base).0
+        };
+        
+        let alpha_thresholds = config.alpha_policy().resolve(&processed_image);
+        let mut mask = LeafMask::from_image(&processed_image, &alpha_thresholds);
+        
+        let (_hole_metrics, hole_pixels) = holes::analyze_holes(&mask.region(true));
+        holes::mark_holes(&mut mask, &hole_pixels, config.fill_holes);
+        
+        // Petiole is detected (and optionally cut) before any region is derived, as in the CLI pipeline
+        let petiole = if config.enable_petiole_detection {
+            let petiole = detect_petiole(&mask.region(true), config.petiole_max_width_ratio, config.petiole_min_elongation)
+                .map_err(|e| format!("Petiole detection failed: {}", e))?;
+            if let Some(petiole) = &petiole {
+                mark_petiole(&mut mask, &petiole.mask, config.remove_petiole);
+            }
+            petiole
+        } else {
+            None
+        };
+        
         let adaptive_opening_kernel_size = calculate_adaptive_opening_kernel_size(
-            &processed_image,
+            &mask,
             config.adaptive_opening_max_density,
             config.adaptive_opening_max_percentage,
             config.adaptive_opening_min_percentage,
@@ -83,58 +120,52 @@ impl AnalysisEngine {
         
         println!("Adaptive opening kernel size: {}", adaptive_opening_kernel_size);
         
-        let opened_image = morphology::apply_opening(&processed_image, adaptive_opening_kernel_size)
+        let leaf_region = mask.region(true);
+        let opened_region = morphology::apply_opening(&leaf_region, adaptive_opening_kernel_size)
             .map_err(|e| format!("Opening failed: {}", e))?;
         
-        let mut marked_image = mark_opened_regions(
-            &processed_image,
-            &opened_image,
-            config.marked_region_color_rgb,
-        );
+        morphology::mark_opened_regions(&mut mask, &opened_region);
         
         // CRITICAL FIX: Clean thin artifacts (single-pixel lines that shouldn't be marked)
-        marked_image = clean_thin_artifacts(&marked_image, config.marked_region_color_rgb);
+        clean_thin_artifacts(&mut mask);
         
-        let mc_image = morphology::create_mc_with_com_component(
-            &processed_image,
-            &mut marked_image,
-            config.marked_region_color_rgb,
-        );
+        let mc_region = morphology::create_mc_with_com_component(&mut mask);
         
-        println!("Created MC image");
+        println!("Created MC region");
         
         let ec_reference_point = point_analysis::get_reference_point(
-            &processed_image,
-            &marked_image,
+            &mask,
             &config.reference_point_choice,
-            config.marked_region_color_rgb,
         ).map_err(|e| format!("Failed to get EC reference point: {}", e))?;
         
         let mc_reference_point = point_analysis::get_mc_reference_point(
-            &mc_image,
-            &marked_image,
+            &mask,
             &config.reference_point_choice,
-            config.marked_region_color_rgb,
         ).map_err(|e| format!("Failed to get MC reference point: {}", e))?;
         
         println!("EC reference point: {:?}", ec_reference_point);
         println!("MC reference point: {:?}", mc_reference_point);
         
-        // Trace ORIGINAL contours
-        let ec_contour_original = morphology::trace_contour(&marked_image, true, config.marked_region_color_rgb);
-        let mc_contour_original = morphology::trace_contour(&mc_image, false, config.marked_region_color_rgb);
+        // Extract ORIGINAL contours with the configured method
+        let ec_contour = LeafContour::extract(
+            &leaf_region, Some(&processed_image), alpha_thresholds.low, config.contour_method,
+        );
+        let mc_contour = LeafContour::extract(
+            &mc_region, Some(&processed_image), alpha_thresholds.low, config.contour_method,
+        );
+        let ec_contour_original = ec_contour.pixels.clone();
+        let mc_contour_original = mc_contour.pixels.clone();
         
         println!("Original EC contour points: {}", ec_contour_original.len());
         println!("Original MC contour points: {}", mc_contour_original.len());
         
-        // Calculate metrics from ORIGINAL images with area parameter
-        let ec_area = shape_analysis::calculate_area(&marked_image);
-        let ec_outline_count = ec_contour_original.len() as u32;
-        let ec_circularity = shape_analysis::calculate_circularity_from_contour(ec_area, &ec_contour_original);
+        // Calculate metrics from ORIGINAL regions with area parameter
+        let (ec_area, ec_circularity, ec_length, ec_width, ec_outline_count, ec_shape_index) = 
+            shape_analysis::analyze_shape_from_contour(&leaf_region, &ec_contour);
         
-        let mc_area = shape_analysis::calculate_area(&mc_image);
-        let mc_outline_count = mc_contour_original.len() as u32;
-        let mc_circularity = shape_analysis::calculate_circularity_from_contour(mc_area, &mc_contour_original);
+        let (mc_area, mc_circularity, mc_length, mc_width, mc_outline_count, mc_shape_index) = 
+            shape_analysis::analyze_shape_from_contour(&mc_region, &mc_contour);
+        let mc_shorter_dimension = shape_analysis::get_shorter_dimension(mc_length, mc_width);
         
         println!("EC metrics: Area={}, Outline={}, Circ={:.3}", ec_area, ec_outline_count, ec_circularity);
         println!("MC metrics: Area={}, Outline={}, Circ={:.3}", mc_area, mc_outline_count, mc_circularity);
@@ -143,21 +174,56 @@ impl AnalysisEngine {
         let initial_ec_features = feature_extraction::generate_features(
             ec_reference_point,
             &ec_contour_original,
-            &processed_image,
-            Some(&marked_image),
-            config.marked_region_color_rgb,
+            &mask,
             true,
         ).map_err(|e| format!("EC feature extraction failed: {}", e))?;
         
         let initial_mc_features = feature_extraction::generate_features(
             mc_reference_point,
             &mc_contour_original,
-            &mc_image,
-            None,
-            config.marked_region_color_rgb,
+            &mask,
             false,
         ).map_err(|e| format!("MC feature extraction failed: {}", e))?;
         
+        // Align the signal start (detected petiole base first, else the EC fallback; shared with MC)
+        let petiole_base = match config.signal_start {
+            SignalStart::Petiole | SignalStart::Apex => petiole.as_ref().map(|p| p.base)
+                .or_else(|| thornfiddle::detect_petiole_base(&initial_ec_features, &ec_contour_original, 1.0)),
+            SignalStart::Leftmost | SignalStart::MaxGeodesic => None,
+        };
+        let image_width = mask.dimensions().0;
+        
+        let ec_start = feature_extraction::signal_start_index(
+            &initial_ec_features, &ec_contour_original, config.signal_start, petiole_base, image_width);
+        let (initial_ec_features, ec_contour_original) = feature_extraction::rotate_features(
+            initial_ec_features, &ec_contour_original, ec_start);
+        
+        let mc_start = feature_extraction::signal_start_index(
+            &initial_mc_features, &mc_contour_original, config.signal_start, petiole_base, image_width);
+        let (initial_mc_features, mc_contour_original) = feature_extraction::rotate_features(
+            initial_mc_features, &mc_contour_original, mc_start);
+        
+        // Resample signals along arc length when configured
+        let (initial_ec_features, ec_contour_original, ec_circumference) =
+            match config.signal_sample_count_for(ec_contour.perimeter()) {
+                Some(samples) => {
+                    let (features, points) = feature_extraction::resample_features(
+                        &initial_ec_features, &ec_contour_original, samples);
+                    (features, points, samples as f64)
+                }
+                None => (initial_ec_features, ec_contour_original, ec_contour.perimeter()),
+            };
+        
+        let (initial_mc_features, mc_contour_original, mc_circumference) =
+            match config.signal_sample_count_for(mc_contour.perimeter()) {
+                Some(samples) => {
+                    let (features, points) = feature_extraction::resample_features(
+                        &initial_mc_features, &mc_contour_original, samples);
+                    (features, points, samples as f64)
+                }
+                None => (initial_mc_features, mc_contour_original, mc_contour.perimeter()),
+            };
+        
         println!("Initial EC features: {}", initial_ec_features.len());
         println!("Initial MC features: {}", initial_mc_features.len());
         
@@ -204,20 +270,9 @@ impl AnalysisEngine {
                      mc_contour_filtered.len(), mc_features.len());
         }
         
-        let (ec_length, ec_width, ec_shape_index) = shape_analysis::calculate_length_width_shape_index(
-            &marked_image,
-            config.marked_region_color_rgb,
-        );
-        
         println!("EC Shape: Length={:.1}, Width={:.1}, Index={:.3}, Circ={:.3}", 
                  ec_length, ec_width, ec_shape_index, ec_circularity);
         
-        let (mc_length, mc_width, mc_shape_index, mc_shorter_dimension) = 
-            shape_analysis::calculate_length_width_shape_index_with_shorter(
-                &mc_image,
-                config.marked_region_color_rgb,
-            );
-        
         println!("MC Shape: Length={:.1}, Width={:.1}, Index={:.3}, Circ={:.3}", 
                  mc_length, mc_width, mc_shape_index, mc_circularity);
         
@@ -235,38 +290,29 @@ impl AnalysisEngine {
         println!("Dynamic thornfiddle: MC Shape Index {:.3} -> {:.1}% -> {} px kernel", 
                  mc_shape_index, dynamic_opening_percentage, dynamic_kernel_size);
         
-        // Create thornfiddle image
-        let thornfiddle_image = morphology::create_thornfiddle_image(
-            &mc_image,
-            dynamic_kernel_size,
-            config.thornfiddle_marked_color_rgb,
-        ).map_err(|e| format!("Failed to create thornfiddle image: {}", e))?;
-        
-        let ec_circumference = thornfiddle::calculate_leaf_circumference(&ec_contour_original);
+        // Mark thornfiddle lobes
+        morphology::mark_lobe_regions(&mut mask, dynamic_kernel_size)
+            .map_err(|e| format!("Failed to mark thornfiddle lobes: {}", e))?;
         
         // Calculate harmonic results
         let ec_harmonic_result = thornfiddle::calculate_thornfiddle_path_harmonic(
             &ec_features,
             ec_circumference,
-            &thornfiddle_image,
+            &mask,
             ec_reference_point,
             &ec_contour_original,
-            config.thornfiddle_marked_color_rgb,
             config.thornfiddle_pixel_threshold,
             config.harmonic_min_chain_length,
             config.harmonic_strength_multiplier,
             config.harmonic_max_harmonics,
         );
         
-        let mc_circumference = thornfiddle::calculate_leaf_circumference(&mc_contour_original);
-        
         let mc_harmonic_result = thornfiddle::calculate_thornfiddle_path_harmonic(
             &mc_features,
             mc_circumference,
-            &thornfiddle_image,
+            &mask,
             mc_reference_point,
             &mc_contour_original,
-            config.thornfiddle_marked_color_rgb,
             config.thornfiddle_pixel_threshold,
             config.harmonic_min_chain_length,
             config.harmonic_strength_multiplier,
@@ -302,13 +348,16 @@ impl AnalysisEngine {
             config.spectral_entropy_sigmoid_c,
         ).0;  // FIXED: Take only the first element (entropy value)
         
-        let ec_approximate_entropy = thornfiddle::calculate_approximate_entropy_from_pink_path(
-            &ec_features_final,
+        let ec_entropy = calculate_entropy_metrics(
+            &thornfiddle::extract_pink_path_signal(&ec_features_final),
             config.approximate_entropy_m,
             config.approximate_entropy_r,
-        );
+            config.permutation_entropy_order,
+            config.permutation_entropy_delay,
+            config.multiscale_entropy_scales,
+        ).value(config.ec_entropy_method);
         
-        println!("EC Approximate Entropy: {:.6}", ec_approximate_entropy);
+        println!("EC Entropy ({}): {:.6}", config.ec_entropy_method.name(), ec_entropy);
         println!("MC Spectral Entropy: {:.6}", mc_spectral_entropy);
         
         let ec_data: Vec<(f64, f64)> = ec_features_final.iter()
@@ -323,8 +372,8 @@ impl AnalysisEngine {
         
         println!("Graph data - EC: {} points (diego_path_pink), MC: {} points", ec_data.len(), mc_data.len());
         
-        let ec_overlay = create_transparent_overlay(&marked_image, &[255, 0, 255]);
-        let mc_overlay = create_transparent_overlay(&thornfiddle_image, &[255, 215, 0]);
+        let ec_overlay = output::render_layer_overlay(&mask, EC_OPENED | MC_REMOVED, [255, 0, 255]);
+        let mc_overlay = output::render_layer_overlay(&mask, LOBE, [255, 215, 0]);
         
         let original_texture = load_texture_from_image(ctx, &processed_image, format!("{}_original", filename));
         let ec_texture = load_texture_from_image(ctx, &ec_overlay, format!("{}_ec", filename));
@@ -335,7 +384,7 @@ impl AnalysisEngine {
             ec_width,
             ec_shape_index,
             ec_circularity,
-            ec_spectral_entropy: ec_approximate_entropy,
+            ec_spectral_entropy: ec_entropy,
             ec_area,
             ec_outline_count,
             mc_length,
@@ -374,49 +423,38 @@ impl AnalysisEngine {
 }
 
 /// Remove thin artifacts from marked regions (single-pixel-wide lines)
-/// Filters out any pink regions where pixels have <= 2 pink neighbors
-fn clean_thin_artifacts(marked_image: &RgbaImage, marked_color: [u8; 3]) -> RgbaImage {
-    let (width, height) = marked_image.dimensions();
-    let mut cleaned = marked_image.clone();
+/// Unmarks any opened pixels that have <= 2 opened neighbors
+fn clean_thin_artifacts(mask: &mut LeafMask) {
+    let (width, height) = mask.dimensions();
     
-    // Helper to check if pixel is pink
-    let is_pink = |x: u32, y: u32| -> bool {
-        if x >= width || y >= height {
-            return false;
-        }
-        let pixel = marked_image.get_pixel(x, y);
-        pixel[0] == marked_color[0] && 
-        pixel[1] == marked_color[1] && 
-        pixel[2] == marked_color[2] &&
-        pixel[3] > 0
+    // Helper to check if pixel is marked by the opening
+    let is_pink = |mask: &LeafMask, x: i32, y: i32| -> bool {
+        x >= 0 && y >= 0 && (x as u32) < width && (y as u32) < height
+            && mask.has(x as u32, y as u32, EC_OPENED)
     };
     
     // Count pink neighbors in 8-connectivity
-    let count_pink_neighbors = |x: u32, y: u32| -> usize {
+    let count_pink_neighbors = |mask: &LeafMask, x: u32, y: u32| -> usize {
         let mut count = 0;
         for dy in -1i32..=1 {
             for dx in -1i32..=1 {
                 if dx == 0 && dy == 0 {
                     continue;
                 }
-                let nx = x as i32 + dx;
-                let ny = y as i32 + dy;
-                if nx >= 0 && ny >= 0 {
-                    if is_pink(nx as u32, ny as u32) {
-                        count += 1;
-                    }
+                if is_pink(mask, x as i32 + dx, y as i32 + dy) {
+                    count += 1;
                 }
             }
         }
         count
     };
     
-    // First pass: mark pixels to remove
+    // First pass: mark pixels to unmark
     let mut to_remove = vec![];
     for y in 0..height {
         for x in 0..width {
-            if is_pink(x, y) {
-                let neighbor_count = count_pink_neighbors(x, y);
+            if mask.has(x, y, EC_OPENED) {
+                let neighbor_count = count_pink_neighbors(mask, x, y);
                 // Remove if 2 or fewer neighbors (thin line or isolated pixel)
                 if neighbor_count <= 2 {
                     to_remove.push((x, y));
@@ -425,30 +463,24 @@ fn clean_thin_artifacts(marked_image: &RgbaImage, marked_color: [u8; 3]) -> Rgba
         }
     }
     
-    // Second pass: remove marked pixels
+    // Second pass: unmark pixels
     for (x, y) in to_remove.iter() {
-        cleaned.put_pixel(*x, *y, Rgba([0, 0, 0, 0]));
+        mask.clear(*x, *y, EC_OPENED);
     }
     
     println!("Cleaned {} thin artifact pixels from pink regions", to_remove.len());
-    cleaned
 }
 
 fn calculate_adaptive_opening_kernel_size(
-    image: &RgbaImage,
+    mask: &LeafMask,
     max_density: f64,
     max_percentage: f64,
     min_percentage: f64,
 ) -> u32 {
-    let (width, height) = image.dimensions();
+    let (width, height) = mask.dimensions();
     let total_pixels = (width * height) as f64;
     
-    let mut non_transparent_count = 0;
-    for pixel in image.pixels() {
-        if pixel[3] > 0 {
-            non_transparent_count += 1;
-        }
-    }
+    let non_transparent_count = mask.count(LEAF);
     
     let non_transparent_percentage = (non_transparent_count as f64 / total_pixels) * 100.0;
     
@@ -469,48 +501,6 @@ fn calculate_adaptive_opening_kernel_size(
     adaptive_kernel_size
 }
 
-fn mark_opened_regions(
-    original: &RgbaImage,
-    opened: &RgbaImage,
-    marked_color: [u8; 3],
-) -> RgbaImage {
-    let (width, height) = original.dimensions();
-    let mut result = original.clone();
-    
-    for y in 0..height {
-        for x in 0..width {
-            let orig_pixel = original.get_pixel(x, y);
-            let opened_pixel = opened.get_pixel(x, y);
-            
-            if orig_pixel[3] > 0 && opened_pixel[3] == 0 {
-                result.put_pixel(x, y, Rgba([marked_color[0], marked_color[1], marked_color[2], 255]));
-            }
-        }
-    }
-    
-    result
-}
-
-fn create_transparent_overlay(image: &RgbaImage, color_to_keep: &[u8; 3]) -> RgbaImage {
-    let (width, height) = image.dimensions();
-    let mut overlay = RgbaImage::new(width, height);
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            
-            if pixel[3] > 0 && pixel[0] == color_to_keep[0] && 
-               pixel[1] == color_to_keep[1] && pixel[2] == color_to_keep[2] {
-                overlay.put_pixel(x, y, *pixel);
-            } else {
-                overlay.put_pixel(x, y, Rgba([0, 0, 0, 0]));
-            }
-        }
-    }
-    
-    overlay
-}
-
 fn load_texture_from_image(ctx: &egui::Context, image: &RgbaImage, name: String) -> egui::TextureHandle {
     let (width, height) = image.dimensions();
     let image_data: Vec<u8> = image.as_raw().clone();
@@ -6,6 +6,7 @@ use std::io::{self, ErrorKind};
 use std::path::{Path, PathBuf};
 
 use crate::errors::{LeafComplexError, Result};
+use crate::image_utils::AlphaPolicy;
 
 /// Main configuration structure for LeafComplexR
 ///
@@ -21,10 +22,59 @@ pub struct Config {
     /// Optional resize dimensions [width, height] for batch processing
     pub resize_dimensions: Option<[u32; 2]>,
     
+    // Foreground (Alpha) Policy
+    /// How leaf pixels are separated from the background: "FIXED", "OTSU" or "HYSTERESIS"
+    #[serde(default = "default_alpha_policy")]
+    pub alpha_policy: AlphaPolicyChoice,
+    
+    /// Alpha threshold for the FIXED policy (alpha >= threshold is leaf)
+    #[serde(default = "default_alpha_threshold")]
+    pub alpha_threshold: u8,
+    
+    /// Lower alpha threshold for the HYSTERESIS policy (weak leaf pixels)
+    #[serde(default = "default_alpha_hysteresis_low")]
+    pub alpha_hysteresis_low: u8,
+    
+    /// Upper alpha threshold for the HYSTERESIS policy (strong leaf pixels)
+    #[serde(default = "default_alpha_hysteresis_high")]
+    pub alpha_hysteresis_high: u8,
+    
+    /// Compute EC/MC on the hole-filled leaf so internal damage does not affect margin metrics
+    #[serde(default = "default_fill_holes")]
+    pub fill_holes: bool,
+    
+    /// Boundary extraction for shape metrics and per-point signals
+    #[serde(default = "default_contour_method")]
+    pub contour_method: ContourMethod,
+    
+    /// Arc-length resampling of per-point signals (NONE keeps one sample per contour point)
+    #[serde(default = "default_signal_resampling")]
+    pub signal_resampling: SignalResampling,
+    
+    /// Number of samples per contour for COUNT resampling
+    #[serde(default = "default_signal_sample_count")]
+    pub signal_sample_count: usize,
+    
+    /// Sample spacing in millimetres for SPACING resampling
+    #[serde(default = "default_signal_sample_spacing_mm")]
+    pub signal_sample_spacing_mm: f64,
+    
+    /// Image scale of the analysed (resized) image, used to convert millimetres to pixels
+    #[serde(default = "default_pixels_per_mm")]
+    pub pixels_per_mm: f64,
+    
+    /// Contour point that index 0 of the per-point signals is aligned to
+    #[serde(default = "default_signal_start")]
+    pub signal_start: SignalStart,
+    
+    /// Rotate the leaf so its base is at the bottom centre and the apex points up
+    #[serde(default = "default_orientation_method")]
+    pub orientation_method: OrientationMethod,
+    
     /// Kernel size for morphological opening (EC region marking)
     pub opening_kernel_size: u32,
     
-    /// RGB color for marking opened regions (default: bright pink)
+    /// RGB color for rendering opened regions in debug output (default: bright pink)
     pub marked_region_color_rgb: [u8; 3],
     
     /// Reference point choice: "EP" (Emerge Point) or "COM" (Center of Mass)
@@ -60,6 +110,23 @@ pub struct Config {
     #[serde(default = "default_petiole_remove_completely")]
     pub petiole_remove_completely: bool,
     
+    // Petiole Detection Parameters (mask level)
+    /// Detect the petiole as a thin, elongated protrusion of the leaf mask
+    #[serde(default = "default_enable_petiole_detection")]
+    pub enable_petiole_detection: bool,
+    
+    /// Widest petiole relative to the largest inscribed diameter of the leaf
+    #[serde(default = "default_petiole_max_width_ratio")]
+    pub petiole_max_width_ratio: f64,
+    
+    /// Minimum length / width of the petiole
+    #[serde(default = "default_petiole_min_elongation")]
+    pub petiole_min_elongation: f64,
+    
+    /// Cut the detected petiole at the blade base so EC and MC analyse the blade only
+    #[serde(default = "default_remove_petiole")]
+    pub remove_petiole: bool,
+    
     // Pink Threshold Filtering Parameters
     /// Enable threshold filtering for Geodesic_EC values
     #[serde(default = "default_enable_pink_threshold_filter")]
@@ -83,6 +150,22 @@ pub struct Config {
     #[serde(default = "default_approximate_entropy_r")]
     pub approximate_entropy_r: f64,
     
+    /// Entropy estimator reported as EC (the others become extra summary columns)
+    #[serde(default = "default_ec_entropy_method")]
+    pub ec_entropy_method: EntropyMethod,
+    
+    /// Permutation entropy order (pattern length)
+    #[serde(default = "default_permutation_entropy_order")]
+    pub permutation_entropy_order: usize,
+    
+    /// Permutation entropy delay (spacing between pattern samples)
+    #[serde(default = "default_permutation_entropy_delay")]
+    pub permutation_entropy_delay: usize,
+    
+    /// Largest coarse-graining scale of the refined composite multiscale entropy
+    #[serde(default = "default_multiscale_entropy_scales")]
+    pub multiscale_entropy_scales: usize,
+    
     /// Scaling factor for edge complexity calculation
     #[serde(default = "default_ec_scaling_factor")]
     pub ec_scaling_factor: f64,
@@ -100,7 +183,7 @@ pub struct Config {
     #[serde(default = "default_thornfiddle_pixel_threshold")]
     pub thornfiddle_pixel_threshold: u32,
     
-    /// RGB color for marking golden lobe regions
+    /// RGB color for rendering golden lobe regions in debug output
     #[serde(default = "default_thornfiddle_marked_color_rgb")]
     pub thornfiddle_marked_color_rgb: [u8; 3],
     
@@ -113,7 +196,7 @@ pub struct Config {
     #[serde(default = "default_harmonic_strength_multiplier")]
     pub harmonic_strength_multiplier: f64,
     
-    /// Minimum chain length (in contour points) to count as valid harmonic chain
+    /// Minimum chain length (in contour points, or samples when signals are resampled) to count as valid harmonic chain
     #[serde(default = "default_harmonic_min_chain_length")]
     pub harmonic_min_chain_length: usize,
     
@@ -125,6 +208,215 @@ pub struct Config {
     /// Center point of sigmoid transition (coefficient of variation threshold)
     #[serde(default = "default_spectral_entropy_sigmoid_c")]
     pub spectral_entropy_sigmoid_c: f64,
+    
+    // Geodesic Shape Descriptors
+    /// Number of margin points sampled for geodesic diameter, tortuosity and eccentricity
+    #[serde(default = "default_geodesic_sample_count")]
+    pub geodesic_sample_count: usize,
+    
+    // Inner-Distance Shape Context Parameters
+    /// Compute and export the inner-distance shape context (IDSC) descriptor
+    #[serde(default = "default_enable_inner_distance_shape_context")]
+    pub enable_inner_distance_shape_context: bool,
+    
+    /// Number of contour points sampled for the IDSC descriptor
+    #[serde(default = "default_idsc_sample_count")]
+    pub idsc_sample_count: usize,
+    
+    /// Number of log inner-distance bins in each IDSC histogram
+    #[serde(default = "default_idsc_distance_bins")]
+    pub idsc_distance_bins: usize,
+    
+    /// Number of inner-angle bins in each IDSC histogram
+    #[serde(default = "default_idsc_angle_bins")]
+    pub idsc_angle_bins: usize,
+    
+    // Granulometry Parameters
+    /// Compute and export the pattern spectrum over a range of opening diameters
+    #[serde(default = "default_enable_granulometry")]
+    pub enable_granulometry: bool,
+    
+    /// Smallest opening diameter as a percentage of the leaf's shorter dimension
+    #[serde(default = "default_granulometry_min_percentage")]
+    pub granulometry_min_percentage: f64,
+    
+    /// Largest opening diameter as a percentage of the leaf's shorter dimension
+    #[serde(default = "default_granulometry_max_percentage")]
+    pub granulometry_max_percentage: f64,
+    
+    /// Number of opening diameters between the minimum and maximum
+    #[serde(default = "default_granulometry_scales")]
+    pub granulometry_scales: usize,
+    
+    // Fractal Dimension Parameters
+    /// Compute box-counting and Minkowski-Bouligand dimensions of the EC and MC contours
+    #[serde(default = "default_enable_fractal_dimension")]
+    pub enable_fractal_dimension: bool,
+    
+    /// Largest box size / dilation radius as a percentage of the region's shorter dimension
+    #[serde(default = "default_fractal_max_percentage")]
+    pub fractal_max_percentage: f64,
+    
+    /// Number of log-spaced scales from 1 pixel to the largest scale
+    #[serde(default = "default_fractal_scales")]
+    pub fractal_scales: usize,
+    
+    /// Export the log-log data of both estimators per image
+    #[serde(default = "default_export_fractal_data")]
+    pub export_fractal_data: bool,
+    
+    // Elliptic Fourier Analysis Parameters
+    /// Compute elliptic Fourier descriptors of the EC and MC outlines and export them
+    #[serde(default = "default_enable_efa")]
+    pub enable_efa: bool,
+    
+    /// Number of harmonics
+    #[serde(default = "default_efa_harmonics")]
+    pub efa_harmonics: usize,
+    
+    /// Normalise the descriptors for size, rotation and start point
+    #[serde(default = "default_efa_normalize")]
+    pub efa_normalize: bool,
+    
+    /// Resample the outlines to this many points along arc length (0 = traced contour)
+    #[serde(default = "default_efa_sample_count")]
+    pub efa_sample_count: usize,
+    
+    // Skeleton Parameters
+    /// Extract the pruned medial axis of the MC leaf and export it as an overlay image
+    #[serde(default = "default_enable_skeleton")]
+    pub enable_skeleton: bool,
+    
+    /// Minimum protrusion of a kept skeleton branch beyond the inscribed disc at its junction,
+    /// relative to that disc's radius
+    #[serde(default = "default_skeleton_prune_ratio")]
+    pub skeleton_prune_ratio: f64,
+    
+    /// RGB color of skeleton pixels in the overlay image
+    #[serde(default = "default_skeleton_color_rgb")]
+    pub skeleton_color_rgb: [u8; 3],
+    
+    // Convex Hull Parameters
+    /// Minimum convexity defect depth as a percentage of the region's shorter dimension
+    #[serde(default = "default_convexity_defect_min_depth_percentage")]
+    pub convexity_defect_min_depth_percentage: f64,
+    
+    // Leaf Architecture Parameters
+    /// Fraction of the base-apex length at which apex and base angles are measured
+    #[serde(default = "default_leaf_angle_fraction")]
+    pub leaf_angle_fraction: f64,
+    
+    // Teeth Parameters
+    /// Smallest EC opening residue counted as a tooth, in pixels
+    #[serde(default = "default_tooth_min_area")]
+    pub tooth_min_area: usize,
+    
+    // Lobe Parameters
+    /// Smallest golden region counted as a lobe, as a percentage of the MC area
+    #[serde(default = "default_lobe_min_area_percentage")]
+    pub lobe_min_area_percentage: f64,
+}
+
+/// Foreground decision method on the alpha channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum AlphaPolicyChoice {
+    /// Single fixed threshold
+    Fixed,
+    /// Per-image Otsu threshold on the alpha histogram
+    Otsu,
+    /// Low/high threshold pair with connectivity
+    Hysteresis,
+}
+
+/// Leaf boundary extraction method
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ContourMethod {
+    /// Moore-neighbour tracing of boundary pixel centres
+    Moore,
+    /// Sub-pixel marching squares on the alpha channel
+    #[serde(rename = "MARCHING_SQUARES")]
+    MarchingSquares,
+}
+
+/// Arc-length normalisation of per-point signals
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SignalResampling {
+    /// One sample per contour point (resolution dependent)
+    None,
+    /// Fixed number of samples per contour
+    Count,
+    /// Fixed spacing in millimetres along the contour
+    Spacing,
+}
+
+/// Alignment of the first point of per-point signals
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum SignalStart {
+    /// Leftmost contour pixel (depends on how the leaf was photographed)
+    Leftmost,
+    /// Petiole base detected in the EC pink signal
+    Petiole,
+    /// Contour point farthest from the petiole base
+    Apex,
+    /// Contour point with the longest geodesic path to the reference point
+    #[serde(rename = "MAX_GEODESIC")]
+    MaxGeodesic,
+}
+
+/// Entropy estimator for the EC signal
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum EntropyMethod {
+    /// Approximate entropy (counts self-matches, biased on short signals)
+    Approximate,
+    /// Sample entropy (no self-matches)
+    Sample,
+    /// Normalised permutation entropy of ordinal patterns
+    Permutation,
+    /// Mean refined composite multiscale entropy
+    Multiscale,
+}
+
+impl EntropyMethod {
+    /// Name reported in output metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntropyMethod::Approximate => "APPROXIMATE",
+            EntropyMethod::Sample => "SAMPLE",
+            EntropyMethod::Permutation => "PERMUTATION",
+            EntropyMethod::Multiscale => "MULTISCALE",
+        }
+    }
+}
+
+/// Detection of the base-apex axis for orientation normalisation
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum OrientationMethod {
+    /// Analyse the leaf as imaged
+    None,
+    /// Maximum Feret diameter
+    Feret,
+    /// Principal axis of inertia
+    Moments,
+    /// Petiole base to the farthest leaf point (mask-level petiole detector)
+    Petiole,
+}
+
+impl OrientationMethod {
+    /// Name reported in output metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            OrientationMethod::None => "NONE",
+            OrientationMethod::Feret => "FERET",
+            OrientationMethod::Moments => "MOMENTS",
+            OrientationMethod::Petiole => "PETIOLE",
+        }
+    }
 }
 
 /// Reference point calculation method
@@ -145,11 +437,19 @@ fn default_adaptive_opening_min_percentage() -> f64 { 1.0 }
 fn default_enable_petiole_filter_ec() -> bool { true }
 fn default_enable_petiole_filter_ec_complexity() -> bool { true }
 fn default_petiole_remove_completely() -> bool { true }
+fn default_enable_petiole_detection() -> bool { false }
+fn default_petiole_max_width_ratio() -> f64 { 0.25 }
+fn default_petiole_min_elongation() -> f64 { 3.0 }
+fn default_remove_petiole() -> bool { false }
 fn default_enable_pink_threshold_filter() -> bool { true }
 fn default_pink_threshold_value() -> f64 { 3.0 }
 fn default_thornfiddle_smoothing_strength() -> f64 { 2.0 }
 fn default_approximate_entropy_m() -> usize { 2 }
 fn default_approximate_entropy_r() -> f64 { 0.2 }
+fn default_ec_entropy_method() -> EntropyMethod { EntropyMethod::Approximate }
+fn default_permutation_entropy_order() -> usize { 3 }
+fn default_permutation_entropy_delay() -> usize { 1 }
+fn default_multiscale_entropy_scales() -> usize { 10 }
 fn default_ec_scaling_factor() -> f64 { 3.0 }
 fn default_thornfiddle_max_opening_percentage() -> f64 { 30.0 }
 fn default_thornfiddle_min_opening_percentage() -> f64 { 5.0 }
@@ -160,6 +460,42 @@ fn default_harmonic_strength_multiplier() -> f64 { 2.0 }
 fn default_harmonic_min_chain_length() -> usize { 15 }
 fn default_spectral_entropy_sigmoid_k() -> f64 { 20.0 }
 fn default_spectral_entropy_sigmoid_c() -> f64 { 0.04 }
+fn default_alpha_policy() -> AlphaPolicyChoice { AlphaPolicyChoice::Fixed }
+fn default_alpha_threshold() -> u8 { 128 }
+fn default_alpha_hysteresis_low() -> u8 { 64 }
+fn default_alpha_hysteresis_high() -> u8 { 192 }
+fn default_fill_holes() -> bool { false }
+fn default_contour_method() -> ContourMethod { ContourMethod::Moore }
+fn default_signal_resampling() -> SignalResampling { SignalResampling::None }
+fn default_signal_sample_count() -> usize { 512 }
+fn default_signal_sample_spacing_mm() -> f64 { 0.5 }
+fn default_pixels_per_mm() -> f64 { 10.0 }
+fn default_signal_start() -> SignalStart { SignalStart::Leftmost }
+fn default_orientation_method() -> OrientationMethod { OrientationMethod::None }
+fn default_geodesic_sample_count() -> usize { 64 }
+fn default_enable_inner_distance_shape_context() -> bool { false }
+fn default_idsc_sample_count() -> usize { 100 }
+fn default_idsc_distance_bins() -> usize { 5 }
+fn default_idsc_angle_bins() -> usize { 12 }
+fn default_enable_granulometry() -> bool { false }
+fn default_granulometry_min_percentage() -> f64 { 1.0 }
+fn default_granulometry_max_percentage() -> f64 { 50.0 }
+fn default_granulometry_scales() -> usize { 16 }
+fn default_enable_fractal_dimension() -> bool { false }
+fn default_fractal_max_percentage() -> f64 { 25.0 }
+fn default_fractal_scales() -> usize { 12 }
+fn default_export_fractal_data() -> bool { false }
+fn default_enable_efa() -> bool { false }
+fn default_efa_harmonics() -> usize { 20 }
+fn default_efa_normalize() -> bool { true }
+fn default_efa_sample_count() -> usize { 0 }
+fn default_enable_skeleton() -> bool { false }
+fn default_skeleton_prune_ratio() -> f64 { 0.25 }
+fn default_skeleton_color_rgb() -> [u8; 3] { [0, 255, 255] }
+fn default_convexity_defect_min_depth_percentage() -> f64 { 1.0 }
+fn default_leaf_angle_fraction() -> f64 { 0.25 }
+fn default_tooth_min_area() -> usize { 5 }
+fn default_lobe_min_area_percentage() -> f64 { 0.5 }
 
 impl Config {
     /// Load configuration from a TOML file
@@ -191,6 +527,18 @@ impl Config {
             input_path: "./input".to_string(),
             output_base_dir: "./output".to_string(),
             resize_dimensions: Some([512, 512]),
+            alpha_policy: AlphaPolicyChoice::Fixed,
+            alpha_threshold: 128,
+            alpha_hysteresis_low: 64,
+            alpha_hysteresis_high: 192,
+            fill_holes: false,
+            contour_method: ContourMethod::Moore,
+            signal_resampling: SignalResampling::None,
+            signal_sample_count: 512,
+            signal_sample_spacing_mm: 0.5,
+            pixels_per_mm: 10.0,
+            signal_start: SignalStart::Leftmost,
+            orientation_method: OrientationMethod::None,
             opening_kernel_size: 9,
             marked_region_color_rgb: [255, 0, 255],
             reference_point_choice: ReferencePointChoice::Com,
@@ -201,11 +549,19 @@ impl Config {
             enable_petiole_filter_ec: true,
             enable_petiole_filter_ec_complexity: true,
             petiole_remove_completely: true,
+            enable_petiole_detection: false,
+            petiole_max_width_ratio: 0.25,
+            petiole_min_elongation: 3.0,
+            remove_petiole: false,
             enable_pink_threshold_filter: true,
             pink_threshold_value: 3.0,
             thornfiddle_smoothing_strength: 2.0,
             approximate_entropy_m: 2,
             approximate_entropy_r: 0.2,
+            ec_entropy_method: EntropyMethod::Approximate,
+            permutation_entropy_order: 3,
+            permutation_entropy_delay: 1,
+            multiscale_entropy_scales: 10,
             ec_scaling_factor: 3.0,
             thornfiddle_max_opening_percentage: 30.0,
             thornfiddle_min_opening_percentage: 5.0,
@@ -216,6 +572,30 @@ impl Config {
             harmonic_min_chain_length: 15,
             spectral_entropy_sigmoid_k: 20.0,
             spectral_entropy_sigmoid_c: 0.04,
+            geodesic_sample_count: 64,
+            enable_inner_distance_shape_context: false,
+            idsc_sample_count: 100,
+            idsc_distance_bins: 5,
+            idsc_angle_bins: 12,
+            enable_granulometry: false,
+            granulometry_min_percentage: 1.0,
+            granulometry_max_percentage: 50.0,
+            granulometry_scales: 16,
+            enable_fractal_dimension: false,
+            fractal_max_percentage: 25.0,
+            fractal_scales: 12,
+            export_fractal_data: false,
+            enable_efa: false,
+            efa_harmonics: 20,
+            efa_normalize: true,
+            efa_sample_count: 0,
+            enable_skeleton: false,
+            skeleton_prune_ratio: 0.25,
+            skeleton_color_rgb: [0, 255, 255],
+            convexity_defect_min_depth_percentage: 1.0,
+            leaf_angle_fraction: 0.25,
+            tooth_min_area: 5,
+            lobe_min_area_percentage: 0.5,
         }
     }
 
@@ -238,6 +618,19 @@ impl Config {
                 "opening_kernel_size must be > 0".to_string(),
             ));
         }
+        
+        // Validate alpha policy parameters
+        if self.alpha_threshold == 0 {
+            return Err(LeafComplexError::Config(
+                "alpha_threshold must be > 0".to_string(),
+            ));
+        }
+        
+        if self.alpha_hysteresis_low == 0 || self.alpha_hysteresis_low > self.alpha_hysteresis_high {
+            return Err(LeafComplexError::Config(
+                "alpha_hysteresis_low must be > 0 and <= alpha_hysteresis_high".to_string(),
+            ));
+        }
 
         // Validate adaptive opening parameters
         if !(0.0..=100.0).contains(&self.adaptive_opening_max_density) {
@@ -272,6 +665,24 @@ impl Config {
             ));
         }
         
+        if self.permutation_entropy_order < 2 || self.permutation_entropy_order > 8 {
+            return Err(LeafComplexError::Config(
+                "permutation_entropy_order must be between 2 and 8".to_string(),
+            ));
+        }
+        
+        if self.permutation_entropy_delay < 1 {
+            return Err(LeafComplexError::Config(
+                "permutation_entropy_delay must be >= 1".to_string(),
+            ));
+        }
+        
+        if self.multiscale_entropy_scales < 1 {
+            return Err(LeafComplexError::Config(
+                "multiscale_entropy_scales must be >= 1".to_string(),
+            ));
+        }
+        
         // Validate thornfiddle parameters
         if !(0.0..=50.0).contains(&self.thornfiddle_max_opening_percentage) {
             return Err(LeafComplexError::Config(
@@ -311,6 +722,25 @@ impl Config {
             ));
         }
         
+        // Validate signal resampling parameters
+        if self.signal_sample_count < 8 {
+            return Err(LeafComplexError::Config(
+                "signal_sample_count must be >= 8".to_string(),
+            ));
+        }
+        
+        if self.signal_sample_spacing_mm <= 0.0 {
+            return Err(LeafComplexError::Config(
+                "signal_sample_spacing_mm must be > 0.0".to_string(),
+            ));
+        }
+        
+        if self.pixels_per_mm <= 0.0 {
+            return Err(LeafComplexError::Config(
+                "pixels_per_mm must be > 0.0".to_string(),
+            ));
+        }
+        
         // Validate spectral entropy sigmoid parameters
         if self.spectral_entropy_sigmoid_k <= 0.0 {
             return Err(LeafComplexError::Config(
@@ -323,6 +753,107 @@ impl Config {
                 "spectral_entropy_sigmoid_c must be > 0.0".to_string(),
             ));
         }
+        
+        // Validate geodesic parameters
+        if self.geodesic_sample_count < 2 {
+            return Err(LeafComplexError::Config(
+                "geodesic_sample_count must be >= 2".to_string(),
+            ));
+        }
+        
+        // Validate inner-distance shape context parameters
+        if self.idsc_sample_count < 3 {
+            return Err(LeafComplexError::Config(
+                "idsc_sample_count must be >= 3".to_string(),
+            ));
+        }
+        
+        if self.idsc_distance_bins == 0 || self.idsc_angle_bins == 0 {
+            return Err(LeafComplexError::Config(
+                "idsc_distance_bins and idsc_angle_bins must be > 0".to_string(),
+            ));
+        }
+        
+        // Validate granulometry parameters
+        if self.granulometry_min_percentage <= 0.0 
+            || self.granulometry_min_percentage >= self.granulometry_max_percentage {
+            return Err(LeafComplexError::Config(
+                "granulometry_min_percentage must be > 0.0 and < granulometry_max_percentage".to_string(),
+            ));
+        }
+        
+        if self.granulometry_scales < 2 {
+            return Err(LeafComplexError::Config(
+                "granulometry_scales must be >= 2".to_string(),
+            ));
+        }
+        
+        // Validate skeleton parameters
+        if self.skeleton_prune_ratio < 0.0 {
+            return Err(LeafComplexError::Config(
+                "skeleton_prune_ratio must be >= 0.0".to_string(),
+            ));
+        }
+        
+        // Validate fractal dimension parameters
+        if self.fractal_max_percentage <= 0.0 || self.fractal_max_percentage > 100.0 {
+            return Err(LeafComplexError::Config(
+                "fractal_max_percentage must be > 0.0 and <= 100.0".to_string(),
+            ));
+        }
+        
+        if self.fractal_scales < 2 {
+            return Err(LeafComplexError::Config(
+                "fractal_scales must be >= 2".to_string(),
+            ));
+        }
+        
+        // Validate elliptic Fourier parameters
+        if self.efa_harmonics == 0 {
+            return Err(LeafComplexError::Config(
+                "efa_harmonics must be > 0".to_string(),
+            ));
+        }
+        
+        if self.efa_sample_count != 0 && self.efa_sample_count < 2 * self.efa_harmonics {
+            return Err(LeafComplexError::Config(
+                "efa_sample_count must be 0 or >= 2 * efa_harmonics".to_string(),
+            ));
+        }
+        
+        // Validate petiole detection parameters
+        if self.petiole_max_width_ratio <= 0.0 || self.petiole_max_width_ratio > 1.0 {
+            return Err(LeafComplexError::Config(
+                "petiole_max_width_ratio must be > 0.0 and <= 1.0".to_string(),
+            ));
+        }
+        
+        if self.petiole_min_elongation <= 0.0 {
+            return Err(LeafComplexError::Config(
+                "petiole_min_elongation must be > 0.0".to_string(),
+            ));
+        }
+        
+        // Validate convex hull parameters
+        if self.convexity_defect_min_depth_percentage < 0.0 {
+            return Err(LeafComplexError::Config(
+                "convexity_defect_min_depth_percentage must be >= 0.0".to_string(),
+            ));
+        }
+        
+        // Validate leaf architecture parameters
+        if self.leaf_angle_fraction <= 0.0 || self.leaf_angle_fraction >= 0.5 {
+            return Err(LeafComplexError::Config(
+                "leaf_angle_fraction must be > 0.0 and < 0.5".to_string(),
+            ));
+        }
+        
+        // Validate lobe parameters
+        if self.lobe_min_area_percentage < 0.0 || self.lobe_min_area_percentage >= 100.0 {
+            return Err(LeafComplexError::Config(
+                "lobe_min_area_percentage must be >= 0.0 and < 100.0".to_string(),
+            ));
+        }
 
         // Create output directories
         let base_dir = PathBuf::from(&self.output_base_dir);
@@ -346,6 +877,33 @@ impl Config {
         Ok(())
     }
 
+    /// Foreground policy used by every stage of the pipeline
+    pub fn alpha_policy(&self) -> AlphaPolicy {
+        match self.alpha_policy {
+            AlphaPolicyChoice::Fixed => AlphaPolicy::Fixed(self.alpha_threshold),
+            AlphaPolicyChoice::Otsu => AlphaPolicy::Otsu,
+            AlphaPolicyChoice::Hysteresis => AlphaPolicy::Hysteresis {
+                low: self.alpha_hysteresis_low,
+                high: self.alpha_hysteresis_high,
+            },
+        }
+    }
+    
+    /// Number of arc-length samples for a contour, or None when signals are not resampled
+    ///
+    /// # Arguments
+    /// * `perimeter` - Contour length in pixels
+    pub fn signal_sample_count_for(&self, perimeter: f64) -> Option<usize> {
+        match self.signal_resampling {
+            SignalResampling::None => None,
+            SignalResampling::Count => Some(self.signal_sample_count),
+            SignalResampling::Spacing => {
+                let spacing_px = self.signal_sample_spacing_mm * self.pixels_per_mm;
+                Some(((perimeter / spacing_px).round() as usize).max(8))
+            }
+        }
+    }
+    
     /// Save configuration to a TOML file
     ///
     /// # Arguments
@@ -0,0 +1,219 @@
+// src/connected_components.rs - Two-pass union-find connected-component labelling
+
+use crate::leaf_mask::BinaryMask;
+
+/// Pixel adjacency used for labelling
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Edge neighbours only (use for background when the foreground is 8-connected)
+    Four,
+    /// Edge and corner neighbours
+    Eight,
+}
+
+/// Per-component statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentStats {
+    /// Label of the component in the label image (1-based)
+    pub label: u32,
+    /// Number of pixels
+    pub area: usize,
+    /// Bounding box (inclusive): min_x, min_y, max_x, max_y
+    pub bounding_box: (u32, u32, u32, u32),
+    /// Mean pixel position (x, y)
+    pub centroid: (f64, f64),
+    /// Number of pixel edges shared with background or the image border (crack perimeter)
+    pub perimeter: usize,
+    /// Number of component pixels on the image border
+    pub border_contact: usize,
+}
+
+impl ComponentStats {
+    /// Whether the component touches the image border
+    pub fn touches_border(&self) -> bool {
+        self.border_contact > 0
+    }
+}
+
+/// Dense label image with statistics of every component
+///
+/// Label 0 is background; components are labelled 1..=n in raster order of
+/// their first pixel, and `stats[label - 1]` describes component `label`.
+#[derive(Debug, Clone)]
+pub struct ComponentLabels {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major label image
+    pub labels: Vec<u32>,
+    pub stats: Vec<ComponentStats>,
+}
+
+impl ComponentLabels {
+    /// Label at (x, y)
+    #[inline]
+    pub fn label(&self, x: u32, y: u32) -> u32 {
+        self.labels[(y * self.width + x) as usize]
+    }
+    
+    /// Number of components
+    pub fn count(&self) -> usize {
+        self.stats.len()
+    }
+    
+    /// Largest component by area (the last one wins ties)
+    pub fn largest(&self) -> Option<&ComponentStats> {
+        self.stats.iter().max_by_key(|stats| stats.area)
+    }
+    
+    /// Mask of the components accepted by `keep`
+    pub fn mask_where<F: Fn(&ComponentStats) -> bool>(&self, keep: F) -> BinaryMask {
+        let kept: Vec<bool> = std::iter::once(false)
+            .chain(self.stats.iter().map(keep))
+            .collect();
+        
+        BinaryMask::from_vec(
+            self.width,
+            self.height,
+            self.labels.iter().map(|&label| kept[label as usize]).collect(),
+        )
+    }
+}
+
+/// Find the root of a provisional label with path halving
+fn find_root(parents: &mut [u32], mut label: u32) -> u32 {
+    while parents[label as usize] != label {
+        parents[label as usize] = parents[parents[label as usize] as usize];
+        label = parents[label as usize];
+    }
+    label
+}
+
+/// Merge two provisional labels (the smaller root becomes the parent)
+fn union(parents: &mut [u32], a: u32, b: u32) -> u32 {
+    let root_a = find_root(parents, a);
+    let root_b = find_root(parents, b);
+    let (root, child) = if root_a < root_b { (root_a, root_b) } else { (root_b, root_a) };
+    parents[child as usize] = root;
+    root
+}
+
+/// Label the 8-connected components of a mask
+///
+/// # Arguments
+/// * `mask` - Foreground mask
+///
+/// # Returns
+/// Label image with per-component statistics
+pub fn label_components(mask: &BinaryMask) -> ComponentLabels {
+    label_components_with(mask, Connectivity::Eight)
+}
+
+/// Label the connected components of a mask
+///
+/// The first pass assigns provisional labels from the already visited
+/// neighbours (W, NW, N, NE for 8-connectivity) and records equivalences in a
+/// union-find forest; the second pass resolves them to compact labels and
+/// accumulates the statistics. Memory is one `u32` per pixel.
+///
+/// # Arguments
+/// * `mask` - Foreground mask
+/// * `connectivity` - Pixel adjacency
+///
+/// # Returns
+/// Label image with per-component statistics
+pub fn label_components_with(mask: &BinaryMask, connectivity: Connectivity) -> ComponentLabels {
+    let (width, height) = mask.dimensions();
+    let visited_neighbours: &[(i32, i32)] = match connectivity {
+        Connectivity::Four => &[(-1, 0), (0, -1)],
+        Connectivity::Eight => &[(-1, 0), (-1, -1), (0, -1), (1, -1)],
+    };
+    let mut labels = vec![0u32; (width * height) as usize];
+    
+    // parents[0] is the background
+    let mut parents: Vec<u32> = vec![0];
+    
+    // Pass 1: provisional labels and equivalences
+    for y in 0..height {
+        for x in 0..width {
+            if !mask.get(x, y) {
+                continue;
+            }
+            
+            let index = (y * width + x) as usize;
+            let mut current = 0;
+            
+            for &(dx, dy) in visited_neighbours {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if !mask.get_or_false(nx, ny) {
+                    continue;
+                }
+                
+                let neighbour = labels[(ny as u32 * width + nx as u32) as usize];
+                current = if current == 0 { neighbour } else { union(&mut parents, current, neighbour) };
+            }
+            
+            if current == 0 {
+                current = parents.len() as u32;
+                parents.push(current);
+            }
+            
+            labels[index] = current;
+        }
+    }
+    
+    // Pass 2: compact labels (raster order of first pixel) and statistics
+    let mut compact = vec![0u32; parents.len()];
+    let mut stats: Vec<ComponentStats> = Vec::new();
+    let mut sums: Vec<(f64, f64)> = Vec::new();
+    
+    for y in 0..height {
+        for x in 0..width {
+            let index = (y * width + x) as usize;
+            if labels[index] == 0 {
+                continue;
+            }
+            
+            let root = find_root(&mut parents, labels[index]) as usize;
+            if compact[root] == 0 {
+                stats.push(ComponentStats {
+                    label: stats.len() as u32 + 1,
+                    area: 0,
+                    bounding_box: (x, y, x, y),
+                    centroid: (0.0, 0.0),
+                    perimeter: 0,
+                    border_contact: 0,
+                });
+                sums.push((0.0, 0.0));
+                compact[root] = stats.len() as u32;
+            }
+            
+            let label = compact[root];
+            labels[index] = label;
+            
+            let component = &mut stats[label as usize - 1];
+            component.area += 1;
+            component.bounding_box.0 = component.bounding_box.0.min(x);
+            component.bounding_box.1 = component.bounding_box.1.min(y);
+            component.bounding_box.2 = component.bounding_box.2.max(x);
+            component.bounding_box.3 = component.bounding_box.3.max(y);
+            
+            component.perimeter += [(1, 0), (-1, 0), (0, 1), (0, -1)].iter()
+                .filter(|&&(dx, dy)| !mask.get_or_false(x as i32 + dx, y as i32 + dy))
+                .count();
+            
+            if x == 0 || y == 0 || x == width - 1 || y == height - 1 {
+                component.border_contact += 1;
+            }
+            
+            sums[label as usize - 1].0 += x as f64;
+            sums[label as usize - 1].1 += y as f64;
+        }
+    }
+    
+    for (component, &(sum_x, sum_y)) in stats.iter_mut().zip(sums.iter()) {
+        component.centroid = (sum_x / component.area as f64, sum_y / component.area as f64);
+    }
+    
+    ComponentLabels { width, height, labels, stats }
+}
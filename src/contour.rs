@@ -0,0 +1,247 @@
+// src/contour.rs - Leaf boundary extraction (Moore pixel contour or sub-pixel marching squares)
+
+use std::collections::HashMap;
+
+use image::RgbaImage;
+
+use crate::config::ContourMethod;
+use crate::leaf_mask::BinaryMask;
+use crate::morphology::trace_contour;
+
+/// Boundary of an analysis region
+///
+/// `polygon` is used for perimeter, area and dimension metrics; `pixels` are
+/// the region pixels along the boundary in the same order and serve as the
+/// marginal points of the per-point signals (geodesic paths run on the pixel
+/// grid).
+#[derive(Debug, Clone)]
+pub struct LeafContour {
+    /// Method that produced the contour
+    pub method: ContourMethod,
+    /// Closed boundary polygon (first point not repeated)
+    pub polygon: Vec<(f64, f64)>,
+    /// Region pixels along the boundary (consecutive duplicates removed)
+    pub pixels: Vec<(u32, u32)>,
+}
+
+impl LeafContour {
+    /// Extract the boundary of a region with the configured method
+    ///
+    /// # Arguments
+    /// * `region` - Analysis region
+    /// * `image` - Source image whose alpha channel refines marching-squares vertices
+    /// * `iso_level` - Alpha level of the marching-squares boundary
+    /// * `method` - Contour extraction method
+    pub fn extract(region: &BinaryMask, image: Option<&RgbaImage>, iso_level: u8, method: ContourMethod) -> Self {
+        match method {
+            ContourMethod::Moore => {
+                let pixels = trace_contour(region);
+                let polygon = pixels.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+                Self { method, polygon, pixels }
+            }
+            ContourMethod::MarchingSquares => {
+                let (polygon, vertex_pixels): (Vec<_>, Vec<_>) =
+                    marching_squares_contour(region, image, iso_level).into_iter().unzip();
+                let mut pixels: Vec<(u32, u32)> = Vec::with_capacity(vertex_pixels.len());
+                for pixel in vertex_pixels {
+                    if pixels.last() != Some(&pixel) {
+                        pixels.push(pixel);
+                    }
+                }
+                if pixels.len() > 1 && pixels.first() == pixels.last() {
+                    pixels.pop();
+                }
+                Self { method, polygon, pixels }
+            }
+        }
+    }
+    
+    /// Perimeter of the boundary polygon
+    pub fn perimeter(&self) -> f64 {
+        polygon_perimeter(&self.polygon)
+    }
+    
+    /// Area enclosed by the boundary polygon
+    pub fn area(&self) -> f64 {
+        polygon_signed_area(&self.polygon).abs()
+    }
+}
+
+/// Perimeter of a closed polygon
+pub fn polygon_perimeter(polygon: &[(f64, f64)]) -> f64 {
+    if polygon.len() < 2 {
+        return 0.0;
+    }
+    
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (x1, y1) = polygon[i];
+            let (x2, y2) = polygon[(i + 1) % n];
+            ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt()
+        })
+        .sum()
+}
+
+/// Shoelace area of a closed polygon in image coordinates
+///
+/// Negative for counter-clockwise polygons as displayed (y axis pointing down).
+pub fn polygon_signed_area(polygon: &[(f64, f64)]) -> f64 {
+    let n = polygon.len();
+    if n < 3 {
+        return 0.0;
+    }
+    
+    (0..n)
+        .map(|i| {
+            let (x1, y1) = polygon[i];
+            let (x2, y2) = polygon[(i + 1) % n];
+            x1 * y2 - x2 * y1
+        })
+        .sum::<f64>() / 2.0
+}
+
+/// Marching-squares vertex: sub-pixel point and the region pixel of its cell edge
+pub type ContourVertex = ((f64, f64), (u32, u32));
+
+/// Scalar field sampled by marching squares
+///
+/// Alpha is used where it agrees with region membership; elsewhere (pixels
+/// removed from the MC leaf, weak pixels dropped by hysteresis) the field is
+/// binary so the boundary falls half-way between pixel centres.
+fn field_value(region: &BinaryMask, image: Option<&RgbaImage>, iso_level: f64, x: i32, y: i32) -> f64 {
+    let inside = region.get_or_false(x, y);
+    let alpha = match image {
+        Some(img) if x >= 0 && y >= 0 && (x as u32) < img.width() && (y as u32) < img.height() => {
+            img.get_pixel(x as u32, y as u32)[3] as f64
+        }
+        _ => if inside { 255.0 } else { 0.0 },
+    };
+    
+    match (inside, alpha >= iso_level) {
+        (true, true) | (false, false) => alpha,
+        (true, false) => 255.0,
+        (false, true) => 0.0,
+    }
+}
+
+/// Extract the outer boundary of a region with marching squares
+///
+/// Pixel centres are the grid corners; the boundary vertex on each crossed
+/// cell edge is linearly interpolated at `iso_level`. Saddle cells join
+/// diagonal region pixels (matching the 8-connected labelling). Segments are
+/// oriented with the region on their left as displayed, so every closed loop
+/// is consistent: outer boundaries counter-clockwise, holes clockwise. The
+/// outer loop enclosing the largest area is returned.
+///
+/// # Arguments
+/// * `region` - Analysis region
+/// * `image` - Optional source image; without it the boundary lies half-way between pixel centres
+/// * `iso_level` - Alpha level of the boundary (the policy's low threshold)
+///
+/// # Returns
+/// Counter-clockwise polygon vertices, each with the region pixel of its cell edge
+pub fn marching_squares_contour(
+    region: &BinaryMask,
+    image: Option<&RgbaImage>,
+    iso_level: u8,
+) -> Vec<ContourVertex> {
+    let (width, height) = region.dimensions();
+    let iso = iso_level.max(1) as f64;
+    let stride = width as i64 + 2;
+    
+    // Edge key: corner index (padded grid) * 2 + direction (0 = right, 1 = down)
+    let edge_key = |x: i32, y: i32, down: bool| ((y as i64 + 1) * stride + (x as i64 + 1)) * 2 + down as i64;
+    
+    // Crossing point and region pixel of the edge from corner a to corner b
+    let crossing = |a: (i32, i32), b: (i32, i32)| -> ContourVertex {
+        let va = field_value(region, image, iso, a.0, a.1);
+        let vb = field_value(region, image, iso, b.0, b.1);
+        let t = ((iso - va) / (vb - va)).clamp(0.0, 1.0);
+        let point = (a.0 as f64 + t * (b.0 - a.0) as f64, a.1 as f64 + t * (b.1 - a.1) as f64);
+        let pixel = if region.get_or_false(a.0, a.1) { a } else { b };
+        (point, (pixel.0 as u32, pixel.1 as u32))
+    };
+    
+    let mut next_edge: HashMap<i64, i64> = HashMap::new();
+    let mut vertices: HashMap<i64, ContourVertex> = HashMap::new();
+    
+    for cy in -1..height as i32 {
+        for cx in -1..width as i32 {
+            let corners = [(cx, cy), (cx + 1, cy), (cx + 1, cy + 1), (cx, cy + 1)];
+            let inside: Vec<bool> = corners.iter().map(|&(x, y)| region.get_or_false(x, y)).collect();
+            let inside_count = inside.iter().filter(|&&v| v).count();
+            if inside_count == 0 || inside_count == 4 {
+                continue;
+            }
+            
+            // Cell edges as (corner a, corner b, key): top, right, bottom, left
+            let edges = [
+                (0, 1, edge_key(cx, cy, false)),
+                (1, 2, edge_key(cx + 1, cy, true)),
+                (3, 2, edge_key(cx, cy + 1, false)),
+                (0, 3, edge_key(cx, cy, true)),
+            ];
+            
+            // Segments as (edge, edge, reference corner, reference corner is inside)
+            let segments: Vec<(usize, usize, usize, bool)> = if inside == [true, false, true, false] {
+                vec![(0, 1, 1, false), (3, 2, 3, false)]
+            } else if inside == [false, true, false, true] {
+                vec![(3, 0, 0, false), (1, 2, 2, false)]
+            } else {
+                let crossed: Vec<usize> = (0..4).filter(|&e| inside[edges[e].0] != inside[edges[e].1]).collect();
+                let reference = (0..4).find(|&c| inside[c]).unwrap_or(0);
+                vec![(crossed[0], crossed[1], reference, true)]
+            };
+            
+            for (e1, e2, reference, reference_inside) in segments {
+                for &e in &[e1, e2] {
+                    let (a, b, key) = edges[e];
+                    vertices.entry(key).or_insert_with(|| crossing(corners[a], corners[b]));
+                }
+                
+                let p = vertices[&edges[e1].2].0;
+                let q = vertices[&edges[e2].2].0;
+                let r = (corners[reference].0 as f64, corners[reference].1 as f64);
+                let cross = (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0);
+                
+                // Region on the left as displayed <=> negative cross product towards an inside corner
+                if (cross < 0.0) == reference_inside {
+                    next_edge.insert(edges[e1].2, edges[e2].2);
+                } else {
+                    next_edge.insert(edges[e2].2, edges[e1].2);
+                }
+            }
+        }
+    }
+    
+    // Link segments into closed loops and keep the largest outer loop
+    let mut best: (f64, Vec<i64>) = (0.0, Vec::new());
+    let mut visited: std::collections::HashSet<i64> = std::collections::HashSet::new();
+    let mut starts: Vec<i64> = next_edge.keys().copied().collect();
+    starts.sort_unstable();
+    
+    for start in starts {
+        if visited.contains(&start) {
+            continue;
+        }
+        
+        let mut loop_keys = Vec::new();
+        let mut key = start;
+        while visited.insert(key) {
+            loop_keys.push(key);
+            key = match next_edge.get(&key) {
+                Some(&next) => next,
+                None => break,
+            };
+        }
+        
+        let polygon: Vec<(f64, f64)> = loop_keys.iter().map(|k| vertices[k].0).collect();
+        let signed_area = polygon_signed_area(&polygon);
+        if signed_area < best.0 {
+            best = (signed_area, loop_keys);
+        }
+    }
+    
+    best.1.iter().map(|k| vertices[k]).collect()
+}
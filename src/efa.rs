@@ -0,0 +1,198 @@
+// src/efa.rs - Elliptic Fourier analysis of the outline (Kuhl-Giardina)
+
+use std::f64::consts::PI;
+
+/// Elliptic Fourier descriptors of a closed outline
+///
+/// Harmonic `n` (1-based) is `coefficients[n - 1] = [A, B, C, D]`, with
+/// x(t) = A0 + Σ A cos(2πnt/T) + B sin(2πnt/T) and y(t) = C0 + Σ C cos + D sin.
+/// Coordinates use the y axis pointing up, as Momocs imports outlines.
+#[derive(Debug, Clone, Default)]
+pub struct EllipticFourier {
+    /// A, B, C, D per harmonic
+    pub coefficients: Vec<[f64; 4]>,
+    /// Mean x of the outline (A0)
+    pub a0: f64,
+    /// Mean y of the outline (C0)
+    pub c0: f64,
+    /// Coefficients normalised for size, rotation and start point
+    pub normalized: bool,
+    /// Semi-major axis of the first harmonic ellipse before normalisation
+    pub size: f64,
+    /// Rotation of the first harmonic ellipse before normalisation, in radians
+    pub rotation: f64,
+    /// Start-point phase shift applied during normalisation, in radians
+    pub phase: f64,
+}
+
+impl EllipticFourier {
+    /// Fourier power per harmonic, (A² + B² + C² + D²) / 2
+    pub fn harmonic_power(&self) -> Vec<f64> {
+        self.coefficients.iter()
+            .map(|c| c.iter().map(|v| v * v).sum::<f64>() / 2.0)
+            .collect()
+    }
+    
+    /// Cumulative fraction of the total power up to each harmonic
+    pub fn cumulative_power(&self) -> Vec<f64> {
+        let power = self.harmonic_power();
+        let total: f64 = power.iter().sum();
+        let mut cumulative = 0.0;
+        power.iter()
+            .map(|p| {
+                cumulative += p;
+                if total > 0.0 { cumulative / total } else { 0.0 }
+            })
+            .collect()
+    }
+}
+
+/// Straight outline segment with its position along the arc
+struct Segment {
+    /// Arc length at the start of the segment
+    start: f64,
+    /// Segment length
+    length: f64,
+    /// x increment
+    dx: f64,
+    /// y increment
+    dy: f64,
+    /// Segment midpoint
+    midpoint: (f64, f64),
+}
+
+/// Kuhl-Giardina coefficients of a closed outline
+///
+/// # Arguments
+/// * `outline` - Outline points in image coordinates (y pointing down), closed implicitly
+/// * `harmonics` - Number of harmonics (capped at half the number of points)
+///
+/// # Returns
+/// Raw (unnormalised) descriptors; empty for fewer than three distinct points
+pub fn elliptic_fourier(outline: &[(f64, f64)], harmonics: usize) -> EllipticFourier {
+    // Flip y so the descriptors match outlines imported by Momocs
+    let points: Vec<(f64, f64)> = outline.iter().map(|&(x, y)| (x, -y)).collect();
+    let n = points.len();
+    
+    // Arc length at the start of every segment (duplicate points are skipped)
+    let mut segments = Vec::with_capacity(n);
+    let mut t = 0.0;
+    for i in 0..n {
+        let (p, q) = (points[i], points[(i + 1) % n]);
+        let (dx, dy) = (q.0 - p.0, q.1 - p.1);
+        let dt = dx.hypot(dy);
+        if dt > 0.0 {
+            segments.push(Segment { start: t, length: dt, dx, dy, midpoint: ((p.0 + q.0) / 2.0, (p.1 + q.1) / 2.0) });
+            t += dt;
+        }
+    }
+    let period = t;
+    
+    if segments.len() < 3 || period <= 0.0 {
+        return EllipticFourier::default();
+    }
+    
+    let harmonics = harmonics.min(segments.len() / 2).max(1);
+    let mut coefficients = Vec::with_capacity(harmonics);
+    
+    for harmonic in 1..=harmonics {
+        let omega = 2.0 * PI * harmonic as f64 / period;
+        let factor = period / (2.0 * (harmonic as f64 * PI).powi(2));
+        let (mut a, mut b, mut c, mut d) = (0.0, 0.0, 0.0, 0.0);
+        
+        for segment in &segments {
+            let (start, end) = (omega * segment.start, omega * (segment.start + segment.length));
+            let (cos_delta, sin_delta) = (end.cos() - start.cos(), end.sin() - start.sin());
+            a += segment.dx / segment.length * cos_delta;
+            b += segment.dx / segment.length * sin_delta;
+            c += segment.dy / segment.length * cos_delta;
+            d += segment.dy / segment.length * sin_delta;
+        }
+        
+        coefficients.push([factor * a, factor * b, factor * c, factor * d]);
+    }
+    
+    // Mean position (DC components) of the piecewise-linear outline
+    let a0 = segments.iter().map(|s| s.midpoint.0 * s.length).sum::<f64>() / period;
+    let c0 = segments.iter().map(|s| s.midpoint.1 * s.length).sum::<f64>() / period;
+    
+    EllipticFourier {
+        coefficients,
+        a0,
+        c0,
+        ..Default::default()
+    }
+}
+
+/// Normalise descriptors for size, rotation and start point (Momocs `efourier_norm`)
+///
+/// The start point is shifted to the end of the major axis of the first
+/// harmonic ellipse, the major axis is rotated onto the x axis and the
+/// coefficients are divided by its length, so A1 = 1 and B1 = C1 = 0.
+///
+/// # Arguments
+/// * `efa` - Raw descriptors from `elliptic_fourier`
+///
+/// # Returns
+/// Normalised descriptors (unchanged when there are no harmonics)
+pub fn normalize_elliptic_fourier(efa: &EllipticFourier) -> EllipticFourier {
+    let [a1, b1, c1, d1] = match efa.coefficients.first() {
+        Some(&first) => first,
+        None => return efa.clone(),
+    };
+    
+    // Phase of the major axis of the first harmonic ellipse (atan2 stays finite
+    // when the first harmonic is a circle, where the atan quotient is 0/0)
+    let mut theta = (0.5 * (2.0 * (a1 * b1 + c1 * d1)).atan2(a1 * a1 + c1 * c1 - b1 * b1 - d1 * d1)).rem_euclid(PI);
+    let (sin, cos) = theta.sin_cos();
+    let first_axis = (a1 * cos + b1 * sin).powi(2) + (c1 * cos + d1 * sin).powi(2);
+    let second_axis = (b1 * cos - a1 * sin).powi(2) + (d1 * cos - c1 * sin).powi(2);
+    if first_axis < second_axis {
+        theta += PI / 2.0;
+    }
+    theta = (theta + PI / 2.0).rem_euclid(PI) - PI / 2.0;
+    
+    // Orientation and length of the major axis
+    let a_star = a1 * theta.cos() + b1 * theta.sin();
+    let c_star = c1 * theta.cos() + d1 * theta.sin();
+    let size = a_star.hypot(c_star);
+    if size <= 0.0 {
+        return efa.clone();
+    }
+    // Full-circle angle, so A1 ends up +1 rather than -1
+    let psi = c_star.atan2(a_star).rem_euclid(2.0 * PI);
+    let (sin_psi, cos_psi) = psi.sin_cos();
+    
+    let coefficients = efa.coefficients.iter()
+        .enumerate()
+        .map(|(i, &[a, b, c, d])| {
+            let (sin_phase, cos_phase) = ((i + 1) as f64 * theta).sin_cos();
+            
+            // Start-point shift: [a b; c d] * [cos -sin; sin cos]
+            let (a, b, c, d) = (
+                a * cos_phase + b * sin_phase,
+                -a * sin_phase + b * cos_phase,
+                c * cos_phase + d * sin_phase,
+                -c * sin_phase + d * cos_phase,
+            );
+            
+            // Rotation and scaling: [cos sin; -sin cos] * [a b; c d] / size
+            [
+                (cos_psi * a + sin_psi * c) / size,
+                (cos_psi * b + sin_psi * d) / size,
+                (-sin_psi * a + cos_psi * c) / size,
+                (-sin_psi * b + cos_psi * d) / size,
+            ]
+        })
+        .collect();
+    
+    EllipticFourier {
+        coefficients,
+        a0: efa.a0,
+        c0: efa.c0,
+        normalized: true,
+        size,
+        rotation: psi,
+        phase: theta,
+    }
+}
@@ -0,0 +1,473 @@
+// src/entropy.rs - Entropy estimators for per-point signals (approximate, sample, permutation, multiscale)
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::config::EntropyMethod;
+
+/// All entropy estimators of one signal
+#[derive(Debug, Clone, Default)]
+pub struct EntropyMetrics {
+    /// Approximate entropy (ApEn, counts self-matches)
+    pub approximate: f64,
+    /// Sample entropy (SampEn, excludes self-matches)
+    pub sample: f64,
+    /// Normalised permutation entropy in [0, 1]
+    pub permutation: f64,
+    /// Refined composite multiscale entropy per scale (scale 1 first)
+    pub multiscale_curve: Vec<f64>,
+    /// Mean of the refined composite multiscale entropy curve
+    pub multiscale: f64,
+}
+
+impl EntropyMetrics {
+    /// Value of the selected estimator
+    pub fn value(&self, method: EntropyMethod) -> f64 {
+        match method {
+            EntropyMethod::Approximate => self.approximate,
+            EntropyMethod::Sample => self.sample,
+            EntropyMethod::Permutation => self.permutation,
+            EntropyMethod::Multiscale => self.multiscale,
+        }
+    }
+}
+
+/// Population standard deviation of a signal
+fn standard_deviation(signal: &[f64]) -> f64 {
+    if signal.is_empty() {
+        return 0.0;
+    }
+    
+    let mean = signal.iter().sum::<f64>() / signal.len() as f64;
+    let variance = signal.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / signal.len() as f64;
+    variance.sqrt()
+}
+
+/// Tolerance as a fraction of the standard deviation (the fraction itself for flat signals)
+pub fn relative_tolerance(signal: &[f64], r: f64) -> f64 {
+    let std_dev = standard_deviation(signal);
+    if std_dev > 1e-6 { r * std_dev } else { r }
+}
+
+/// Distinct templates of a signal, sorted lexicographically
+///
+/// Per-point signals have long plateaus (the EC pink signal is mostly 0), so
+/// many templates are identical; counting each distinct template once and
+/// weighting it by its multiplicity keeps the match counts exact.
+struct TemplateGroups {
+    /// Start index of one template per group, in lexicographic order
+    representatives: Vec<usize>,
+    /// Number of templates in each group
+    multiplicities: Vec<u64>,
+    /// Group of every template start
+    group_of: Vec<usize>,
+}
+
+/// Group the templates of `length` samples starting at `0..count`
+fn group_templates(signal: &[f64], count: usize, length: usize) -> TemplateGroups {
+    let template = |i: usize| &signal[i..i + length];
+    
+    let mut starts: Vec<usize> = (0..count).collect();
+    starts.par_sort_by(|&a, &b| {
+        template(a).iter()
+            .zip(template(b))
+            .map(|(x, y)| x.total_cmp(y))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+    
+    let mut groups = TemplateGroups {
+        representatives: Vec::new(),
+        multiplicities: Vec::new(),
+        group_of: vec![0; count],
+    };
+    
+    for start in starts {
+        let same = groups.representatives.last().is_some_and(|&r| template(r) == template(start));
+        if !same {
+            groups.representatives.push(start);
+            groups.multiplicities.push(0);
+        }
+        let group = groups.representatives.len() - 1;
+        groups.multiplicities[group] += 1;
+        groups.group_of[start] = group;
+    }
+    
+    groups
+}
+
+/// Templates of length m starting at i and j are within `r` in every sample
+fn templates_match(signal: &[f64], i: usize, j: usize, m: usize, r: f64) -> bool {
+    (0..m).all(|k| (signal[i + k] - signal[j + k]).abs() <= r)
+}
+
+/// Weighted k-d tree over the distinct templates for Chebyshev range counting
+///
+/// Implicit layout: the node of `order[lo..hi]` is its median `order[mid]`,
+/// with the left subtree in `lo..mid` and the right one in `mid + 1..hi`.
+/// Each node stores the total multiplicity and the bounding box of its
+/// subtree, so a query adds whole subtrees inside the tolerance box without
+/// visiting them and skips subtrees outside it. Boxes are classified with the
+/// same `|v - x| <= r` test as `templates_match`; the test holds on an
+/// interval of v, so both classifications are exact.
+///
+/// A query costs O(n^(1 - 1/length)) in the worst case (the nodes cut by the
+/// box boundary), so counting all templates is O(n^(2 - 1/length)) rather
+/// than the O(n²·length) pairwise comparison; plateau-heavy signals, whose
+/// matches are mostly whole subtrees, come close to O(n log n).
+struct TemplateTree<'a> {
+    signal: &'a [f64],
+    groups: &'a TemplateGroups,
+    /// Template length (tree dimension)
+    length: usize,
+    /// Groups in tree order
+    order: Vec<usize>,
+    /// Total multiplicity of the subtree rooted at each position
+    weights: Vec<u64>,
+    /// Subtree bounding box at each position: `length` minima, then `length` maxima
+    bounds: Vec<f64>,
+}
+
+impl<'a> TemplateTree<'a> {
+    fn new(signal: &'a [f64], groups: &'a TemplateGroups, length: usize) -> Self {
+        let count = groups.representatives.len();
+        let mut tree = TemplateTree {
+            signal,
+            groups,
+            length,
+            order: (0..count).collect(),
+            weights: vec![0; count],
+            bounds: vec![0.0; count * 2 * length],
+        };
+        tree.build(0, count, 0);
+        tree
+    }
+    
+    /// Sample k of the representative template of a group
+    fn coordinate(&self, group: usize, k: usize) -> f64 {
+        self.signal[self.groups.representatives[group] + k]
+    }
+    
+    /// Build the subtree of `order[lo..hi]`, split on sample `axis`
+    fn build(&mut self, lo: usize, hi: usize, axis: usize) {
+        if lo >= hi {
+            return;
+        }
+        
+        let mid = lo + (hi - lo) / 2;
+        let (signal, groups) = (self.signal, self.groups);
+        self.order[lo..hi].select_nth_unstable_by(mid - lo, |&a, &b| {
+            signal[groups.representatives[a] + axis].total_cmp(&signal[groups.representatives[b] + axis])
+        });
+        
+        let next_axis = (axis + 1) % self.length;
+        self.build(lo, mid, next_axis);
+        self.build(mid + 1, hi, next_axis);
+        
+        let group = self.order[mid];
+        let mut weight = self.groups.multiplicities[group];
+        let mut bounds: Vec<f64> = (0..self.length)
+            .map(|k| self.coordinate(group, k))
+            .cycle()
+            .take(2 * self.length)
+            .collect();
+        
+        for child in [(lo, mid), (mid + 1, hi)] {
+            if child.0 >= child.1 {
+                continue;
+            }
+            let root = child.0 + (child.1 - child.0) / 2;
+            weight += self.weights[root];
+            let child_bounds = &self.bounds[root * 2 * self.length..(root + 1) * 2 * self.length];
+            for k in 0..self.length {
+                bounds[k] = bounds[k].min(child_bounds[k]);
+                bounds[self.length + k] = bounds[self.length + k].max(child_bounds[self.length + k]);
+            }
+        }
+        
+        self.weights[mid] = weight;
+        self.bounds[mid * 2 * self.length..(mid + 1) * 2 * self.length].copy_from_slice(&bounds);
+    }
+    
+    /// Number of templates (with multiplicity) within `r` of the template starting at `start`
+    fn count_within(&self, start: usize, r: f64) -> u64 {
+        self.count_range(0, self.order.len(), start, r)
+    }
+    
+    fn count_range(&self, lo: usize, hi: usize, start: usize, r: f64) -> u64 {
+        if lo >= hi {
+            return 0;
+        }
+        
+        let mid = lo + (hi - lo) / 2;
+        let bounds = &self.bounds[mid * 2 * self.length..(mid + 1) * 2 * self.length];
+        
+        let mut inside = true;
+        for k in 0..self.length {
+            let x = self.signal[start + k];
+            let (low, high) = (bounds[k], bounds[self.length + k]);
+            let low_within = (low - x).abs() <= r;
+            let high_within = (high - x).abs() <= r;
+            
+            // Every sample beyond a failing bound on the far side of x fails too
+            if (low > x && !low_within) || (high < x && !high_within) {
+                return 0;
+            }
+            inside &= low_within && high_within;
+        }
+        
+        if inside {
+            return self.weights[mid];
+        }
+        
+        let group = self.order[mid];
+        let own = if templates_match(self.signal, start, self.groups.representatives[group], self.length, r) {
+            self.groups.multiplicities[group]
+        } else {
+            0
+        };
+        
+        own + self.count_range(lo, mid, start, r) + self.count_range(mid + 1, hi, start, r)
+    }
+}
+
+/// Matching template count (with multiplicity, including itself) of every group
+fn group_match_counts(signal: &[f64], groups: &TemplateGroups, length: usize, r: f64) -> Vec<u64> {
+    let tree = TemplateTree::new(signal, groups, length);
+    
+    groups.representatives
+        .par_iter()
+        .map(|&start| tree.count_within(start, r))
+        .collect()
+}
+
+/// Phi term of approximate entropy: mean log fraction of templates within `r`
+///
+/// Distinct templates are range-counted in parallel on a k-d tree (see
+/// `TemplateTree` for the cost); the logarithms are summed in template order,
+/// so the result is identical to the pairwise comparison.
+fn approximate_entropy_phi(signal: &[f64], m: usize, r: f64) -> f64 {
+    let templates = signal.len() - m + 1;
+    if m == 0 {
+        return 0.0;
+    }
+    
+    let groups = group_templates(signal, templates, m);
+    let counts = group_match_counts(signal, &groups, m, r);
+    
+    let mut sum = 0.0;
+    for i in 0..templates {
+        let ratio = counts[groups.group_of[i]] as f64 / templates as f64;
+        if ratio > 1e-12 {
+            sum += ratio.ln();
+        }
+    }
+    
+    sum / templates as f64
+}
+
+/// Approximate entropy of a signal (Pincus 1991)
+///
+/// # Arguments
+/// * `signal` - Per-point signal (finite samples)
+/// * `m` - Template length
+/// * `r` - Absolute tolerance
+///
+/// # Returns
+/// ApEn = phi(m) - phi(m + 1), or 0 when the signal is not longer than m
+pub fn calculate_approximate_entropy(signal: &[f64], m: usize, r: f64) -> f64 {
+    if signal.len() <= m {
+        return 0.0;
+    }
+    
+    approximate_entropy_phi(signal, m, r) - approximate_entropy_phi(signal, m + 1, r)
+}
+
+/// Unordered pairs of distinct templates of `length` samples within `r`
+fn matching_pairs(signal: &[f64], count: usize, length: usize, r: f64) -> u64 {
+    let groups = group_templates(signal, count, length);
+    let counts = group_match_counts(signal, &groups, length, r);
+    
+    // Ordered pairs, minus each template matching itself
+    let (ordered, self_matches) = groups.representatives.iter()
+        .zip(&groups.multiplicities)
+        .zip(&counts)
+        .map(|((&start, &multiplicity), &count)| {
+            let self_match = if templates_match(signal, start, start, length, r) { multiplicity } else { 0 };
+            (multiplicity * count, self_match)
+        })
+        .fold((0u64, 0u64), |acc, pair| (acc.0 + pair.0, acc.1 + pair.1));
+    
+    (ordered - self_matches) / 2
+}
+
+/// Template matches of length m and m + 1 (Chebyshev distance <= r, no self-matches)
+///
+/// Both lengths use the same N - m templates, as in Richman & Moorman (2000).
+/// Each length is range-counted on its own k-d tree, so the counts are exact.
+///
+/// # Returns
+/// (matching pairs of length m, matching pairs of length m + 1)
+pub fn sample_entropy_counts(signal: &[f64], m: usize, r: f64) -> (u64, u64) {
+    let n = signal.len();
+    if m == 0 || n <= m {
+        return (0, 0);
+    }
+    
+    (matching_pairs(signal, n - m, m, r), matching_pairs(signal, n - m, m + 1, r))
+}
+
+/// Sample entropy -ln(A / B) from template match counts
+///
+/// When no template of length m + 1 matches, the lower bound of the
+/// conditional probability, 2 / ((N - m - 1)(N - m)), is used so the value
+/// stays finite.
+fn sample_entropy_from_counts(matches_m: u64, matches_m1: u64, templates: usize) -> f64 {
+    if matches_m == 0 {
+        return 0.0;
+    }
+    
+    if matches_m1 == 0 {
+        let pairs = (templates as f64) * (templates as f64 - 1.0) / 2.0;
+        return if pairs > 0.0 { pairs.ln() } else { 0.0 };
+    }
+    
+    (matches_m as f64 / matches_m1 as f64).ln()
+}
+
+/// Sample entropy of a signal
+///
+/// # Arguments
+/// * `signal` - Per-point signal
+/// * `m` - Template length
+/// * `r` - Absolute tolerance
+///
+/// # Returns
+/// SampEn, or 0 when no template of length m matches
+pub fn calculate_sample_entropy(signal: &[f64], m: usize, r: f64) -> f64 {
+    let (matches_m, matches_m1) = sample_entropy_counts(signal, m, r);
+    sample_entropy_from_counts(matches_m, matches_m1, signal.len().saturating_sub(m))
+}
+
+/// Normalised permutation entropy (Bandt & Pompe)
+///
+/// Every window of `order` samples spaced `delay` apart is mapped to the
+/// permutation that sorts it (ties keep their order); the Shannon entropy
+/// of the ordinal pattern distribution is divided by ln(order!).
+///
+/// # Arguments
+/// * `signal` - Per-point signal
+/// * `order` - Embedding dimension (pattern length)
+/// * `delay` - Spacing between pattern samples
+///
+/// # Returns
+/// Permutation entropy in [0, 1] (0 for too short signals)
+pub fn calculate_permutation_entropy(signal: &[f64], order: usize, delay: usize) -> f64 {
+    if order < 2 || delay == 0 {
+        return 0.0;
+    }
+    
+    let span = (order - 1) * delay;
+    if signal.len() <= span {
+        return 0.0;
+    }
+    
+    let mut patterns: HashMap<Vec<usize>, usize> = HashMap::new();
+    let windows = signal.len() - span;
+    
+    for start in 0..windows {
+        let mut pattern: Vec<usize> = (0..order).collect();
+        pattern.sort_by(|&a, &b| signal[start + a * delay].total_cmp(&signal[start + b * delay]));
+        *patterns.entry(pattern).or_insert(0) += 1;
+    }
+    
+    let entropy = patterns.values()
+        .map(|&count| count as f64 / windows as f64)
+        .map(|p| p * (1.0 / p).ln())
+        .sum::<f64>();
+    let max_entropy = (2..=order).map(|k| (k as f64).ln()).sum::<f64>();
+    
+    if max_entropy > 0.0 { entropy / max_entropy } else { 0.0 }
+}
+
+/// Refined composite multiscale entropy (Wu et al. 2014)
+///
+/// At scale τ the signal is coarse-grained by averaging non-overlapping
+/// windows of τ samples, once for each of the τ possible offsets. The
+/// template matches of all offsets are summed before taking -ln(A / B), which
+/// keeps the estimate defined on short signals. The tolerance is fixed from
+/// the original signal.
+///
+/// # Arguments
+/// * `signal` - Per-point signal
+/// * `m` - Template length
+/// * `r` - Absolute tolerance
+/// * `max_scale` - Largest coarse-graining scale
+///
+/// # Returns
+/// Entropy per scale, from 1 to `max_scale`
+pub fn calculate_multiscale_entropy(signal: &[f64], m: usize, r: f64, max_scale: usize) -> Vec<f64> {
+    (1..=max_scale)
+        .map(|scale| {
+            let (mut matches_m, mut matches_m1, mut templates) = (0u64, 0u64, 0usize);
+            
+            for offset in 0..scale {
+                let coarse: Vec<f64> = signal.get(offset..)
+                    .unwrap_or(&[])
+                    .chunks_exact(scale)
+                    .map(|window| window.iter().sum::<f64>() / scale as f64)
+                    .collect();
+                
+                let (a, b) = sample_entropy_counts(&coarse, m, r);
+                matches_m += a;
+                matches_m1 += b;
+                templates = templates.max(coarse.len().saturating_sub(m));
+            }
+            
+            sample_entropy_from_counts(matches_m, matches_m1, templates)
+        })
+        .collect()
+}
+
+/// All entropy estimators of a signal with a common tolerance
+///
+/// # Arguments
+/// * `signal` - Per-point signal
+/// * `m` - Template length for approximate, sample and multiscale entropy
+/// * `r` - Tolerance as a fraction of the signal's standard deviation
+/// * `order` - Permutation entropy order
+/// * `delay` - Permutation entropy delay
+/// * `max_scale` - Largest multiscale entropy scale
+///
+/// # Returns
+/// Every estimator (all 0 for signals shorter than 4 samples)
+pub fn calculate_entropy_metrics(
+    signal: &[f64],
+    m: usize,
+    r: f64,
+    order: usize,
+    delay: usize,
+    max_scale: usize,
+) -> EntropyMetrics {
+    if signal.len() < 4 {
+        return EntropyMetrics::default();
+    }
+    
+    let tolerance = relative_tolerance(signal, r);
+    let multiscale_curve = calculate_multiscale_entropy(signal, m, tolerance, max_scale);
+    let multiscale = if multiscale_curve.is_empty() {
+        0.0
+    } else {
+        multiscale_curve.iter().sum::<f64>() / multiscale_curve.len() as f64
+    };
+    
+    EntropyMetrics {
+        approximate: calculate_approximate_entropy(signal, m, tolerance),
+        sample: calculate_sample_entropy(signal, m, tolerance),
+        permutation: calculate_permutation_entropy(signal, order, delay),
+        multiscale_curve,
+        multiscale,
+    }
+}
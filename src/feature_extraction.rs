@@ -1,8 +1,11 @@
 // src/feature_extraction.rs - Simplified feature extraction for EC/MC analysis
 
-use image::RgbaImage;
-
+use crate::config::SignalStart;
 use crate::errors::{LeafComplexError, Result};
+use crate::leaf_mask::LeafMask;
+use crate::morphology::{
+    contour_start_index, resample_contour_arc_length, resample_signal, ContourStart,
+};
 use crate::path_algorithms::{
     calculate_straight_path_length, calculate_diego_path, 
     calculate_diego_path_length, calculate_diego_path_pink, trace_straight_line,
@@ -36,19 +39,15 @@ pub struct MarginalPointFeatures {
 /// # Arguments
 /// * `reference_point` - The reference point (COM or EP)
 /// * `marginal_points` - All points on the leaf contour
-/// * `image` - The processed image
-/// * `marked_image` - Image with pink regions marked (for EC analysis)
-/// * `marked_color` - RGB color used for marking
-/// * `is_ec` - true for EC (pink as opaque), false for MC (pink as transparent)
+/// * `mask` - Leaf mask with the EC marked layers
+/// * `is_ec` - true for EC (whole leaf), false for MC (MC leaf only)
 ///
 /// # Returns
 /// Vector of features for each marginal point
 pub fn generate_features(
     reference_point: (u32, u32),
     marginal_points: &[(u32, u32)],
-    image: &RgbaImage,
-    marked_image: Option<&RgbaImage>,
-    marked_color: [u8; 3],
+    mask: &LeafMask,
     is_ec: bool,
 ) -> Result<Vec<MarginalPointFeatures>> {
     if marginal_points.is_empty() {
@@ -57,14 +56,9 @@ pub fn generate_features(
     
     let mut features = Vec::with_capacity(marginal_points.len());
     
-    // Select the appropriate image based on analysis type
-    let analysis_image = if is_ec {
-        // For EC, use the marked image where pink regions are opaque
-        marked_image.unwrap_or(image)
-    } else {
-        // For MC, use the original image
-        image
-    };
+    // Select the region to navigate based on analysis type
+    // (EC: whole leaf including marked regions, MC: MC leaf only)
+    let analysis_region = mask.region(is_ec);
     
     // Process each marginal point
     for (idx, &marginal_point) in marginal_points.iter().enumerate() {
@@ -75,11 +69,11 @@ pub fn generate_features(
         let straight_line = trace_straight_line(reference_point, marginal_point);
         
         // Check if straight line crosses transparency
-        let crosses_transparency = check_straight_line_transparency(&straight_line, analysis_image);
+        let crosses_transparency = check_straight_line_transparency(&straight_line, &analysis_region);
         
         // Calculate Diego Path - the shortest path that stays within the leaf
         let diego_path = if crosses_transparency {
-            calculate_diego_path(reference_point, marginal_point, analysis_image)
+            calculate_diego_path(reference_point, marginal_point, &analysis_region)
         } else {
             straight_line.clone()
         };
@@ -93,11 +87,7 @@ pub fn generate_features(
         
         // Calculate Diego path pink pixels (only for EC analysis)
         let diego_path_pink = if is_ec && !diego_path.is_empty() {
-            if let Some(marked) = marked_image {
-                Some(calculate_diego_path_pink(&diego_path, marked, marked_color))
-            } else {
-                None
-            }
+            Some(calculate_diego_path_pink(&diego_path, mask))
         } else {
             None
         };
@@ -118,3 +108,136 @@ pub fn generate_features(
     
     Ok(features)
 }
+
+/// Resample features to equally spaced positions along the contour arc length
+///
+/// Every per-point signal (geodesic, pink count, Thornfiddle, harmonic) is
+/// linearly interpolated between the neighbouring contour points; pink counts
+/// are rounded to whole pixels. The resampled marginal points are returned so
+/// that later geometric steps (golden chain detection) use the same samples.
+///
+/// # Arguments
+/// * `features` - Features of every contour point, in contour order
+/// * `marginal_points` - Contour the features were measured on
+/// * `target_points` - Number of samples
+///
+/// # Returns
+/// Resampled features (point_index = sample index) and sample positions
+pub fn resample_features(
+    features: &[MarginalPointFeatures],
+    marginal_points: &[(u32, u32)],
+    target_points: usize,
+) -> (Vec<MarginalPointFeatures>, Vec<(u32, u32)>) {
+    let n = features.len().min(marginal_points.len());
+    if n == 0 || target_points == 0 {
+        return (Vec::new(), Vec::new());
+    }
+    
+    let contour = &marginal_points[..n];
+    let interpolate = |value: fn(&MarginalPointFeatures) -> f64| -> Vec<f64> {
+        let signal: Vec<f64> = features[..n].iter().map(value).collect();
+        resample_signal(contour, &signal, target_points)
+    };
+    
+    let straight_path_length = interpolate(|f| f.straight_path_length);
+    let diego_path_length = interpolate(|f| f.diego_path_length);
+    let diego_path_pink = interpolate(|f| f.diego_path_pink.unwrap_or(0) as f64);
+    let thornfiddle_path = interpolate(|f| f.thornfiddle_path);
+    let thornfiddle_path_harmonic = interpolate(|f| f.thornfiddle_path_harmonic);
+    let has_pink = features[0].diego_path_pink.is_some();
+    
+    let resampled = (0..target_points)
+        .map(|i| MarginalPointFeatures {
+            point_index: i,
+            straight_path_length: straight_path_length[i],
+            diego_path_length: diego_path_length[i],
+            diego_path_pink: if has_pink { Some(diego_path_pink[i].round() as u32) } else { None },
+            thornfiddle_path: thornfiddle_path[i],
+            thornfiddle_path_harmonic: thornfiddle_path_harmonic[i],
+        })
+        .collect();
+    
+    (resampled, resample_contour_arc_length(contour, target_points))
+}
+
+/// Index of the contour point selected by a signal start policy
+///
+/// # Arguments
+/// * `features` - Features of every contour point, in contour order
+/// * `marginal_points` - Contour the features were measured on
+/// * `start` - Signal start policy
+/// * `petiole_base` - Detected petiole base, if any (see `thornfiddle::detect_petiole_base`)
+/// * `width` - Image width, for the bottom/top centre fallback without a petiole base
+///
+/// # Returns
+/// Index of the new first point; 0 for an empty contour
+pub fn signal_start_index(
+    features: &[MarginalPointFeatures],
+    marginal_points: &[(u32, u32)],
+    start: SignalStart,
+    petiole_base: Option<(u32, u32)>,
+    width: u32,
+) -> usize {
+    let n = features.len().min(marginal_points.len());
+    if n == 0 {
+        return 0;
+    }
+    let contour = &marginal_points[..n];
+    
+    let base_index = || match petiole_base {
+        Some((x, y)) => contour_start_index(contour, width, ContourStart::NearestTo(x, y)),
+        None => contour_start_index(contour, width, ContourStart::PetioleBase),
+    };
+    
+    match start {
+        SignalStart::Leftmost => 0,
+        SignalStart::Petiole => base_index(),
+        SignalStart::Apex => {
+            let (bx, by) = contour[base_index()];
+            match petiole_base {
+                Some(_) => contour.iter().enumerate()
+                    .max_by_key(|(_, &(x, y))| {
+                        let dx = x as i64 - bx as i64;
+                        let dy = y as i64 - by as i64;
+                        dx * dx + dy * dy
+                    })
+                    .map(|(i, _)| i)
+                    .unwrap_or(0),
+                None => contour_start_index(contour, width, ContourStart::Apex),
+            }
+        }
+        SignalStart::MaxGeodesic => features[..n].iter().enumerate()
+            .max_by(|(_, a), (_, b)| a.diego_path_length.total_cmp(&b.diego_path_length))
+            .map(|(i, _)| i)
+            .unwrap_or(0),
+    }
+}
+
+/// Rotate features and their contour so that `start` becomes the first point
+///
+/// # Arguments
+/// * `features` - Features of every contour point, in contour order
+/// * `marginal_points` - Contour the features were measured on
+/// * `start` - Index of the new first point
+///
+/// # Returns
+/// Rotated features (point_index renumbered) and contour
+pub fn rotate_features(
+    mut features: Vec<MarginalPointFeatures>,
+    marginal_points: &[(u32, u32)],
+    start: usize,
+) -> (Vec<MarginalPointFeatures>, Vec<(u32, u32)>) {
+    let n = features.len().min(marginal_points.len());
+    features.truncate(n);
+    let mut points = marginal_points[..n].to_vec();
+    
+    if n > 0 {
+        features.rotate_left(start % n);
+        points.rotate_left(start % n);
+    }
+    for (i, feature) in features.iter_mut().enumerate() {
+        feature.point_index = i;
+    }
+    
+    (features, points)
+}
@@ -0,0 +1,169 @@
+// src/fractal.rs - Fractal dimension of the margin (box counting and Minkowski-Bouligand)
+
+use crate::leaf_mask::BinaryMask;
+use crate::morphology::dilate_mask;
+
+/// Log-log regression of a fractal dimension estimator
+#[derive(Debug, Clone, Default)]
+pub struct FractalFit {
+    /// Box sizes or dilation radii in pixels (strictly increasing)
+    pub scales: Vec<u32>,
+    /// Occupied boxes or dilated area at each scale
+    pub measures: Vec<usize>,
+    /// Estimated fractal dimension
+    pub dimension: f64,
+    /// Coefficient of determination of the log-log fit
+    pub r_squared: f64,
+}
+
+/// Box-counting and Minkowski-Bouligand dimensions of one contour
+#[derive(Debug, Clone, Default)]
+pub struct FractalMetrics {
+    /// Box counting: log N(s) against log s, dimension = -slope
+    pub box_counting: FractalFit,
+    /// Minkowski-Bouligand: log A(r) against log r, dimension = 2 - slope
+    pub minkowski: FractalFit,
+}
+
+/// Log-spaced integer scales from 1 pixel to `max_scale`
+///
+/// # Arguments
+/// * `max_scale` - Largest scale in pixels
+/// * `count` - Number of scales before duplicates are removed
+///
+/// # Returns
+/// Distinct scales in increasing order
+pub fn fractal_scales(max_scale: f64, count: usize) -> Vec<u32> {
+    let max_scale = max_scale.max(1.0);
+    let mut scales: Vec<u32> = (0..count)
+        .map(|i| {
+            let t = if count > 1 { i as f64 / (count - 1) as f64 } else { 0.0 };
+            max_scale.powf(t).round().max(1.0) as u32
+        })
+        .collect();
+    
+    scales.dedup();
+    scales
+}
+
+/// Least-squares line through (x, y)
+///
+/// # Returns
+/// (slope, r_squared), or (0, 0) for fewer than two distinct x values
+fn linear_fit(x: &[f64], y: &[f64]) -> (f64, f64) {
+    let n = x.len() as f64;
+    if x.len() < 2 {
+        return (0.0, 0.0);
+    }
+    
+    let mean_x = x.iter().sum::<f64>() / n;
+    let mean_y = y.iter().sum::<f64>() / n;
+    let sxx: f64 = x.iter().map(|xi| (xi - mean_x).powi(2)).sum();
+    let syy: f64 = y.iter().map(|yi| (yi - mean_y).powi(2)).sum();
+    let sxy: f64 = x.iter().zip(y).map(|(xi, yi)| (xi - mean_x) * (yi - mean_y)).sum();
+    
+    if sxx <= 0.0 {
+        return (0.0, 0.0);
+    }
+    
+    let slope = sxy / sxx;
+    let r_squared = if syy > 0.0 { sxy * sxy / (sxx * syy) } else { 1.0 };
+    (slope, r_squared)
+}
+
+/// Fit `log(measure)` against `log(scale)` and turn the slope into a dimension
+fn fit_dimension(scales: Vec<u32>, measures: Vec<usize>, dimension_from_slope: fn(f64) -> f64) -> FractalFit {
+    let log_scales: Vec<f64> = scales.iter().map(|&s| (s as f64).ln()).collect();
+    let log_measures: Vec<f64> = measures.iter().map(|&m| (m.max(1) as f64).ln()).collect();
+    let (slope, r_squared) = linear_fit(&log_scales, &log_measures);
+    
+    FractalFit {
+        dimension: if scales.len() < 2 { 0.0 } else { dimension_from_slope(slope) },
+        r_squared,
+        scales,
+        measures,
+    }
+}
+
+/// Box-counting dimension of a contour
+///
+/// The grid is anchored at the top-left corner of the contour's bounding box;
+/// N(s) is the number of s x s boxes holding at least one contour pixel.
+///
+/// # Arguments
+/// * `contour` - Contour pixels
+/// * `box_sizes` - Box sizes in pixels, increasing
+///
+/// # Returns
+/// Box counts with the fitted dimension and R²
+pub fn calculate_box_counting_dimension(contour: &[(u32, u32)], box_sizes: &[u32]) -> FractalFit {
+    if contour.is_empty() {
+        return FractalFit::default();
+    }
+    
+    let min_x = contour.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_y = contour.iter().map(|p| p.1).min().unwrap_or(0);
+    
+    let counts = box_sizes.iter()
+        .map(|&size| {
+            let mut boxes: Vec<(u32, u32)> = contour.iter()
+                .map(|&(x, y)| ((x - min_x) / size, (y - min_y) / size))
+                .collect();
+            boxes.sort_unstable();
+            boxes.dedup();
+            boxes.len()
+        })
+        .collect();
+    
+    fit_dimension(box_sizes.to_vec(), counts, |slope| -slope)
+}
+
+/// Minkowski-Bouligand dimension of a contour
+///
+/// The contour pixels are dilated with `dilate_mask` by discs of radius r
+/// (diameter 2r + 1) on a canvas padded so no dilation is clipped; the
+/// sausage area A(r) grows as r^(2 - D).
+///
+/// # Arguments
+/// * `contour` - Contour pixels
+/// * `radii` - Dilation radii in pixels, increasing
+///
+/// # Returns
+/// Dilated areas with the fitted dimension and R²
+pub fn calculate_minkowski_dimension(contour: &[(u32, u32)], radii: &[u32]) -> FractalFit {
+    if contour.is_empty() {
+        return FractalFit::default();
+    }
+    
+    let padding = radii.iter().copied().max().unwrap_or(0) + 1;
+    let min_x = contour.iter().map(|p| p.0).min().unwrap_or(0);
+    let min_y = contour.iter().map(|p| p.1).min().unwrap_or(0);
+    let max_x = contour.iter().map(|p| p.0).max().unwrap_or(0);
+    let max_y = contour.iter().map(|p| p.1).max().unwrap_or(0);
+    
+    let mut margin = BinaryMask::new(max_x - min_x + 2 * padding + 1, max_y - min_y + 2 * padding + 1);
+    for &(x, y) in contour {
+        margin.set(x - min_x + padding, y - min_y + padding, true);
+    }
+    
+    let areas = radii.iter()
+        .map(|&radius| dilate_mask(&margin, 2 * radius + 1).count())
+        .collect();
+    
+    fit_dimension(radii.to_vec(), areas, |slope| 2.0 - slope)
+}
+
+/// Box-counting and Minkowski-Bouligand dimensions of a contour
+///
+/// # Arguments
+/// * `contour` - Contour pixels (the same contour as the EC or MC analysis)
+/// * `scales` - Box sizes and dilation radii in pixels, increasing
+///
+/// # Returns
+/// Both estimates with their log-log data
+pub fn calculate_fractal_dimensions(contour: &[(u32, u32)], scales: &[u32]) -> FractalMetrics {
+    FractalMetrics {
+        box_counting: calculate_box_counting_dimension(contour, scales),
+        minkowski: calculate_minkowski_dimension(contour, scales),
+    }
+}
@@ -0,0 +1,125 @@
+// src/holes.rs - Hole detection and inner-contour analysis for damaged or perforated leaves
+
+use crate::connected_components::{label_components_with, ComponentLabels, Connectivity};
+use crate::leaf_mask::{BinaryMask, LeafMask, HOLE, LEAF};
+use crate::morphology::trace_boundary;
+
+/// Per-leaf hole measurements
+#[derive(Debug, Clone, Default)]
+pub struct HoleMetrics {
+    /// Number of enclosed background regions
+    pub hole_count: usize,
+    /// Total hole area in pixels
+    pub total_hole_area: usize,
+    /// Area of the largest hole in pixels
+    pub largest_hole_area: usize,
+    /// Hole area as a percentage of the hole-filled leaf area
+    pub percent_area_missing: f64,
+    /// Inner contour (leaf pixels bordering the hole) of every hole, in hole label order
+    pub inner_contours: Vec<Vec<(u32, u32)>>,
+}
+
+/// Label the holes of a region
+///
+/// Background is labelled with 4-connectivity (the dual of the 8-connected
+/// leaf), so a diagonal gap in the margin does not leak the outside in.
+/// Components touching the image border are outside the leaf, not holes.
+///
+/// # Arguments
+/// * `region` - Leaf region
+///
+/// # Returns
+/// Background labels and the mask of enclosed background pixels
+pub fn find_holes(region: &BinaryMask) -> (ComponentLabels, BinaryMask) {
+    let (width, height) = region.dimensions();
+    let background = BinaryMask::from_fn(width, height, |x, y| !region.get(x, y));
+    let labels = label_components_with(&background, Connectivity::Four);
+    let holes = labels.mask_where(|stats| !stats.touches_border());
+    
+    (labels, holes)
+}
+
+/// Trace the inner contour around one hole with Moore-Neighbor tracing
+///
+/// Tracing starts at the leaf pixel west of the hole's first raster pixel,
+/// with the hole pixel as backtrack.
+///
+/// # Arguments
+/// * `region` - Leaf region
+/// * `hole_start` - First pixel of the hole in raster order
+///
+/// # Returns
+/// Leaf pixels bordering the hole, in tracing order
+pub fn trace_inner_contour(region: &BinaryMask, hole_start: (u32, u32)) -> Vec<(u32, u32)> {
+    let (hx, hy) = hole_start;
+    if hx == 0 || !region.get(hx - 1, hy) {
+        return Vec::new();
+    }
+    
+    trace_boundary(region, (hx - 1, hy), (hx as i32, hy as i32))
+}
+
+/// Measure the holes of a leaf region and trace their inner contours
+///
+/// # Arguments
+/// * `region` - Leaf region
+///
+/// # Returns
+/// Hole metrics and the mask of hole pixels
+pub fn analyze_holes(region: &BinaryMask) -> (HoleMetrics, BinaryMask) {
+    let (labels, holes) = find_holes(region);
+    
+    let hole_stats: Vec<_> = labels.stats.iter()
+        .filter(|stats| !stats.touches_border())
+        .collect();
+    
+    let total_hole_area: usize = hole_stats.iter().map(|stats| stats.area).sum();
+    let largest_hole_area = hole_stats.iter().map(|stats| stats.area).max().unwrap_or(0);
+    
+    let filled_area = region.count() + total_hole_area;
+    let percent_area_missing = if filled_area > 0 {
+        total_hole_area as f64 / filled_area as f64 * 100.0
+    } else {
+        0.0
+    };
+    
+    // The first raster pixel of a component is on the top row of its bounding box
+    let inner_contours = hole_stats.iter()
+        .map(|stats| {
+            let (min_x, min_y, max_x, _) = stats.bounding_box;
+            let start_x = (min_x..=max_x)
+                .find(|&x| labels.label(x, min_y) == stats.label)
+                .unwrap_or(min_x);
+            trace_inner_contour(region, (start_x, min_y))
+        })
+        .collect();
+    
+    let metrics = HoleMetrics {
+        hole_count: hole_stats.len(),
+        total_hole_area,
+        largest_hole_area,
+        percent_area_missing,
+        inner_contours,
+    };
+    
+    (metrics, holes)
+}
+
+/// Mark hole pixels in the leaf mask, optionally filling them into the leaf
+///
+/// # Arguments
+/// * `mask` - Leaf mask to update
+/// * `holes` - Hole pixels from `analyze_holes`
+/// * `fill` - Also set `LEAF` so EC/MC are computed on the hole-filled leaf
+pub fn mark_holes(mask: &mut LeafMask, holes: &BinaryMask, fill: bool) {
+    let (width, height) = mask.dimensions();
+    let layer = if fill { HOLE | LEAF } else { HOLE };
+    
+    for y in 0..height {
+        for x in 0..width {
+            if holes.get(x, y) {
+                mask.set(x, y, layer);
+            }
+        }
+    }
+}
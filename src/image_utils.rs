@@ -1,54 +1,151 @@
 use image::{ImageBuffer, Rgba, RgbaImage};
+use std::collections::VecDeque;
 
 /// Constants
 pub const ALPHA_THRESHOLD: u8 = 128; // Alpha value above which a pixel is considered non-transparent
 
-/// Resize an image to the specified dimensions
-pub fn resize_image(
-    image: &RgbaImage,
-    dimensions: [u32; 2],
-) -> RgbaImage {
-    let (width, height) = (dimensions[0], dimensions[1]);
-    image::imageops::resize(
-        image,
-        width,
-        height,
-        image::imageops::FilterType::Triangle,
-    )
+/// Foreground (leaf vs background) decision on the alpha channel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaPolicy {
+    /// Alpha >= threshold is leaf
+    Fixed(u8),
+    /// Threshold chosen per image by Otsu's method on the alpha histogram
+    Otsu,
+    /// Alpha >= high is leaf; alpha >= low is leaf if 8-connected to such a pixel
+    Hysteresis { low: u8, high: u8 },
 }
 
-/// Check if a pixel is transparent (alpha below threshold)
-#[inline]
-pub fn is_transparent(pixel: &Rgba<u8>) -> bool {
-    pixel[3] < ALPHA_THRESHOLD
+impl AlphaPolicy {
+    /// Name reported in output metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            AlphaPolicy::Fixed(_) => "FIXED",
+            AlphaPolicy::Otsu => "OTSU",
+            AlphaPolicy::Hysteresis { .. } => "HYSTERESIS",
+        }
+    }
+    
+    /// Resolve the effective thresholds for an image
+    pub fn resolve(&self, image: &RgbaImage) -> AlphaThresholds {
+        let (low, high) = match *self {
+            AlphaPolicy::Fixed(threshold) => (threshold, threshold),
+            AlphaPolicy::Otsu => {
+                let threshold = otsu_alpha_threshold(image);
+                (threshold, threshold)
+            }
+            AlphaPolicy::Hysteresis { low, high } => (low, high),
+        };
+        
+        AlphaThresholds { policy: *self, low, high }
+    }
 }
 
-/// Check if a pixel is non-transparent (alpha >= threshold)
-#[inline]
-pub fn is_non_transparent(pixel: &Rgba<u8>) -> bool {
-    pixel[3] >= ALPHA_THRESHOLD
+/// Effective alpha thresholds of a policy for one image
+///
+/// For fixed and Otsu policies `low == high`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AlphaThresholds {
+    pub policy: AlphaPolicy,
+    pub low: u8,
+    pub high: u8,
 }
 
-/// Check if a pixel has the specified RGB color (ignoring alpha)
-#[inline]
-pub fn has_rgb_color(pixel: &Rgba<u8>, color: [u8; 3]) -> bool {
-    pixel[0] == color[0] && pixel[1] == color[1] && pixel[2] == color[2]
+/// Otsu threshold of the alpha histogram
+///
+/// # Returns
+/// Threshold such that alpha >= threshold is foreground (`ALPHA_THRESHOLD`
+/// if the alpha channel is constant)
+pub fn otsu_alpha_threshold(image: &RgbaImage) -> u8 {
+    let mut histogram = [0u64; 256];
+    for pixel in image.pixels() {
+        histogram[pixel[3] as usize] += 1;
+    }
+    
+    let total: u64 = histogram.iter().sum();
+    let total_sum: f64 = histogram.iter()
+        .enumerate()
+        .map(|(value, &count)| value as f64 * count as f64)
+        .sum();
+    
+    let mut background_count = 0u64;
+    let mut background_sum = 0.0;
+    let mut best_variance = 0.0;
+    let mut best_split = None;
+    
+    // Split between background (alpha <= t) and foreground (alpha > t)
+    for (t, &count) in histogram.iter().enumerate().take(255) {
+        background_count += count;
+        background_sum += t as f64 * count as f64;
+        
+        let foreground_count = total - background_count;
+        if background_count == 0 || foreground_count == 0 {
+            continue;
+        }
+        
+        let background_mean = background_sum / background_count as f64;
+        let foreground_mean = (total_sum - background_sum) / foreground_count as f64;
+        let variance = background_count as f64 * foreground_count as f64
+            * (background_mean - foreground_mean).powi(2);
+        
+        if variance > best_variance {
+            best_variance = variance;
+            best_split = Some(t);
+        }
+    }
+    
+    best_split.map_or(ALPHA_THRESHOLD, |t| t as u8 + 1)
 }
 
-/// Create a mask image from alpha channel (255 for non-transparent, 0 for transparent)
-pub fn create_alpha_mask(image: &RgbaImage) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+/// Foreground mask of an image (row-major) according to the resolved alpha thresholds
+pub fn alpha_foreground(image: &RgbaImage, thresholds: &AlphaThresholds) -> Vec<bool> {
     let (width, height) = image.dimensions();
-    let mut mask = ImageBuffer::new(width, height);
+    let mut foreground: Vec<bool> = image.pixels()
+        .map(|pixel| pixel[3] >= thresholds.high)
+        .collect();
+    
+    if thresholds.low >= thresholds.high {
+        return foreground;
+    }
+    
+    // Grow the strong pixels through weak (>= low) 8-connected pixels
+    let mut queue: VecDeque<(u32, u32)> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| foreground[(y * width + x) as usize])
+        .collect();
     
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            let mask_value = if is_non_transparent(pixel) { 255 } else { 0 };
-            mask.put_pixel(x, y, Rgba([mask_value, mask_value, mask_value, 255]));
+    while let Some((x, y)) = queue.pop_front() {
+        for dy in -1..=1 {
+            for dx in -1..=1 {
+                let nx = x as i32 + dx;
+                let ny = y as i32 + dy;
+                if !in_bounds(nx, ny, width, height) {
+                    continue;
+                }
+                
+                let index = (ny as u32 * width + nx as u32) as usize;
+                if !foreground[index] && image.get_pixel(nx as u32, ny as u32)[3] >= thresholds.low {
+                    foreground[index] = true;
+                    queue.push_back((nx as u32, ny as u32));
+                }
+            }
         }
     }
     
-    mask
+    foreground
+}
+
+/// Resize an image to the specified dimensions
+pub fn resize_image(
+    image: &RgbaImage,
+    dimensions: [u32; 2],
+) -> RgbaImage {
+    let (width, height) = (dimensions[0], dimensions[1]);
+    image::imageops::resize(
+        image,
+        width,
+        height,
+        image::imageops::FilterType::Triangle,
+    )
 }
 
 /// Convert coordinates between image systems if needed
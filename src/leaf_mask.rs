@@ -0,0 +1,228 @@
+// src/leaf_mask.rs - Layered binary mask representation of a leaf
+
+use image::RgbaImage;
+
+use crate::image_utils::{alpha_foreground, AlphaThresholds};
+
+/// Pixel belongs to the leaf (opaque in the input image)
+pub const LEAF: u8 = 1 << 0;
+
+/// Leaf pixel removed by the adaptive EC opening (pink in rendered output)
+pub const EC_OPENED: u8 = 1 << 1;
+
+/// Leaf pixel outside the isolated MC component
+pub const MC_REMOVED: u8 = 1 << 2;
+
+/// MC pixel removed by the Thornfiddle opening (golden lobe region)
+pub const LOBE: u8 = 1 << 3;
+
+/// Background pixel enclosed by the leaf (hole); also `LEAF` when holes are filled
+pub const HOLE: u8 = 1 << 4;
+
+/// Leaf pixel in the detected petiole; `LEAF` is cleared when the petiole is removed
+pub const PETIOLE: u8 = 1 << 5;
+
+/// Single-layer binary image (row-major)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryMask {
+    width: u32,
+    height: u32,
+    data: Vec<bool>,
+}
+
+impl BinaryMask {
+    /// Create an empty (all false) mask
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![false; (width * height) as usize],
+        }
+    }
+    
+    /// Create a mask from row-major data
+    ///
+    /// # Panics
+    /// If `data.len() != width * height`
+    pub fn from_vec(width: u32, height: u32, data: Vec<bool>) -> Self {
+        assert_eq!(data.len(), (width * height) as usize, "mask data does not match dimensions");
+        Self { width, height, data }
+    }
+    
+    /// Create a mask by evaluating `f(x, y)` for every pixel
+    pub fn from_fn<F: Fn(u32, u32) -> bool>(width: u32, height: u32, f: F) -> Self {
+        let mut mask = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                mask.data[(y * width + x) as usize] = f(x, y);
+            }
+        }
+        mask
+    }
+    
+    /// (width, height) of the mask
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+    
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    
+    /// Value at (x, y); panics if out of bounds
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> bool {
+        self.data[(y * self.width + x) as usize]
+    }
+    
+    /// Value at signed coordinates; out-of-bounds pixels are false
+    #[inline]
+    pub fn get_or_false(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height
+            && self.data[(y as u32 * self.width + x as u32) as usize]
+    }
+    
+    /// Set the value at (x, y)
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32, value: bool) {
+        self.data[(y * self.width + x) as usize] = value;
+    }
+    
+    /// Row-major view of the mask
+    #[inline]
+    pub fn as_slice(&self) -> &[bool] {
+        &self.data
+    }
+    
+    /// Number of set pixels
+    pub fn count(&self) -> usize {
+        self.data.iter().filter(|&&value| value).count()
+    }
+}
+
+/// Per-pixel bit layers describing the leaf and the analysis regions
+///
+/// Replaces colour-coded RGBA state: a leaf pixel that happens to be pink or
+/// gold is no longer misclassified. Colours are only applied when rendering
+/// output and debug images.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeafMask {
+    width: u32,
+    height: u32,
+    layers: Vec<u8>,
+}
+
+impl LeafMask {
+    /// Create an empty mask
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            layers: vec![0; (width * height) as usize],
+        }
+    }
+    
+    /// Create a mask whose `LEAF` layer holds the foreground pixels of the image
+    ///
+    /// This is the single place where opacity is decided; every later stage
+    /// works on the layers.
+    pub fn from_image(image: &RgbaImage, thresholds: &AlphaThresholds) -> Self {
+        let (width, height) = image.dimensions();
+        Self {
+            width,
+            height,
+            layers: alpha_foreground(image, thresholds)
+                .into_iter()
+                .map(|is_leaf| if is_leaf { LEAF } else { 0 })
+                .collect(),
+        }
+    }
+    
+    /// (width, height) of the mask
+    #[inline]
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+    
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    
+    #[inline]
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    
+    /// All layer bits at (x, y)
+    #[inline]
+    pub fn get(&self, x: u32, y: u32) -> u8 {
+        self.layers[(y * self.width + x) as usize]
+    }
+    
+    /// Whether (x, y) has any of the given layer bits
+    #[inline]
+    pub fn has(&self, x: u32, y: u32, layer: u8) -> bool {
+        self.get(x, y) & layer != 0
+    }
+    
+    /// Set layer bits at (x, y)
+    #[inline]
+    pub fn set(&mut self, x: u32, y: u32, layer: u8) {
+        self.layers[(y * self.width + x) as usize] |= layer;
+    }
+    
+    /// Clear layer bits at (x, y)
+    #[inline]
+    pub fn clear(&mut self, x: u32, y: u32, layer: u8) {
+        self.layers[(y * self.width + x) as usize] &= !layer;
+    }
+    
+    /// Pixel belongs to the leaf
+    #[inline]
+    pub fn is_leaf(&self, x: u32, y: u32) -> bool {
+        self.has(x, y, LEAF)
+    }
+    
+    /// Pixel belongs to the MC leaf (leaf minus the removed components)
+    #[inline]
+    pub fn is_mc(&self, x: u32, y: u32) -> bool {
+        self.get(x, y) & (LEAF | MC_REMOVED) == LEAF
+    }
+    
+    /// Pixel is marked for EC: opened by the adaptive opening or outside the MC leaf
+    #[inline]
+    pub fn is_ec_marked(&self, x: u32, y: u32) -> bool {
+        self.is_leaf(x, y) && self.has(x, y, EC_OPENED | MC_REMOVED)
+    }
+    
+    /// Pixel is part of a Thornfiddle lobe
+    #[inline]
+    pub fn is_lobe(&self, x: u32, y: u32) -> bool {
+        self.has(x, y, LOBE)
+    }
+    
+    /// Analysis region: the whole leaf for EC, the MC leaf otherwise
+    pub fn region(&self, is_ec: bool) -> BinaryMask {
+        BinaryMask::from_fn(self.width, self.height, |x, y| {
+            if is_ec { self.is_leaf(x, y) } else { self.is_mc(x, y) }
+        })
+    }
+    
+    /// Pixels having any of the given layer bits
+    pub fn layer(&self, layer: u8) -> BinaryMask {
+        BinaryMask::from_fn(self.width, self.height, |x, y| self.has(x, y, layer))
+    }
+    
+    /// Number of pixels having any of the given layer bits
+    pub fn count(&self, layer: u8) -> usize {
+        self.layers.iter().filter(|&&bits| bits & layer != 0).count()
+    }
+}
@@ -1,21 +1,38 @@
 // src/lib.rs - Library interface for LeafComplexR
 
 pub mod config;
+pub mod connected_components;
+pub mod contour;
+pub mod efa;
+pub mod entropy;
 pub mod errors;
 pub mod feature_extraction;
+pub mod fractal;
+pub mod granulometry;
+pub mod holes;
 pub mod image_io;
 pub mod image_utils;
+pub mod leaf_mask;
+pub mod lobes;
 pub mod morphology;
+pub mod orientation;
 pub mod path_algorithms;
+pub mod petiole;
 pub mod pipeline;
 pub mod point_analysis;
+pub mod protrusion;
+pub mod rotation_diagnostics;
 pub mod output;
 pub mod thornfiddle;
 pub mod shape_analysis;
+pub mod shape_context;
+pub mod skeleton;
+pub mod teeth;
 
 // Re-export commonly used types and functions
 pub use errors::{LeafComplexError, Result};
-pub use config::Config;
+pub use config::{Config, ContourMethod, EntropyMethod, OrientationMethod, SignalResampling, SignalStart};
+pub use image_utils::{AlphaPolicy, AlphaThresholds};
 pub use pipeline::process_image;
 pub use image_io::{InputImage, load_image, save_image};
 
@@ -23,18 +40,33 @@ pub use image_io::{InputImage, load_image, save_image};
 pub use shape_analysis::{
     analyze_shape,
     analyze_shape_comprehensive,
+    analyze_shape_from_contour,
     calculate_biological_dimensions,
     calculate_biological_dimensions_fast,
+    calculate_biological_dimensions_polygon,
     calculate_bounding_box_dimensions,
     calculate_outline_count,
     calculate_outline_count_from_contour,
     calculate_circularity_from_contour,
+    calculate_circularity_from_polygon,
     calculate_area,
     calculate_circularity,
     calculate_length_width_shape_index,
     calculate_length_width_shape_index_with_shorter,
+    calculate_length_width_shape_index_from_contour,
     calculate_dynamic_opening_percentage,
     calculate_shape_index,
+    calculate_convex_hull_metrics,
+    convex_hull_indices,
+    ConvexHullMetrics,
+    ConvexityDefect,
+    calculate_feret_diameters,
+    FeretDiameters,
+    calculate_leaf_architecture,
+    lowest_contour_point,
+    LeafArchitecture,
+    calculate_shape_indices,
+    ShapeIndices,
 };
 
 // Re-export thornfiddle analysis functions
@@ -45,7 +77,6 @@ pub use thornfiddle::{
     calculate_spectral_entropy_from_harmonic_thornfiddle_path,
     
     // Entropy and complexity functions
-    calculate_approximate_entropy_from_pink_path,
     calculate_edge_feature_density,
     
     // Harmonic thornfiddle functions
@@ -63,6 +94,7 @@ pub use thornfiddle::{
     // Filtering functions
     filter_petiole_from_ec_features,
     detect_petiole_sequence,
+    detect_petiole_base,
     apply_petiole_filter,
     apply_pink_threshold_filter,
     
@@ -74,10 +106,146 @@ pub use thornfiddle::{
 // Re-export morphology functions
 pub use morphology::{
     trace_contour,
+    trace_contour_from,
+    trace_boundary,
+    ContourStart,
+    contour_start_index,
+    resample_contour_arc_length,
+    resample_signal,
     apply_opening,
     calculate_center_of_mass,
-    create_thornfiddle_image,
+    mark_opened_regions,
+    mark_lobe_regions,
     create_mc_with_com_component,
+    erode_mask,
+    dilate_mask,
+    distance_to_background,
+};
+
+// Re-export connected-component labelling
+pub use connected_components::{
+    label_components,
+    label_components_with,
+    ComponentLabels,
+    ComponentStats,
+    Connectivity,
+};
+
+// Re-export contour extraction
+pub use contour::{
+    marching_squares_contour,
+    polygon_perimeter,
+    polygon_signed_area,
+    ContourVertex,
+    LeafContour,
+};
+
+// Re-export granulometry
+pub use granulometry::{
+    calculate_pattern_spectrum,
+    granulometry_diameters,
+    PatternSpectrum,
+};
+
+// Re-export hole analysis
+pub use holes::{
+    analyze_holes,
+    find_holes,
+    mark_holes,
+    trace_inner_contour,
+    HoleMetrics,
+};
+
+// Re-export leaf mask types
+pub use leaf_mask::{
+    BinaryMask,
+    LeafMask,
+};
+
+// Re-export geodesic descriptor functions
+pub use path_algorithms::{
+    calculate_geodesic_distance_map,
+    calculate_geodesic_metrics,
+    GeodesicMetrics,
+};
+
+// Re-export inner-distance shape context functions
+pub use shape_context::{
+    calculate_inner_distance_shape_context,
+    inner_distance_shape_context_distance,
+    inner_distance_shape_context_distance_matrix,
+    InnerDistanceShapeContext,
+};
+
+// Re-export rotation diagnostics
+pub use rotation_diagnostics::{
+    rotate_image_padded,
+    run_rotation_diagnostics,
+    summarise_rotation_spread,
+    RotationMetricSpread,
+    DEFAULT_ROTATION_ANGLES,
+};
+
+// Re-export skeleton extraction
+pub use skeleton::{
+    extract_skeleton,
+    zhang_suen_thinning,
+    Skeleton,
+};
+
+// Re-export orientation normalisation
+pub use orientation::{
+    detect_leaf_axis,
+    normalize_orientation,
+    OrientationTransform,
+};
+
+// Re-export petiole detection
+pub use petiole::{
+    detect_petiole,
+    mark_petiole,
+    Petiole,
+};
+
+// Re-export entropy estimators
+pub use entropy::{
+    calculate_approximate_entropy,
+    calculate_entropy_metrics,
+    calculate_multiscale_entropy,
+    calculate_permutation_entropy,
+    calculate_sample_entropy,
+    EntropyMetrics,
+};
+
+// Re-export elliptic Fourier analysis
+pub use efa::{
+    elliptic_fourier,
+    normalize_elliptic_fourier,
+    EllipticFourier,
+};
+
+// Re-export fractal dimension estimators
+pub use fractal::{
+    calculate_box_counting_dimension,
+    calculate_fractal_dimensions,
+    calculate_minkowski_dimension,
+    fractal_scales,
+    FractalFit,
+    FractalMetrics,
+};
+
+// Re-export lobe segmentation
+pub use lobes::{
+    segment_lobes,
+    Lobe,
+    LobeMetrics,
+};
+
+// Re-export tooth detection
+pub use teeth::{
+    detect_teeth,
+    TeethMetrics,
+    Tooth,
 };
 
 // Re-export point analysis functions
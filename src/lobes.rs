@@ -0,0 +1,174 @@
+// src/lobes.rs - Explicit lobe segmentation from the Thornfiddle golden regions
+
+use crate::connected_components::{label_components, ComponentLabels};
+use crate::leaf_mask::{BinaryMask, LeafMask};
+use crate::protrusion::{deepest_sinus, measure_protrusion};
+
+/// One lobe (a component of the Thornfiddle golden region)
+#[derive(Debug, Clone)]
+pub struct Lobe {
+    /// First MC contour index covered by the lobe
+    pub start_index: usize,
+    /// Last MC contour index covered by the lobe (may wrap past the end of the contour)
+    pub end_index: usize,
+    /// Lobe pixel on the margin farthest from the base line
+    pub apex: (u32, u32),
+    /// Ends of the base line (where the lobe meets the opened blade)
+    pub base: ((u32, u32), (u32, u32)),
+    /// Deepest margin point between this lobe and the next one (None for a single lobe)
+    pub sinus: Option<(u32, u32)>,
+    /// Distance from the sinus to the line joining this apex and the next one
+    pub sinus_depth: f64,
+    /// Distance from the apex to the base line
+    pub length: f64,
+    /// Length of the base line
+    pub width: f64,
+    /// Number of lobe pixels
+    pub area: usize,
+}
+
+/// Lobes of a leaf with per-leaf aggregates
+#[derive(Debug, Clone, Default)]
+pub struct LobeMetrics {
+    /// Lobes in contour order
+    pub lobes: Vec<Lobe>,
+    /// Total lobe area divided by the MC area
+    pub area_fraction: f64,
+    /// Mean lobe area
+    pub mean_area: f64,
+    /// Median lobe area
+    pub median_area: f64,
+    /// Largest lobe area
+    pub max_area: usize,
+    /// Coefficient of variation of the lobe areas (0 for fewer than two lobes)
+    pub area_cv: f64,
+    /// Mean sinus depth
+    pub mean_sinus_depth: f64,
+}
+
+/// Shortest cyclic run of contour indices covering all given indices
+///
+/// # Returns
+/// (start, end) with `end` possibly past the contour end (`end - start < n`)
+fn contour_span(mut indices: Vec<usize>, n: usize) -> (usize, usize) {
+    indices.sort_unstable();
+    
+    // The span starts right after the largest gap between consecutive indices
+    let mut start_position = 0;
+    let mut largest_gap = 0;
+    for i in 0..indices.len() {
+        let next = indices[(i + 1) % indices.len()];
+        let gap = (next + n - indices[i]) % n;
+        if gap > largest_gap {
+            largest_gap = gap;
+            start_position = (i + 1) % indices.len();
+        }
+    }
+    
+    let start = indices[start_position];
+    let end = start + (n - largest_gap) % n;
+    (start, end)
+}
+
+/// Measure one lobe component
+fn measure_lobe(
+    labels: &ComponentLabels,
+    label: u32,
+    area: usize,
+    blade: &BinaryMask,
+    contour: &[(u32, u32)],
+) -> Option<(Lobe, usize)> {
+    let protrusion = measure_protrusion(labels, label, blade, contour)?;
+    let (start_index, end_index) = contour_span(protrusion.contour_indices, contour.len());
+    
+    let lobe = Lobe {
+        start_index,
+        end_index,
+        apex: contour[protrusion.apex_index],
+        base: protrusion.base,
+        sinus: None,
+        sinus_depth: 0.0,
+        length: protrusion.height,
+        width: protrusion.width,
+        area,
+    };
+    
+    Some((lobe, protrusion.apex_index))
+}
+
+/// Segment the Thornfiddle golden region into individual lobes
+///
+/// Every component of the `LOBE` layer is a lobe. As for teeth, its base is
+/// the line between the farthest pair of pixels where it meets the opened
+/// blade and its length is the distance from that line to the farthest
+/// margin pixel (apex). The contour span is the shortest run of MC contour
+/// points covering the lobe. The sinus after a lobe is the margin point
+/// deepest below the chord to the next apex; the sinus depth is its distance
+/// to that chord.
+///
+/// # Arguments
+/// * `mask` - Leaf mask with the `LOBE` layer marked
+/// * `contour` - MC contour pixels
+/// * `min_area_percentage` - Smallest lobe as a percentage of the MC area (smaller golden residues are noise)
+///
+/// # Returns
+/// Lobes in contour order with per-leaf aggregates
+pub fn segment_lobes(mask: &LeafMask, contour: &[(u32, u32)], min_area_percentage: f64) -> LobeMetrics {
+    let (width, height) = mask.dimensions();
+    let mc_area = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| mask.is_mc(x, y))
+        .count();
+    let min_area = ((min_area_percentage / 100.0 * mc_area as f64).ceil() as usize).max(1);
+    
+    let golden = BinaryMask::from_fn(width, height, |x, y| mask.is_lobe(x, y));
+    let blade = BinaryMask::from_fn(width, height, |x, y| mask.is_mc(x, y) && !mask.is_lobe(x, y));
+    let labels = label_components(&golden);
+    
+    let mut measured: Vec<(Lobe, usize)> = labels.stats.iter()
+        .filter(|stats| stats.area >= min_area)
+        .filter_map(|stats| measure_lobe(&labels, stats.label, stats.area, &blade, contour))
+        .collect();
+    measured.sort_by_key(|(lobe, _)| lobe.start_index);
+    
+    let count = measured.len();
+    if count > 1 {
+        for i in 0..count {
+            let (start, end) = (measured[i].1, measured[(i + 1) % count].1);
+            let sinus = deepest_sinus(contour, start, end);
+            measured[i].0.sinus = sinus.map(|(point, _)| point);
+            measured[i].0.sinus_depth = sinus.map_or(0.0, |(_, depth)| depth);
+        }
+    }
+    let lobes: Vec<Lobe> = measured.into_iter().map(|(lobe, _)| lobe).collect();
+    
+    if lobes.is_empty() {
+        return LobeMetrics::default();
+    }
+    
+    let mut areas: Vec<usize> = lobes.iter().map(|lobe| lobe.area).collect();
+    areas.sort_unstable();
+    let total: usize = areas.iter().sum();
+    let mean_area = total as f64 / count as f64;
+    let median_area = if count % 2 == 1 {
+        areas[count / 2] as f64
+    } else {
+        (areas[count / 2 - 1] + areas[count / 2]) as f64 / 2.0
+    };
+    let area_cv = if count > 1 {
+        let variance = areas.iter().map(|&a| (a as f64 - mean_area).powi(2)).sum::<f64>() / (count - 1) as f64;
+        variance.sqrt() / mean_area
+    } else {
+        0.0
+    };
+    
+    LobeMetrics {
+        area_fraction: if mc_area > 0 { total as f64 / mc_area as f64 } else { 0.0 },
+        mean_area,
+        median_area,
+        max_area: areas[count - 1],
+        area_cv,
+        mean_sinus_depth: lobes.iter().map(|lobe| lobe.sinus_depth).sum::<f64>() / count as f64,
+        lobes,
+    }
+}
@@ -1,17 +1,33 @@
 // src/main.rs - Main entry point for LeafComplexR CLI
 
 mod config;
+mod connected_components;
+mod contour;
+mod efa;
+mod entropy;
 mod errors;
 mod feature_extraction;
+mod fractal;
+mod granulometry;
+mod holes;
 mod image_io;
 mod image_utils;
+mod leaf_mask;
+mod lobes;
 mod morphology;
+mod orientation;
 mod output;
 mod path_algorithms;
+mod petiole;
 mod pipeline;
 mod point_analysis;
+mod protrusion;
+mod rotation_diagnostics;
 mod thornfiddle;
 mod shape_analysis;
+mod shape_context;
+mod skeleton;
+mod teeth;
 
 use std::path::PathBuf;
 use std::time::Instant;
@@ -23,6 +39,7 @@ use config::Config;
 use errors::{LeafComplexError, Result};
 use image_io::{get_png_files_in_dir, load_image};
 use pipeline::process_image;
+use rotation_diagnostics::{run_rotation_diagnostics, DEFAULT_ROTATION_ANGLES};
 
 /// Command-line arguments for LeafComplexR
 #[derive(Parser, Debug)]
@@ -47,6 +64,14 @@ struct Args {
     /// Enable debug mode (save intermediate images and print more info)
     #[clap(short, long)]
     debug: bool,
+    
+    /// Analyse rotated copies of each image and report the variance of every summary metric
+    #[clap(long)]
+    rotation_diagnostics: bool,
+    
+    /// Clockwise rotation angles in degrees for rotation diagnostics (comma separated)
+    #[clap(long, value_delimiter = ',')]
+    rotation_angles: Vec<f64>,
 }
 
 /// Reference point argument for CLI
@@ -100,7 +125,28 @@ fn main() -> Result<()> {
     // Process input
     let input_path = PathBuf::from(&config.input_path);
     
-    if input_path.is_file() {
+    if args.rotation_diagnostics {
+        let angles = if args.rotation_angles.is_empty() {
+            DEFAULT_ROTATION_ANGLES.to_vec()
+        } else {
+            args.rotation_angles.clone()
+        };
+        
+        let png_files = if input_path.is_file() {
+            vec![input_path.clone()]
+        } else if input_path.is_dir() {
+            get_png_files_in_dir(&input_path)?
+        } else {
+            return Err(LeafComplexError::InvalidPath(input_path));
+        };
+        
+        // Sequential: every image appends to the same per-angle summaries
+        for path in &png_files {
+            println!("Rotation diagnostics: {}", path.display());
+            let input_image = load_image(path)?;
+            run_rotation_diagnostics(input_image, &config, &angles, args.debug)?;
+        }
+    } else if input_path.is_file() {
         // Process single file
         println!("Processing single file: {}", input_path.display());
         let input_image = load_image(&input_path)?;
@@ -1,614 +1,358 @@
-use image::{ImageBuffer, Rgba, RgbaImage};
-use std::cmp::{max, min};
-use std::collections::{HashMap, VecDeque};
-
+use crate::connected_components::label_components;
 use crate::errors::{LeafComplexError, Result};
-use crate::image_utils::{create_circular_kernel, in_bounds, has_rgb_color, ALPHA_THRESHOLD};
+use crate::leaf_mask::{BinaryMask, LeafMask, EC_OPENED, LOBE, MC_REMOVED};
 
-pub fn erode_alpha(
-    image: &RgbaImage,
-    kernel: &ImageBuffer<Rgba<u8>, Vec<u8>>,
-) -> RgbaImage {
-    let (width, height) = image.dimensions();
-    let (k_width, k_height) = kernel.dimensions();
-    let k_radius_x = (k_width / 2) as i32;
-    let k_radius_y = (k_height / 2) as i32;
-    
-    let mut result = RgbaImage::new(width, height);
-    
-    for y in 0..height {
-        for x in 0..width {
-            let mut min_alpha = 255u8;
-            let mut erode = false;
-            
-            // Check if any kernel pixel is outside the object
-            for ky in 0..k_height {
-                for kx in 0..k_width {
-                    if kernel.get_pixel(kx, ky)[0] > 0 {
-                        let img_x = x as i32 + (kx as i32) - k_radius_x;
-                        let img_y = y as i32 + (ky as i32) - k_radius_y;
-                        
-                        if !in_bounds(img_x, img_y, width, height) {
-                            // Consider out-of-bounds as transparent
-                            min_alpha = 0;
-                            erode = true;
-                            break;
-                        }
-                        
-                        let img_alpha = image.get_pixel(img_x as u32, img_y as u32)[3];
-                        min_alpha = min(min_alpha, img_alpha);
-                        
-                        if img_alpha < ALPHA_THRESHOLD {
-                            erode = true;
-                            break;
-                        }
-                    }
-                }
-                if erode {
-                    break;
-                }
-            }
-            
-            // Copy RGB from original, but use eroded alpha
-            let original = image.get_pixel(x, y);
-            result.put_pixel(
-                x, 
-                y, 
-                Rgba([original[0], original[1], original[2], if erode { 0 } else { original[3] }])
-            );
-        }
+/// Squared-distance offset and radius of the circular kernel of a given diameter
+///
+/// Mirrors `create_circular_kernel`: odd diameters are centred on the anchor
+/// pixel, even diameters are centred half a pixel up-left of it.
+/// Returns (sample offset, squared radius).
+fn circular_kernel_geometry(diameter: u32) -> (f64, f64) {
+    if diameter % 2 == 1 {
+        (0.0, (((diameter - 1) / 2) as f64).powi(2))
+    } else {
+        (-0.5, ((diameter / 2) as f64).powi(2))
     }
-    
-    result
 }
 
-/// Applies morphological dilation to the alpha channel
-pub fn dilate_alpha(
-    image: &RgbaImage,
-    kernel: &ImageBuffer<Rgba<u8>, Vec<u8>>,
-) -> RgbaImage {
-    let (width, height) = image.dimensions();
-    let (k_width, k_height) = kernel.dimensions();
-    let k_radius_x = (k_width / 2) as i32;
-    let k_radius_y = (k_height / 2) as i32;
-    
-    let mut result = RgbaImage::new(width, height);
-    
-    for y in 0..height {
-        for x in 0..width {
-            let mut max_alpha = 0u8;
-            let mut dilate = false;
+/// One-dimensional squared distance transform (Felzenszwalb–Huttenlocher)
+///
+/// Computes `out[i] = min_q (i + offset - q)² + f[q]` in linear time from the
+/// lower envelope of parabolas rooted at finite samples of `f`.
+fn distance_transform_1d(f: &[f64], offset: f64, out: &mut [f64]) {
+    let n = f.len();
+    let mut v: Vec<usize> = Vec::with_capacity(n);
+    let mut z: Vec<f64> = Vec::with_capacity(n + 1);
+    
+    for q in 0..n {
+        if !f[q].is_finite() {
+            continue;
+        }
+        
+        if v.is_empty() {
+            v.push(q);
+            z.push(f64::NEG_INFINITY);
+            z.push(f64::INFINITY);
+            continue;
+        }
+        
+        let qf = q as f64;
+        loop {
+            let p = v[v.len() - 1];
+            let pf = p as f64;
+            let s = ((f[q] + qf * qf) - (f[p] + pf * pf)) / (2.0 * qf - 2.0 * pf);
             
-            // Check if any kernel pixel overlaps with a non-transparent pixel
-            for ky in 0..k_height {
-                for kx in 0..k_width {
-                    if kernel.get_pixel(kx, ky)[0] > 0 {
-                        let img_x = x as i32 + (kx as i32) - k_radius_x;
-                        let img_y = y as i32 + (ky as i32) - k_radius_y;
-                        
-                        if in_bounds(img_x, img_y, width, height) {
-                            let img_alpha = image.get_pixel(img_x as u32, img_y as u32)[3];
-                            max_alpha = max(max_alpha, img_alpha);
-                            
-                            if img_alpha >= ALPHA_THRESHOLD {
-                                dilate = true;
-                                break;
-                            }
-                        }
-                    }
-                }
-                if dilate {
-                    break;
-                }
+            if s <= z[v.len() - 1] {
+                v.pop();
+                z.pop();
+            } else {
+                let last = z.len() - 1;
+                z[last] = s;
+                v.push(q);
+                z.push(f64::INFINITY);
+                break;
             }
-            
-            // Copy RGB from original, but use dilated alpha
-            let original = image.get_pixel(x, y);
-            result.put_pixel(
-                x, 
-                y, 
-                Rgba([original[0], original[1], original[2], if dilate { original[3].max(1) } else { original[3] }])
-            );
         }
     }
     
-    result
-}
-
-/// Apply morphological opening (erosion followed by dilation)
-pub fn apply_opening(
-    image: &RgbaImage, 
-    kernel_size: u32
-) -> Result<RgbaImage> {
-    if kernel_size == 0 {
-        return Err(LeafComplexError::Morphology(
-            "Kernel size must be greater than 0".to_string()
-        ));
+    if v.is_empty() {
+        out.iter_mut().for_each(|value| *value = f64::INFINITY);
+        return;
     }
     
-    // Create circular kernel once
-    let kernel = create_circular_kernel(kernel_size);
-    
-    // Pre-compute kernel properties
-    let (k_width, k_height) = kernel.dimensions();
-    let k_radius_x = (k_width / 2) as i32;
-    let k_radius_y = (k_height / 2) as i32;
-    
-    // Create kernel lookup for faster access
-    let mut kernel_pixels = vec![false; (k_width * k_height) as usize];
-    for ky in 0..k_height {
-        for kx in 0..k_width {
-            if kernel.get_pixel(kx, ky)[0] > 0 {
-                kernel_pixels[(ky * k_width + kx) as usize] = true;
-            }
+    let mut k = 0;
+    for (i, value) in out.iter_mut().enumerate() {
+        let x = i as f64 + offset;
+        while z[k + 1] < x {
+            k += 1;
         }
+        let dx = x - v[k] as f64;
+        *value = dx * dx + f[v[k]];
     }
-    
-    // Image properties
-    let (width, height) = image.dimensions();
-    
-    // Apply erosion - using a non-parallel implementation first to fix the issues
-    let mut eroded = RgbaImage::new(width, height);
-    for y in 0..height {
-        for x in 0..width {
-            let original = image.get_pixel(x, y);
-            
-            // Skip transparent pixels - they stay transparent
-            if original[3] < ALPHA_THRESHOLD {
-                eroded.put_pixel(x, y, *original);
-                continue;
-            }
-            
-            let mut erode = false;
-            // Check kernel
-            'kernel_check: for ky in 0..k_height {
-                for kx in 0..k_width {
-                    if kernel_pixels[(ky * k_width + kx) as usize] {
-                        let img_x = x as i32 + (kx as i32) - k_radius_x;
-                        let img_y = y as i32 + (ky as i32) - k_radius_y;
-                        
-                        if img_x < 0 || img_y < 0 || img_x >= width as i32 || img_y >= height as i32 {
-                            erode = true;
-                            break 'kernel_check;
-                        }
-                        
-                        let img_alpha = image.get_pixel(img_x as u32, img_y as u32)[3];
-                        if img_alpha < ALPHA_THRESHOLD {
-                            erode = true;
-                            break 'kernel_check;
-                        }
-                    }
-                }
-            }
-            
-            let new_pixel = if erode {
-                Rgba([original[0], original[1], original[2], 0])
-            } else {
-                *original
-            };
-            eroded.put_pixel(x, y, new_pixel);
+}
+
+/// Exact squared Euclidean distance transform of a binary image
+///
+/// For every pixel (sampled at `pixel + offset` in both axes) returns the
+/// squared distance to the nearest seed pixel, or infinity if there are no seeds.
+/// Separable column/row passes, O(width * height).
+fn squared_distance_transform(seeds: &[bool], width: u32, height: u32, offset: f64) -> Vec<f64> {
+    let (w, h) = (width as usize, height as usize);
+    let mut column_pass = vec![f64::INFINITY; w * h];
+    
+    let mut f = vec![0.0; h];
+    let mut out = vec![0.0; h];
+    for x in 0..w {
+        for y in 0..h {
+            f[y] = if seeds[y * w + x] { 0.0 } else { f64::INFINITY };
+        }
+        distance_transform_1d(&f, offset, &mut out);
+        for y in 0..h {
+            column_pass[y * w + x] = out[y];
         }
     }
     
-    // Apply dilation
-    let mut dilated = RgbaImage::new(width, height);
-    for y in 0..height {
-        for x in 0..width {
-            let original = eroded.get_pixel(x, y);
-            
-            let mut dilate = false;
-            // Check kernel
-            'kernel_check: for ky in 0..k_height {
-                for kx in 0..k_width {
-                    if kernel_pixels[(ky * k_width + kx) as usize] {
-                        let img_x = x as i32 + (kx as i32) - k_radius_x;
-                        let img_y = y as i32 + (ky as i32) - k_radius_y;
-                        
-                        if img_x >= 0 && img_y >= 0 && img_x < width as i32 && img_y < height as i32 {
-                            let img_alpha = eroded.get_pixel(img_x as u32, img_y as u32)[3];
-                            if img_alpha >= ALPHA_THRESHOLD {
-                                dilate = true;
-                                break 'kernel_check;
-                            }
-                        }
-                    }
-                }
-            }
-            
-            let new_pixel = if dilate {
-                Rgba([original[0], original[1], original[2], original[3].max(1)])
-            } else {
-                *original
-            };
-            dilated.put_pixel(x, y, new_pixel);
-        }
+    let mut distances = vec![f64::INFINITY; w * h];
+    let mut out = vec![0.0; w];
+    for y in 0..h {
+        distance_transform_1d(&column_pass[y * w..(y + 1) * w], offset, &mut out);
+        distances[y * w..(y + 1) * w].copy_from_slice(&out);
     }
     
-    Ok(dilated)
+    distances
 }
 
-pub fn mark_opened_regions(
-    original: &RgbaImage, 
-    opened: &RgbaImage, 
-    color: [u8; 3]
-) -> RgbaImage {
-    let (width, height) = original.dimensions();
-    let mut marked = original.clone();
-    
-    // Mark pixels that were non-transparent in original but transparent in opened
+/// Squared distance from every pixel to the nearest background pixel
+///
+/// Out-of-bounds pixels count as background (one-pixel padded border);
+/// background pixels get 0.
+fn squared_distance_to_background(foreground: &[bool], width: u32, height: u32, offset: f64) -> Vec<f64> {
+    let (padded_width, padded_height) = (width + 2, height + 2);
+    let mut background = vec![true; (padded_width * padded_height) as usize];
     for y in 0..height {
         for x in 0..width {
-            let orig_pixel = original.get_pixel(x, y);
-            let opened_pixel = opened.get_pixel(x, y);
-            
-            // If pixel was originally non-transparent but is transparent after opening
-            if orig_pixel[3] > 0 && opened_pixel[3] == 0 {
-                // Mark it with the specified color
-                marked.put_pixel(x, y, Rgba([color[0], color[1], color[2], orig_pixel[3]]));
-            }
+            let padded_index = ((y + 1) * padded_width + x + 1) as usize;
+            background[padded_index] = !foreground[(y * width + x) as usize];
         }
     }
-        
-    marked
-}
-
-/// NEW: Create Thornfiddle Image with golden lobe regions
-/// Takes LMC image as base, applies aggressive opening, marks removed regions as golden
-pub fn create_thornfiddle_image(
-    lmc_image: &RgbaImage,
-    dynamic_kernel_size: u32,
-    golden_color: [u8; 3],
-) -> Result<RgbaImage> {
-    let (width, height) = lmc_image.dimensions();
-    
-    // Ensure minimum kernel size of 1
-    let aggressive_size = dynamic_kernel_size.max(1);
     
-    println!("Creating Thornfiddle image with DYNAMIC kernel size: {} pixels (based on LMC SHORTER dimension)", 
-             aggressive_size);
-    
-    // Apply aggressive opening to LMC image
-    let aggressively_opened = apply_opening(lmc_image, aggressive_size)?;
+    let distances = squared_distance_transform(&background, padded_width, padded_height, offset);
     
-    // Create Thornfiddle image: LMC base + golden overlays for removed regions
-    let mut thornfiddle_image = lmc_image.clone();
-    
-    // Mark pixels that were non-transparent in LMC but transparent after aggressive opening
-    let mut golden_pixel_count = 0;
+    let mut unpadded = vec![0.0; (width * height) as usize];
     for y in 0..height {
         for x in 0..width {
-            let lmc_pixel = lmc_image.get_pixel(x, y);
-            let opened_pixel = aggressively_opened.get_pixel(x, y);
-            
-            // If pixel was originally non-transparent in LMC but is transparent after aggressive opening
-            if lmc_pixel[3] > 0 && opened_pixel[3] == 0 {
-                // Mark it with golden color (lobe region)
-                thornfiddle_image.put_pixel(x, y, Rgba([golden_color[0], golden_color[1], golden_color[2], lmc_pixel[3]]));
-                golden_pixel_count += 1;
-            }
+            let padded_index = ((y + 1) * padded_width + x + 1) as usize;
+            unpadded[(y * width + x) as usize] = distances[padded_index];
         }
     }
     
-    println!("Thornfiddle image created with {} golden lobe pixels using dynamic kernel size", golden_pixel_count);
-    
-    Ok(thornfiddle_image)
+    unpadded
 }
 
-/// Find all connected components in an image
-/// Returns a vector of component sizes and a map of pixel coordinates to component IDs
-fn find_connected_components(image: &RgbaImage, pink_color: [u8; 3]) -> (Vec<usize>, HashMap<(u32, u32), u32>) {
-    let (width, height) = image.dimensions();
-    let mut visited = vec![false; (width * height) as usize];
-    let mut component_map = HashMap::new();
-    let mut component_sizes = Vec::new();
-    let mut component_id = 0u32;
-    
-    // 8-connected neighbors
-    let directions = [
-        (0, 1), (1, 0), (0, -1), (-1, 0), (1, 1), (1, -1), (-1, 1), (-1, -1)
-    ];
-    
-    for y in 0..height {
-        for x in 0..width {
-            let idx = (y * width + x) as usize;
-            
-            if visited[idx] {
-                continue;
-            }
-            
-            let pixel = image.get_pixel(x, y);
-            
-            // Check if this pixel should be considered (non-transparent and not pink)
-            if pixel[3] > 0 && !has_rgb_color(pixel, pink_color) {
-                // Start a new connected component
-                let mut queue = VecDeque::new();
-                let mut component_pixels = Vec::new();
-                
-                queue.push_back((x, y));
-                visited[idx] = true;
-                
-                // BFS to find all connected pixels
-                while let Some((cx, cy)) = queue.pop_front() {
-                    component_pixels.push((cx, cy));
-                    component_map.insert((cx, cy), component_id);
-                    
-                    // Check all 8 neighbors
-                    for &(dx, dy) in &directions {
-                        let nx = cx as i32 + dx;
-                        let ny = cy as i32 + dy;
-                        
-                        if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
-                            continue;
-                        }
-                        
-                        let nx = nx as u32;
-                        let ny = ny as u32;
-                        let nidx = (ny * width + nx) as usize;
-                        
-                        if visited[nidx] {
-                            continue;
-                        }
-                        
-                        let npixel = image.get_pixel(nx, ny);
-                        if npixel[3] > 0 && !has_rgb_color(npixel, pink_color) {
-                            queue.push_back((nx, ny));
-                            visited[nidx] = true;
-                        }
-                    }
-                }
-                
-                component_sizes.push(component_pixels.len());
-                component_id += 1;
-            }
-        }
-    }
-    
-    (component_sizes, component_map)
+/// Binary erosion with a circular kernel via the distance transform
+///
+/// A pixel survives if no background pixel (out-of-bounds counts as
+/// background) lies under the kernel. Identical to scanning the kernel from
+/// `create_circular_kernel(diameter)` pixel by pixel.
+fn erode_mask_circular(foreground: &[bool], width: u32, height: u32, diameter: u32) -> Vec<bool> {
+    let (offset, radius_sq) = circular_kernel_geometry(diameter);
+    
+    squared_distance_to_background(foreground, width, height, offset)
+        .into_iter()
+        .map(|distance| distance > radius_sq)
+        .collect()
 }
 
-/// Remove small connected components based on size threshold
-fn filter_small_components(
-    image: &RgbaImage, 
-    component_sizes: &[usize], 
-    component_map: &HashMap<(u32, u32), u32>,
-    min_size_threshold: usize
-) -> RgbaImage {
-    let (width, height) = image.dimensions();
-    let mut filtered_image = RgbaImage::new(width, height);
-    
-    // Initialize with transparent pixels
-    for y in 0..height {
-        for x in 0..width {
-            filtered_image.put_pixel(x, y, Rgba([0, 0, 0, 0]));
-        }
-    }
-    
-    // Copy pixels from components that are large enough
-    for y in 0..height {
-        for x in 0..width {
-            if let Some(&component_id) = component_map.get(&(x, y)) {
-                let component_size = component_sizes[component_id as usize];
-                
-                if component_size >= min_size_threshold {
-                    // Keep this pixel
-                    filtered_image.put_pixel(x, y, *image.get_pixel(x, y));
-                }
-            }
-        }
+/// Binary dilation with a circular kernel via the distance transform
+///
+/// A pixel is set if any foreground pixel lies under the kernel. Identical to
+/// scanning the kernel from `create_circular_kernel(diameter)` pixel by pixel.
+fn dilate_mask_circular(foreground: &[bool], width: u32, height: u32, diameter: u32) -> Vec<bool> {
+    let (offset, radius_sq) = circular_kernel_geometry(diameter);
+    
+    squared_distance_transform(foreground, width, height, offset)
+        .into_iter()
+        .map(|distance| distance <= radius_sq)
+        .collect()
+}
+
+/// Erode a mask with a circular kernel of the given diameter
+///
+/// Runs in O(width * height) regardless of kernel size.
+pub fn erode_mask(mask: &BinaryMask, diameter: u32) -> BinaryMask {
+    if diameter == 0 {
+        return mask.clone();
     }
     
-    filtered_image
+    let (width, height) = mask.dimensions();
+    BinaryMask::from_vec(width, height, erode_mask_circular(mask.as_slice(), width, height, diameter))
 }
 
-/// Keep only the largest connected component
-fn keep_largest_component(
-    image: &RgbaImage, 
-    component_sizes: &[usize], 
-    component_map: &HashMap<(u32, u32), u32>
-) -> RgbaImage {
-    let (width, height) = image.dimensions();
-    let mut filtered_image = RgbaImage::new(width, height);
-    
-    // Initialize with transparent pixels
-    for y in 0..height {
-        for x in 0..width {
-            filtered_image.put_pixel(x, y, Rgba([0, 0, 0, 0]));
-        }
+/// Dilate a mask with a circular kernel of the given diameter
+///
+/// Runs in O(width * height) regardless of kernel size.
+pub fn dilate_mask(mask: &BinaryMask, diameter: u32) -> BinaryMask {
+    if diameter == 0 {
+        return mask.clone();
     }
     
-    // Find the largest component
-    if let Some((largest_component_id, _)) = component_sizes.iter()
-        .enumerate()
-        .max_by_key(|(_, &size)| size) {
-        
-        // Copy pixels from the largest component only
-        for y in 0..height {
-            for x in 0..width {
-                if let Some(&component_id) = component_map.get(&(x, y)) {
-                    if component_id == largest_component_id as u32 {
-                        filtered_image.put_pixel(x, y, *image.get_pixel(x, y));
-                    }
-                }
-            }
-        }
+    let (width, height) = mask.dimensions();
+    BinaryMask::from_vec(width, height, dilate_mask_circular(mask.as_slice(), width, height, diameter))
+}
+
+/// Euclidean distance from every pixel to the nearest background pixel
+///
+/// Out-of-bounds pixels count as background, so the value of a region pixel
+/// is the radius of the largest disc centred on it that fits in the region.
+/// Background pixels get 0. Runs in O(width * height).
+///
+/// # Returns
+/// Row-major distances
+pub fn distance_to_background(region: &BinaryMask) -> Vec<f64> {
+    let (width, height) = region.dimensions();
+    
+    squared_distance_to_background(region.as_slice(), width, height, 0.0)
+        .into_iter()
+        .map(f64::sqrt)
+        .collect()
+}
+
+/// Apply morphological opening (erosion followed by dilation)
+///
+/// Uses the exact Euclidean distance transform, so the cost is independent of
+/// the kernel size.
+pub fn apply_opening(
+    mask: &BinaryMask, 
+    kernel_size: u32
+) -> Result<BinaryMask> {
+    if kernel_size == 0 {
+        return Err(LeafComplexError::Morphology(
+            "Kernel size must be greater than 0".to_string()
+        ));
     }
     
-    filtered_image
+    Ok(dilate_mask(&erode_mask(mask, kernel_size), kernel_size))
 }
 
-/// Apply additional morphological cleaning to remove thin connections and shells
-fn clean_thin_artifacts(image: &RgbaImage, pink_color: [u8; 3]) -> RgbaImage {
-    let (width, height) = image.dimensions();
-    
-    // Step 1: Apply a small erosion to break thin connections (2-pixel radius)
-    let small_kernel = create_circular_kernel(3); // 3x3 kernel to break 1-2 pixel connections
-    let mut eroded = RgbaImage::new(width, height);
-    
-    let (k_width, k_height) = small_kernel.dimensions();
-    let k_radius_x = (k_width / 2) as i32;
-    let k_radius_y = (k_height / 2) as i32;
-    
-    // Create kernel lookup
-    let mut kernel_pixels = vec![false; (k_width * k_height) as usize];
-    for ky in 0..k_height {
-        for kx in 0..k_width {
-            if small_kernel.get_pixel(kx, ky)[0] > 0 {
-                kernel_pixels[(ky * k_width + kx) as usize] = true;
-            }
-        }
-    }
+/// Mark leaf pixels removed by the opening in the `EC_OPENED` layer
+///
+/// # Returns
+/// Number of marked pixels
+pub fn mark_opened_regions(
+    mask: &mut LeafMask, 
+    opened: &BinaryMask
+) -> usize {
+    let (width, height) = mask.dimensions();
+    let mut marked_count = 0;
     
-    // Apply erosion
+    // Mark pixels that belong to the leaf but not to the opened leaf
     for y in 0..height {
         for x in 0..width {
-            let original = image.get_pixel(x, y);
-            
-            if original[3] < ALPHA_THRESHOLD || has_rgb_color(original, pink_color) {
-                eroded.put_pixel(x, y, Rgba([0, 0, 0, 0]));
-                continue;
-            }
-            
-            let mut erode = false;
-            'kernel_check: for ky in 0..k_height {
-                for kx in 0..k_width {
-                    if kernel_pixels[(ky * k_width + kx) as usize] {
-                        let img_x = x as i32 + (kx as i32) - k_radius_x;
-                        let img_y = y as i32 + (ky as i32) - k_radius_y;
-                        
-                        if img_x < 0 || img_y < 0 || img_x >= width as i32 || img_y >= height as i32 {
-                            erode = true;
-                            break 'kernel_check;
-                        }
-                        
-                        let check_pixel = image.get_pixel(img_x as u32, img_y as u32);
-                        if check_pixel[3] < ALPHA_THRESHOLD || has_rgb_color(check_pixel, pink_color) {
-                            erode = true;
-                            break 'kernel_check;
-                        }
-                    }
-                }
-            }
-            
-            if erode {
-                eroded.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+            if mask.is_leaf(x, y) && !opened.get(x, y) {
+                mask.set(x, y, EC_OPENED);
+                marked_count += 1;
             } else {
-                eroded.put_pixel(x, y, *original);
+                mask.clear(x, y, EC_OPENED);
             }
         }
     }
+        
+    marked_count
+}
+
+/// Mark the Thornfiddle lobe regions in the `LOBE` layer
+///
+/// Applies an aggressive opening to the MC leaf; MC pixels removed by it are the lobes.
+///
+/// # Returns
+/// Number of lobe pixels
+pub fn mark_lobe_regions(
+    mask: &mut LeafMask,
+    dynamic_kernel_size: u32,
+) -> Result<usize> {
+    let (width, height) = mask.dimensions();
     
-    // Step 2: Find connected components after erosion
-    let (component_sizes, component_map) = find_connected_components(&eroded, pink_color);
+    // Ensure minimum kernel size of 1
+    let aggressive_size = dynamic_kernel_size.max(1);
     
-    // Step 3: Keep only the largest component
-    let largest_only = keep_largest_component(&eroded, &component_sizes, &component_map);
+    println!("Creating Thornfiddle lobes with DYNAMIC kernel size: {} pixels (based on LMC SHORTER dimension)", 
+             aggressive_size);
     
-    // Step 4: Apply a small dilation to restore size (1-pixel radius)
-    let _restore_kernel = create_circular_kernel(3);
-    let mut dilated = RgbaImage::new(width, height);
+    // Apply aggressive opening to the MC leaf
+    let aggressively_opened = apply_opening(&mask.region(false), aggressive_size)?;
     
+    // Mark pixels that belong to the MC leaf but not to the aggressively opened leaf
+    let mut golden_pixel_count = 0;
     for y in 0..height {
         for x in 0..width {
-            let mut dilate = false;
-            
-            'kernel_check: for ky in 0..k_height {
-                for kx in 0..k_width {
-                    if kernel_pixels[(ky * k_width + kx) as usize] {
-                        let img_x = x as i32 + (kx as i32) - k_radius_x;
-                        let img_y = y as i32 + (ky as i32) - k_radius_y;
-                        
-                        if img_x >= 0 && img_y >= 0 && img_x < width as i32 && img_y < height as i32 {
-                            let check_pixel = largest_only.get_pixel(img_x as u32, img_y as u32);
-                            if check_pixel[3] >= ALPHA_THRESHOLD && !has_rgb_color(check_pixel, pink_color) {
-                                dilate = true;
-                                break 'kernel_check;
-                            }
-                        }
-                    }
-                }
-            }
-            
-            if dilate {
-                // Use the original pixel color from the input image
-                let orig_pixel = image.get_pixel(x, y);
-                if orig_pixel[3] > 0 && !has_rgb_color(orig_pixel, pink_color) {
-                    dilated.put_pixel(x, y, *orig_pixel);
-                } else {
-                    dilated.put_pixel(x, y, Rgba([128, 128, 128, 255])); // Fallback gray
-                }
+            if mask.is_mc(x, y) && !aggressively_opened.get(x, y) {
+                mask.set(x, y, LOBE);
+                golden_pixel_count += 1;
             } else {
-                dilated.put_pixel(x, y, Rgba([0, 0, 0, 0]));
+                mask.clear(x, y, LOBE);
             }
         }
     }
     
-    dilated
+    println!("Thornfiddle lobes marked with {} golden pixels using dynamic kernel size", golden_pixel_count);
+    
+    Ok(golden_pixel_count)
+}
+
+/// Apply additional morphological cleaning to remove thin connections and shells
+fn clean_thin_artifacts(mask: &BinaryMask) -> BinaryMask {
+    // Step 1: Apply a small erosion to break thin connections (3x3 kernel breaks 1-2 pixel connections)
+    let eroded = erode_mask(mask, 3);
+    
+    // Step 2: Find connected components after erosion
+    let components = label_components(&eroded);
+    
+    // Step 3: Keep only the largest component
+    let largest_label = components.largest().map_or(0, |stats| stats.label);
+    let largest_only = components.mask_where(|stats| stats.label == largest_label);
+    
+    // Step 4: Apply a small dilation to restore size (1-pixel radius)
+    dilate_mask(&largest_only, 3)
 }
 
 /// Improved LMC creation with thin artifact removal
-pub fn create_mc_with_com_component(
-    processed_image: &RgbaImage, 
-    marked_image: &mut RgbaImage, 
-    pink_color: [u8; 3]
-) -> RgbaImage {
-    let (width, height) = processed_image.dimensions();
+///
+/// Leaf pixels outside the resulting MC component are marked in the `MC_REMOVED` layer.
+///
+/// # Returns
+/// The MC region
+pub fn create_mc_with_com_component(mask: &mut LeafMask) -> BinaryMask {
+    let (width, height) = mask.dimensions();
     
     // First, calculate the center of mass
-    let (com_x, com_y) = calculate_center_of_mass(processed_image)
+    let (com_x, com_y) = calculate_center_of_mass(&mask.region(true))
         .unwrap_or((width / 2, height / 2)); // Fallback to center if calculation fails
     
     println!("Center of Mass: ({}, {})", com_x, com_y);
     
-    // Create a version of the image with pink pixels made transparent
-    let mut temp_image = marked_image.clone();
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = temp_image.get_pixel_mut(x, y);
-            if has_rgb_color(pixel, pink_color) {
-                *pixel = image::Rgba([0, 0, 0, 0]);
-            }
-        }
-    }
+    // Leaf without the regions removed by the opening
+    let unopened = BinaryMask::from_fn(width, height, |x, y| {
+        mask.is_leaf(x, y) && !mask.has(x, y, EC_OPENED)
+    });
     
     // NEW: Apply morphological cleaning to remove thin artifacts
     println!("Cleaning thin artifacts...");
-    let cleaned_image = clean_thin_artifacts(&temp_image, pink_color);
+    let cleaned = clean_thin_artifacts(&unopened);
     
-    // Find connected components in the cleaned image
-    let (component_sizes, component_map) = find_connected_components(&cleaned_image, pink_color);
+    // Find connected components in the cleaned mask
+    let components = label_components(&cleaned);
     
-    println!("Found {} connected components after cleaning", component_sizes.len());
+    println!("Found {} connected components after cleaning", components.count());
     
     // Calculate size threshold (e.g., components must be at least 0.5% of image area)
     let total_pixels = (width * height) as usize;
     let min_size_threshold = (total_pixels as f64 * 0.005).max(50.0) as usize; // Reduced to 0.5% since we've already cleaned
     
     // Apply size-based filtering
-    let size_filtered = filter_small_components(&cleaned_image, &component_sizes, &component_map, min_size_threshold);
+    let size_filtered = components.mask_where(|stats| stats.area >= min_size_threshold);
     
     // Keep only the largest remaining component
-    let (final_component_sizes, final_component_map) = find_connected_components(&size_filtered, pink_color);
-    let lmc_image = keep_largest_component(&size_filtered, &final_component_sizes, &final_component_map);
+    let final_components = label_components(&size_filtered);
+    let largest = final_components.largest();
+    let largest_label = largest.map_or(0, |stats| stats.label);
+    let mc_component = final_components.mask_where(|stats| stats.label == largest_label);
     
     println!("After cleaning and filtering: {} components, keeping largest with {} pixels", 
-             final_component_sizes.len(),
-             final_component_sizes.iter().max().unwrap_or(&0));
+             final_components.count(),
+             largest.map_or(0, |stats| stats.area));
     
-    // Update marking: If pixel is in original but not in final LMC, mark it pink
+    // Update marking: leaf pixels outside the final MC component are removed
     for y in 0..height {
         for x in 0..width {
-            let orig_pixel = processed_image.get_pixel(x, y);
-            let lmc_pixel = lmc_image.get_pixel(x, y);
-            
-            // If pixel is non-transparent in original but transparent in LMC
-            if orig_pixel[3] > 0 && lmc_pixel[3] == 0 {
-                // Mark it pink in the marked image
-                marked_image.put_pixel(x, y, image::Rgba([pink_color[0], pink_color[1], pink_color[2], orig_pixel[3]]));
+            if mask.is_leaf(x, y) && !mc_component.get(x, y) {
+                mask.set(x, y, MC_REMOVED);
+            } else {
+                mask.clear(x, y, MC_REMOVED);
             }
         }
     }
     
-    lmc_image
+    mask.region(false)
 }
 
 /// Direction vectors for Moore-Neighbor contour tracing
@@ -623,292 +367,294 @@ static MOORE_NEIGHBORHOOD: [(i32, i32); 8] = [
     (1, -1),  // up-right
 ];
 
-/// Trace the contour of a region using Moore-Neighbor tracing algorithm
-pub fn trace_contour(image: &RgbaImage, is_pink_opaque: bool, pink_color: [u8; 3]) -> Vec<(u32, u32)> {
-    use std::collections::HashSet;
-    
-    let (width, height) = image.dimensions();
-    let mut contour = Vec::new();
+/// Where a traced contour starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContourStart {
+    /// Leftmost region pixel (topmost among ties)
+    Leftmost,
+    /// Bottommost boundary pixel closest to the image centre column (leaves are imaged petiole down)
+    PetioleBase,
+    /// Topmost boundary pixel closest to the image centre column
+    Apex,
+    /// Boundary pixel nearest to the given point
+    NearestTo(u32, u32),
+}
+
+/// Follow a region boundary with Moore-Neighbor tracing
+///
+/// Neighbours are swept counter-clockwise (as displayed) starting after the
+/// backtrack pixel, so outer boundaries come out counter-clockwise. Tracing
+/// stops when the tracer is back at the start pixel and about to repeat its
+/// first move (Jacob's stopping criterion); one-pixel-wide bridges are
+/// therefore walked in both directions and their pixels appear twice.
+///
+/// # Arguments
+/// * `region` - Region to trace
+/// * `start` - Boundary pixel to start from
+/// * `backtrack` - Non-region neighbour of `start` (the pixel the tracer "came from")
+///
+/// # Returns
+/// Boundary pixels in tracing order (closing pixel not repeated)
+pub fn trace_boundary(region: &BinaryMask, start: (u32, u32), backtrack: (i32, i32)) -> Vec<(u32, u32)> {
+    let (width, height) = region.dimensions();
+    let start = (start.0 as i32, start.1 as i32);
+    let mut current = start;
+    let mut backtrack = backtrack;
+    let mut first_move = None;
+    let mut contour = vec![(start.0 as u32, start.1 as u32)];
     
-    // Find the leftmost non-transparent pixel (first pixel encountered in scanning order)
-    let mut start_point = None;
+    // Every boundary pixel is entered at most once from each of its 8 neighbours
+    let max_iterations = (width * height) as usize * 8;
     
-    'outer: for x in 0..width {
-        for y in 0..height {
-            let pixel = image.get_pixel(x, y);
-            
-            // Check if this is a valid contour pixel based on mode
-            let is_valid = if is_pink_opaque {
-                pixel[3] > 0  // For LEC, any non-transparent pixel is valid
-            } else {
-                pixel[3] > 0 && !has_rgb_color(pixel, pink_color)  // For LMC, non-transparent and non-pink
-            };
+    for _ in 0..max_iterations {
+        let offset = (backtrack.0 - current.0, backtrack.1 - current.1);
+        let first = MOORE_NEIGHBORHOOD.iter().position(|&d| d == offset).unwrap_or(0);
+        
+        let mut previous = backtrack;
+        let mut next = None;
+        
+        for step in 1..8 {
+            let (dx, dy) = MOORE_NEIGHBORHOOD[(first + 8 - step) % 8];
+            let candidate = (current.0 + dx, current.1 + dy);
             
-            if is_valid {
-                // Check if it's a boundary pixel by checking if it has any transparent/invalid neighbor
-                let mut is_boundary = false;
-                
-                // Check 8-connected neighbors
-                for dy in -1..=1 {
-                    for dx in -1..=1 {
-                        if dx == 0 && dy == 0 { continue; }
-                        
-                        let nx = x as i32 + dx;
-                        let ny = y as i32 + dy;
-                        
-                        if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
-                            // Out of bounds counts as boundary
-                            is_boundary = true;
-                            break;
-                        } else {
-                            let neighbor = image.get_pixel(nx as u32, ny as u32);
-                            let is_neighbor_valid = if is_pink_opaque {
-                                neighbor[3] > 0
-                            } else {
-                                neighbor[3] > 0 && !has_rgb_color(neighbor, pink_color)
-                            };
-                            
-                            if !is_neighbor_valid {
-                                is_boundary = true;
-                                break;
-                            }
-                        }
-                    }
-                    if is_boundary { break; }
-                }
-                
-                if is_boundary {
-                    start_point = Some((x, y));
-                    break 'outer;
-                }
+            if region.get_or_false(candidate.0, candidate.1) {
+                next = Some(candidate);
+                break;
             }
-        }
-    }
-    
-    // If no start point found, return empty contour
-    let start = match start_point {
-        Some(p) => p,
-        None => return Vec::new(),
-    };
-    
-    // Add start point to contour
-    contour.push(start);
-    
-    // Initialize variables for contour tracing
-    let mut current = start;
-    let mut visited = HashSet::new();
-    visited.insert(current);
-    
-    // Direction offsets for 8-connected neighbors (clockwise order from right)
-    let directions = [
-        (1, 0), (1, 1), (0, 1), (-1, 1), (-1, 0), (-1, -1), (0, -1), (1, -1)
-    ];
-    
-    // Start with the right direction
-    let mut direction_idx = 0;
-    
-    // Safety counter to prevent infinite loops
-    let max_iterations = (width * height) as usize * 2;
-    let mut iteration_count = 0;
-    
-    // Main tracing loop
-    loop {
-        iteration_count += 1;
-        if iteration_count > max_iterations {
-            println!("Warning: Contour tracing terminated after {} iterations to prevent infinite loop.", max_iterations);
-            break;
+            previous = candidate;
         }
         
-        // Find the next boundary pixel by checking neighbors in clockwise order
-        let mut found_next = false;
+        // Isolated pixel: the contour is the pixel itself
+        let next = match next {
+            Some(n) => n,
+            None => return contour,
+        };
         
-        // Look in all 8 directions, starting from the backtracking direction + 2
-        // This ensures we follow the boundary by making a right turn whenever possible
-        for i in 0..8 {
-            // Start from backtracking direction + 2 (90 degrees clockwise from backtrack)
-            let check_idx = (direction_idx + 6 + i) % 8;
-            let (dx, dy) = directions[check_idx];
-            let nx = current.0 as i32 + dx;
-            let ny = current.1 as i32 + dy;
-            
-            // Check bounds
-            if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
-                continue;
-            }
-            
-            let next = (nx as u32, ny as u32);
-            let pixel = image.get_pixel(next.0, next.1);
-            
-            // Check if this pixel is valid according to our criteria
-            let is_valid = if is_pink_opaque {
-                pixel[3] > 0
-            } else {
-                pixel[3] > 0 && !has_rgb_color(pixel, pink_color)
-            };
-            
-            if is_valid {
-                // We found the next boundary pixel
-                current = next;
-                direction_idx = check_idx;
-                
-                // Only add to contour if we haven't visited this pixel yet
-                if !visited.contains(&current) {
-                    contour.push(current);
-                    visited.insert(current);
-                }
-                
-                found_next = true;
+        if current == start {
+            if first_move == Some(next) {
                 break;
             }
+            first_move.get_or_insert(next);
         }
         
-        // If we couldn't find a next pixel or we've returned to start and completed at least one circuit
-        if !found_next || (current == start && contour.len() > 1) {
-            break;
-        }
+        contour.push((next.0 as u32, next.1 as u32));
+        current = next;
+        backtrack = previous;
+    }
+    
+    // The loop closes on the start pixel
+    if contour.len() > 1 && contour.last() == contour.first() {
+        contour.pop();
     }
     
-    // Return the traced contour
     contour
 }
 
-/// Calculate the Center of Mass (COM) - adapted from your existing code
-pub fn calculate_center_of_mass(image: &RgbaImage) -> Option<(u32, u32)> {
-    let (width, height) = image.dimensions();
+/// Trace the outer contour of a region, starting at the leftmost pixel
+pub fn trace_contour(region: &BinaryMask) -> Vec<(u32, u32)> {
+    trace_contour_from(region, ContourStart::Leftmost)
+}
+
+/// Trace the outer contour of a region using Moore-Neighbor tracing
+///
+/// The contour is counter-clockwise (as displayed) and begins at the pixel
+/// selected by `start`. Only the component containing the leftmost pixel is
+/// traced.
+///
+/// # Arguments
+/// * `region` - Region to trace
+/// * `start` - Start policy
+///
+/// # Returns
+/// Boundary pixels in tracing order; empty for an empty region
+pub fn trace_contour_from(region: &BinaryMask, start: ContourStart) -> Vec<(u32, u32)> {
+    let (width, height) = region.dimensions();
+    
+    // The leftmost (then topmost) pixel is on the outer boundary and its west neighbour is outside
+    let leftmost = (0..width)
+        .flat_map(|x| (0..height).map(move |y| (x, y)))
+        .find(|&(x, y)| region.get(x, y));
+    
+    let leftmost = match leftmost {
+        Some(p) => p,
+        None => return Vec::new(),
+    };
+    
+    let mut contour = trace_boundary(region, leftmost, (leftmost.0 as i32 - 1, leftmost.1 as i32));
+    let start_index = contour_start_index(&contour, width, start);
+    
+    contour.rotate_left(start_index);
+    contour
+}
+
+/// Index of the contour pixel selected by a start policy
+///
+/// # Arguments
+/// * `contour` - Traced contour
+/// * `width` - Image width (the centre column breaks ties for `PetioleBase` and `Apex`)
+/// * `start` - Start policy
+///
+/// # Returns
+/// Index into `contour`; 0 for an empty contour
+pub fn contour_start_index(contour: &[(u32, u32)], width: u32, start: ContourStart) -> usize {
+    let center_x = width as f64 * 0.5;
+    
+    match start {
+        ContourStart::Leftmost => 0,
+        ContourStart::PetioleBase => contour.iter().enumerate()
+            .min_by(|(_, a), (_, b)| {
+                b.1.cmp(&a.1).then((a.0 as f64 - center_x).abs().total_cmp(&(b.0 as f64 - center_x).abs()))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0),
+        ContourStart::Apex => contour.iter().enumerate()
+            .min_by(|(_, a), (_, b)| {
+                a.1.cmp(&b.1).then((a.0 as f64 - center_x).abs().total_cmp(&(b.0 as f64 - center_x).abs()))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0),
+        ContourStart::NearestTo(px, py) => contour.iter().enumerate()
+            .min_by_key(|(_, &(x, y))| {
+                let dx = x as i64 - px as i64;
+                let dy = y as i64 - py as i64;
+                dx * dx + dy * dy
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0),
+    }
+}
+
+
+/// Calculate the Center of Mass (COM) of a region
+pub fn calculate_center_of_mass(region: &BinaryMask) -> Option<(u32, u32)> {
+    let (width, height) = region.dimensions();
     let mut sum_x = 0.0;
     let mut sum_y = 0.0;
-    let mut sum_alpha = 0.0;
+    let mut count = 0.0;
     
     for y in 0..height {
         for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            let alpha = pixel[3] as f32;
-            
-            if alpha > 0.0 {
-                sum_x += x as f32 * alpha;
-                sum_y += y as f32 * alpha;
-                sum_alpha += alpha;
+            if region.get(x, y) {
+                sum_x += x as f64;
+                sum_y += y as f64;
+                count += 1.0;
             }
         }
     }
     
-    if sum_alpha <= 0.0 {
+    if count <= 0.0 {
         return None;
     }
     
     // Calculate COM
-    let com_x = sum_x / sum_alpha;
-    let com_y = sum_y / sum_alpha;
+    let com_x = sum_x / count;
+    let com_y = sum_y / count;
     
     // Round to nearest integer
     Some((com_x.round() as u32, com_y.round() as u32))
 }
 
-pub fn resample_contour(contour: &[(u32, u32)], target_points: usize) -> Vec<(u32, u32)> {
-    if contour.len() <= 1 || target_points <= 1 {
-        return contour.to_vec();
+/// Equally spaced arc-length sample positions along a closed contour
+///
+/// # Arguments
+/// * `contour` - Closed contour (first point not repeated)
+/// * `target_points` - Number of samples
+///
+/// # Returns
+/// For each sample, the index of the contour segment containing it and the
+/// interpolation factor along that segment (the segment ends at the next
+/// point, wrapping to the first)
+pub fn arc_length_samples(contour: &[(u32, u32)], target_points: usize) -> Vec<(usize, f64)> {
+    let n = contour.len();
+    if n == 0 || target_points == 0 {
+        return Vec::new();
     }
     
-    if target_points >= contour.len() {
-        // If target is larger than current, just return the original
-        return contour.to_vec();
+    // Segment lengths including the closing segment
+    let segment_lengths: Vec<f64> = (0..n)
+        .map(|i| {
+            let (x1, y1) = contour[i];
+            let (x2, y2) = contour[(i + 1) % n];
+            let dx = x2 as f64 - x1 as f64;
+            let dy = y2 as f64 - y1 as f64;
+            (dx * dx + dy * dy).sqrt()
+        })
+        .collect();
+    let total_perimeter: f64 = segment_lengths.iter().sum();
+    
+    if total_perimeter <= 0.0 {
+        return vec![(0, 0.0); target_points];
     }
     
-    // Calculate cumulative distances along the contour
-    let mut cumulative_distances = vec![0.0; contour.len()];
-    let mut total_perimeter = 0.0;
-    
-    for i in 1..contour.len() {
-        let dx = contour[i].0 as f64 - contour[i-1].0 as f64;
-        let dy = contour[i].1 as f64 - contour[i-1].1 as f64;
-        let segment_length = (dx * dx + dy * dy).sqrt();
-        total_perimeter += segment_length;
-        cumulative_distances[i] = total_perimeter;
-    }
-    
-    // Handle closed contour - add distance from last point back to first
-    if contour.len() > 2 {
-        let last_idx = contour.len() - 1;
-        let dx = contour[0].0 as f64 - contour[last_idx].0 as f64;
-        let dy = contour[0].1 as f64 - contour[last_idx].1 as f64;
-        let closing_segment = (dx * dx + dy * dy).sqrt();
-        total_perimeter += closing_segment;
-    }
-    
-    // Generate target distances for resampled points
-    let mut resampled_contour = Vec::with_capacity(target_points);
+    let mut samples = Vec::with_capacity(target_points);
+    let mut segment = 0;
+    let mut segment_start_distance = 0.0;
     
     for i in 0..target_points {
         let target_distance = (i as f64 * total_perimeter) / target_points as f64;
         
-        // Find the segment containing this target distance
-        let mut segment_start_idx = 0;
-        for j in 1..cumulative_distances.len() {
-            if cumulative_distances[j] > target_distance {
-                segment_start_idx = j - 1;
-                break;
-            }
-            if j == cumulative_distances.len() - 1 {
-                segment_start_idx = j;
-            }
+        while segment < n - 1 && segment_start_distance + segment_lengths[segment] <= target_distance {
+            segment_start_distance += segment_lengths[segment];
+            segment += 1;
         }
         
-        // Handle the case where target_distance is in the closing segment
-        if target_distance > cumulative_distances[cumulative_distances.len() - 1] {
-            // Interpolate between last point and first point
-            let excess_distance = target_distance - cumulative_distances[cumulative_distances.len() - 1];
-            let last_idx = contour.len() - 1;
-            let dx = contour[0].0 as f64 - contour[last_idx].0 as f64;
-            let dy = contour[0].1 as f64 - contour[last_idx].1 as f64;
-            let closing_segment_length = (dx * dx + dy * dy).sqrt();
-            
-            if closing_segment_length > 0.0 {
-                let t = excess_distance / closing_segment_length;
-                let x = contour[last_idx].0 as f64 + t * dx;
-                let y = contour[last_idx].1 as f64 + t * dy;
-                resampled_contour.push((x.round() as u32, y.round() as u32));
-            } else {
-                resampled_contour.push(contour[last_idx]);
-            }
+        let t = if segment_lengths[segment] > 0.0 {
+            ((target_distance - segment_start_distance) / segment_lengths[segment]).clamp(0.0, 1.0)
         } else {
-            // Interpolate within the current segment
-            let segment_end_idx = if segment_start_idx == cumulative_distances.len() - 1 {
-                0 // Wrap to first point for closed contour
-            } else {
-                segment_start_idx + 1
-            };
-            
-            if segment_start_idx == segment_end_idx {
-                // Single point case
-                resampled_contour.push(contour[segment_start_idx]);
-            } else {
-                let segment_start_distance = cumulative_distances[segment_start_idx];
-                let segment_end_distance = if segment_end_idx == 0 {
-                    total_perimeter
-                } else {
-                    cumulative_distances[segment_end_idx]
-                };
-                
-                let segment_length = segment_end_distance - segment_start_distance;
-                
-                if segment_length > 0.0 {
-                    let t = (target_distance - segment_start_distance) / segment_length;
-                    
-                    let start_point = contour[segment_start_idx];
-                    let end_point = contour[segment_end_idx];
-                    
-                    let x = start_point.0 as f64 + t * (end_point.0 as f64 - start_point.0 as f64);
-                    let y = start_point.1 as f64 + t * (end_point.1 as f64 - start_point.1 as f64);
-                    
-                    resampled_contour.push((x.round() as u32, y.round() as u32));
-                } else {
-                    resampled_contour.push(contour[segment_start_idx]);
-                }
-            }
-        }
+            0.0
+        };
+        samples.push((segment, t));
+    }
+    
+    samples
+}
+
+/// Resample a closed contour to a fixed number of points equally spaced along arc length
+///
+/// Unlike `resample_contour` this also upsamples short contours.
+pub fn resample_contour_arc_length(contour: &[(u32, u32)], target_points: usize) -> Vec<(u32, u32)> {
+    let n = contour.len();
+    arc_length_samples(contour, target_points)
+        .into_iter()
+        .map(|(segment, t)| {
+            let start_point = contour[segment];
+            let end_point = contour[(segment + 1) % n];
+            let x = start_point.0 as f64 + t * (end_point.0 as f64 - start_point.0 as f64);
+            let y = start_point.1 as f64 + t * (end_point.1 as f64 - start_point.1 as f64);
+            (x.round() as u32, y.round() as u32)
+        })
+        .collect()
+}
+
+/// Resample a per-point signal along the arc length of its closed contour
+///
+/// # Arguments
+/// * `contour` - Contour the signal was measured on
+/// * `signal` - One value per contour point
+/// * `target_points` - Number of samples
+///
+/// # Returns
+/// Linearly interpolated signal at equally spaced arc-length positions
+pub fn resample_signal(contour: &[(u32, u32)], signal: &[f64], target_points: usize) -> Vec<f64> {
+    let n = contour.len().min(signal.len());
+    arc_length_samples(&contour[..n], target_points)
+        .into_iter()
+        .map(|(segment, t)| signal[segment] + t * (signal[(segment + 1) % n] - signal[segment]))
+        .collect()
+}
+
+/// Resample a closed contour to fewer points equally spaced along arc length
+pub fn resample_contour(contour: &[(u32, u32)], target_points: usize) -> Vec<(u32, u32)> {
+    if contour.len() <= 1 || target_points <= 1 {
+        return contour.to_vec();
+    }
+    
+    if target_points >= contour.len() {
+        // If target is larger than current, just return the original
+        return contour.to_vec();
     }
     
-    resampled_contour
+    resample_contour_arc_length(contour, target_points)
 }
 
 /// Smooth contour points to reduce digitization artifacts
@@ -0,0 +1,266 @@
+// src/orientation.rs - Leaf orientation normalisation (base at the bottom centre, apex up)
+
+use image::{Rgba, RgbaImage};
+use imageproc::geometric_transformations::{warp_into_with, Interpolation};
+
+use crate::config::OrientationMethod;
+use crate::leaf_mask::BinaryMask;
+use crate::shape_analysis::calculate_feret_diameters;
+
+/// Length of the slab at each end of the axis compared to pick the base, as a fraction of the axis
+const END_SLAB_FRACTION: f64 = 0.15;
+
+/// Relative area difference below which the two axis ends are considered alike
+const END_AREA_TOLERANCE: f64 = 0.1;
+
+/// Transparent margin around the normalised leaf, in pixels
+const CANVAS_MARGIN: f64 = 2.0;
+
+/// Rigid transform from the analysed image to the orientation-normalised image
+///
+/// Normalised coordinates are `R(angle) * (p - base) + target_base`, where the
+/// rotation is clockwise as displayed (y pointing down).
+#[derive(Debug, Clone)]
+pub struct OrientationTransform {
+    /// Method that detected the leaf axis
+    pub method: OrientationMethod,
+    /// Clockwise rotation in degrees, in (-180, 180]
+    pub angle_degrees: f64,
+    /// Leaf base in the analysed image
+    pub base: (f64, f64),
+    /// Leaf apex in the analysed image
+    pub apex: (f64, f64),
+    /// Leaf base in the normalised image (bottom centre)
+    pub target_base: (f64, f64),
+    /// Width of the normalised image
+    pub width: u32,
+    /// Height of the normalised image
+    pub height: u32,
+}
+
+impl OrientationTransform {
+    /// Transform that leaves an image unchanged
+    pub fn identity(width: u32, height: u32) -> Self {
+        Self {
+            method: OrientationMethod::None,
+            angle_degrees: 0.0,
+            base: (0.0, 0.0),
+            apex: (0.0, 0.0),
+            target_base: (0.0, 0.0),
+            width,
+            height,
+        }
+    }
+    
+    /// True when no rotation or translation is applied
+    pub fn is_identity(&self) -> bool {
+        self.method == OrientationMethod::None
+    }
+    
+    /// Map a point of the analysed image to the normalised image
+    pub fn to_normalized(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (sin, cos) = self.angle_degrees.to_radians().sin_cos();
+        let (dx, dy) = (x - self.base.0, y - self.base.1);
+        (
+            cos * dx - sin * dy + self.target_base.0,
+            sin * dx + cos * dy + self.target_base.1,
+        )
+    }
+    
+    /// Map a point of the normalised image back to the analysed image
+    pub fn to_original(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        let (sin, cos) = self.angle_degrees.to_radians().sin_cos();
+        let (dx, dy) = (x - self.target_base.0, y - self.target_base.1);
+        (
+            cos * dx + sin * dy + self.base.0,
+            -sin * dx + cos * dy + self.base.1,
+        )
+    }
+}
+
+/// Leaf pixels as floating-point coordinates
+fn region_points(region: &BinaryMask) -> Vec<(f64, f64)> {
+    let (width, height) = region.dimensions();
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| region.get(x, y))
+        .map(|(x, y)| (x as f64, y as f64))
+        .collect()
+}
+
+/// Axis end points through the centroid along the principal axis of inertia
+fn principal_axis_ends(points: &[(f64, f64)]) -> Option<((f64, f64), (f64, f64))> {
+    if points.is_empty() {
+        return None;
+    }
+    
+    let n = points.len() as f64;
+    let cx = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let cy = points.iter().map(|p| p.1).sum::<f64>() / n;
+    
+    let (mut mu20, mut mu02, mut mu11) = (0.0, 0.0, 0.0);
+    for &(x, y) in points {
+        mu20 += (x - cx).powi(2);
+        mu02 += (y - cy).powi(2);
+        mu11 += (x - cx) * (y - cy);
+    }
+    
+    let theta = 0.5 * (2.0 * mu11).atan2(mu20 - mu02);
+    let axis = (theta.cos(), theta.sin());
+    
+    let projections = points.iter().map(|&(x, y)| (x - cx) * axis.0 + (y - cy) * axis.1);
+    let (min, max) = projections.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), t| (lo.min(t), hi.max(t)));
+    
+    Some((
+        (cx + axis.0 * min, cy + axis.1 * min),
+        (cx + axis.0 * max, cy + axis.1 * max),
+    ))
+}
+
+/// Detect the base-apex axis of a leaf
+///
+/// PETIOLE runs from the petiole base to the farthest leaf pixel. FERET uses
+/// the maximum Feret diameter; MOMENTS spans the leaf along its principal axis
+/// of inertia. For these two, the end whose slab holds less leaf area (the
+/// petiole or narrow base) becomes the base; when both ends are alike, the
+/// lower end in the image is kept as the base.
+///
+/// # Arguments
+/// * `region` - Leaf region
+/// * `method` - Axis detection method
+/// * `petiole_base` - Petiole base from `detect_petiole` (required for PETIOLE)
+///
+/// # Returns
+/// (base, apex), or None for an empty region, NONE, or PETIOLE without a base
+pub fn detect_leaf_axis(
+    region: &BinaryMask,
+    method: OrientationMethod,
+    petiole_base: Option<(u32, u32)>,
+) -> Option<((f64, f64), (f64, f64))> {
+    let points = region_points(region);
+    
+    let (first, second) = match method {
+        OrientationMethod::None => return None,
+        OrientationMethod::Petiole => {
+            let (bx, by) = petiole_base?;
+            let base = (bx as f64, by as f64);
+            let apex = points.iter()
+                .cloned()
+                .max_by(|a, b| {
+                    let da = (a.0 - base.0).powi(2) + (a.1 - base.1).powi(2);
+                    let db = (b.0 - base.0).powi(2) + (b.1 - base.1).powi(2);
+                    da.total_cmp(&db)
+                })?;
+            return Some((base, apex));
+        }
+        OrientationMethod::Feret => {
+            let feret = calculate_feret_diameters(&points);
+            if feret.max_feret <= 0.0 {
+                return None;
+            }
+            feret.max_feret_points
+        }
+        OrientationMethod::Moments => principal_axis_ends(&points)?,
+    };
+    
+    let axis = (second.0 - first.0, second.1 - first.1);
+    let axis_length_sq = axis.0 * axis.0 + axis.1 * axis.1;
+    if axis_length_sq <= 0.0 {
+        return None;
+    }
+    
+    // Leaf area within the end slabs of the axis
+    let (mut first_area, mut second_area) = (0usize, 0usize);
+    for &(x, y) in &points {
+        let t = ((x - first.0) * axis.0 + (y - first.1) * axis.1) / axis_length_sq;
+        if t <= END_SLAB_FRACTION {
+            first_area += 1;
+        } else if t >= 1.0 - END_SLAB_FRACTION {
+            second_area += 1;
+        }
+    }
+    
+    let larger = first_area.max(second_area).max(1) as f64;
+    let first_is_base = if (first_area as f64 - second_area as f64).abs() / larger < END_AREA_TOLERANCE {
+        first.1 >= second.1
+    } else {
+        first_area < second_area
+    };
+    
+    Some(if first_is_base { (first, second) } else { (second, first) })
+}
+
+/// Rotate and translate a leaf image so the base is at the bottom centre and the apex points up
+///
+/// The canvas is sized to the rotated leaf plus a small margin; pixels are
+/// resampled bilinearly.
+///
+/// # Arguments
+/// * `image` - Analysed (resized) image
+/// * `region` - Leaf region of the image
+/// * `method` - Axis detection method
+/// * `petiole_base` - Petiole base for PETIOLE (FERET is used when it is None)
+///
+/// # Returns
+/// Normalised image and the applied transform (the input and an identity
+/// transform for NONE or when no axis is found)
+pub fn normalize_orientation(
+    image: &RgbaImage,
+    region: &BinaryMask,
+    method: OrientationMethod,
+    petiole_base: Option<(u32, u32)>,
+) -> (RgbaImage, OrientationTransform) {
+    let (width, height) = image.dimensions();
+    let method = if method == OrientationMethod::Petiole && petiole_base.is_none() {
+        OrientationMethod::Feret
+    } else {
+        method
+    };
+    let (base, apex) = match detect_leaf_axis(region, method, petiole_base) {
+        Some(axis) => axis,
+        None => return (image.clone(), OrientationTransform::identity(width, height)),
+    };
+    
+    // Rotate the base -> apex direction onto straight up (0, -1)
+    let direction = (apex.0 - base.0, apex.1 - base.1);
+    let angle = (-90.0 - direction.1.atan2(direction.0).to_degrees() + 180.0).rem_euclid(360.0) - 180.0;
+    let angle_degrees = if angle <= -180.0 { 180.0 } else { angle };
+    
+    let mut transform = OrientationTransform {
+        method,
+        angle_degrees,
+        base,
+        apex,
+        target_base: (0.0, 0.0),
+        width,
+        height,
+    };
+    
+    // Extent of the rotated leaf around the base
+    let (mut half_width, mut above, mut below) = (0.0f64, 0.0f64, 0.0f64);
+    for point in region_points(region) {
+        let (x, y) = transform.to_normalized(point);
+        half_width = half_width.max(x.abs());
+        above = above.max(-y);
+        below = below.max(y);
+    }
+    
+    let half_width = (half_width + CANVAS_MARGIN).ceil();
+    transform.width = (2.0 * half_width) as u32 + 1;
+    transform.height = (above + below + 2.0 * CANVAS_MARGIN).ceil() as u32 + 1;
+    transform.target_base = (half_width, (above + CANVAS_MARGIN).ceil());
+    
+    let mut normalized = RgbaImage::new(transform.width, transform.height);
+    warp_into_with(
+        image,
+        |x, y| {
+            let (ox, oy) = transform.to_original((x as f64, y as f64));
+            (ox as f32, oy as f32)
+        },
+        Interpolation::Bilinear,
+        Rgba([0, 0, 0, 0]),
+        &mut normalized,
+    );
+    
+    (normalized, transform)
+}
@@ -1,11 +1,28 @@
-// src/output.rs - CSV output generation for EC/MC analysis
+// src/output.rs - CSV and image output generation for EC/MC analysis
 
 use std::fs;
 use std::path::Path;
 use csv::Writer;
+use image::{Rgba, RgbaImage};
 
 use crate::errors::{LeafComplexError, Result};
 use crate::feature_extraction::MarginalPointFeatures;
+use crate::config::EntropyMethod;
+use crate::efa::EllipticFourier;
+use crate::entropy::EntropyMetrics;
+use crate::fractal::{FractalFit, FractalMetrics};
+use crate::granulometry::PatternSpectrum;
+use crate::holes::HoleMetrics;
+use crate::image_utils::AlphaThresholds;
+use crate::leaf_mask::{BinaryMask, LeafMask};
+use crate::orientation::OrientationTransform;
+use crate::path_algorithms::GeodesicMetrics;
+use crate::petiole::Petiole;
+use crate::shape_analysis::{ConvexHullMetrics, FeretDiameters, LeafArchitecture, ShapeIndices};
+use crate::shape_context::InnerDistanceShapeContext;
+use crate::lobes::LobeMetrics;
+use crate::skeleton::Skeleton;
+use crate::teeth::TeethMetrics;
 
 /// Write EC (Edge Complexity) features to CSV
 ///
@@ -117,48 +134,684 @@ pub fn write_mc_csv<P: AsRef<Path>>(
     Ok(())
 }
 
-/// Create summary CSV with aggregate metrics
+/// Write the inner-distance shape context feature matrix to CSV
+///
+/// # Arguments
+/// * `descriptor` - IDSC descriptor of the leaf
+/// * `orientation` - Orientation transform, to report points in the analysed image
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - Point_Index
+/// - X, Y (sampled contour point in the analysed image)
+/// - D{distance_bin}_A{angle_bin} (normalised histogram value)
+pub fn write_idsc_csv<P: AsRef<Path>>(
+    descriptor: &InnerDistanceShapeContext,
+    orientation: &OrientationTransform,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("IDSC").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    let mut header = vec!["Point_Index".to_string(), "X".to_string(), "Y".to_string()];
+    for distance_bin in 0..descriptor.distance_bins {
+        for angle_bin in 0..descriptor.angle_bins {
+            header.push(format!("D{}_A{}", distance_bin, angle_bin));
+        }
+    }
+    writer.write_record(&header).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (i, (point, histogram)) in descriptor.points.iter().zip(descriptor.histograms.iter()).enumerate() {
+        let (x, y) = orientation.to_original((point.0 as f64, point.1 as f64));
+        let mut record = vec![i.to_string(), format!("{:.0}", x), format!("{:.0}", y)];
+        record.extend(histogram.iter().map(|value| format!("{:.6}", value)));
+        writer.write_record(&record).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the EC and MC pattern spectra to CSV
+///
+/// # Arguments
+/// * `ec_spectrum` - Pattern spectrum of the whole leaf
+/// * `mc_spectrum` - Pattern spectrum of the MC region
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - Region (EC or MC)
+/// - Scale_Index
+/// - Diameter_Px, Diameter_Percent (of the region's shorter dimension)
+/// - Remaining_Area, Removed_Area (pixels)
+/// - Pattern_Spectrum (fraction of leaf area first removed at this scale)
+pub fn write_granulometry_csv<P: AsRef<Path>>(
+    ec_spectrum: &PatternSpectrum,
+    mc_spectrum: &PatternSpectrum,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("Granulometry").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    writer.write_record(&[
+        "Region",
+        "Scale_Index",
+        "Diameter_Px",
+        "Diameter_Percent",
+        "Remaining_Area",
+        "Removed_Area",
+        "Pattern_Spectrum",
+    ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (region, spectrum) in [("EC", ec_spectrum), ("MC", mc_spectrum)] {
+        for i in 0..spectrum.diameters.len() {
+            writer.write_record(&[
+                region.to_string(),
+                i.to_string(),
+                spectrum.diameters[i].to_string(),
+                format!("{:.3}", spectrum.relative_diameters[i]),
+                spectrum.remaining_area[i].to_string(),
+                spectrum.removed_area[i].to_string(),
+                format!("{:.6}", spectrum.pattern_spectrum[i]),
+            ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+        }
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the log-log data of the EC and MC fractal dimension estimators to CSV
 ///
 /// # Arguments
+/// * `ec_fractal` - Fractal dimensions of the EC contour
+/// * `mc_fractal` - Fractal dimensions of the MC contour
 /// * `output_dir` - Base output directory
 /// * `filename` - Name of the input file (without extension)
-/// * `subfolder` - Subfolder name for organization
-/// * `mc_spectral_entropy` - Spectral entropy from MC analysis
-/// * `ec_approximate_entropy` - Approximate entropy from EC analysis
-/// * `ec_length` - Biological length from EC contour
-/// * `mc_length` - Biological length from MC contour
-/// * `ec_width` - Biological width from EC contour
-/// * `mc_width` - Biological width from MC contour
-/// * `ec_shape_index` - Shape index from EC analysis
-/// * `mc_shape_index` - Shape index from MC analysis
-/// * `outline_count` - Number of contour points
-/// * `harmonic_chain_count` - Number of harmonic chains detected
+///
+/// # Output Columns
+/// - Region (EC or MC)
+/// - Method (BOX or MINKOWSKI)
+/// - Scale (box size or dilation radius, pixels), Measure (occupied boxes or dilated area)
+/// - Log_Scale, Log_Measure (natural logarithms used in the fit)
+pub fn write_fractal_csv<P: AsRef<Path>>(
+    ec_fractal: &FractalMetrics,
+    mc_fractal: &FractalMetrics,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("Fractal").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    writer.write_record(&[
+        "Region",
+        "Method",
+        "Scale",
+        "Measure",
+        "Log_Scale",
+        "Log_Measure",
+    ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (region, fractal) in [("EC", ec_fractal), ("MC", mc_fractal)] {
+        for (method, fit) in [("BOX", &fractal.box_counting), ("MINKOWSKI", &fractal.minkowski)] {
+            for (&scale, &measure) in fit.scales.iter().zip(fit.measures.iter()) {
+                writer.write_record(&[
+                    region.to_string(),
+                    method.to_string(),
+                    scale.to_string(),
+                    measure.to_string(),
+                    format!("{:.6}", (scale as f64).ln()),
+                    format!("{:.6}", (measure.max(1) as f64).ln()),
+                ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+            }
+        }
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the EC and MC elliptic Fourier coefficients to CSV in Momocs layout
+///
+/// # Arguments
+/// * `ec_efa` - Descriptors of the EC outline
+/// * `mc_efa` - Descriptors of the MC outline
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - ID (<filename>_EC or <filename>_MC, used as row names)
+/// - A1..An, B1..Bn, C1..Cn, D1..Dn
+pub fn write_efa_csv<P: AsRef<Path>>(
+    ec_efa: &EllipticFourier,
+    mc_efa: &EllipticFourier,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("EFA").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header (the harmonic count is the same for both outlines unless one is very short)
+    let harmonics = ec_efa.coefficients.len().max(mc_efa.coefficients.len());
+    let mut header = vec!["ID".to_string()];
+    for letter in ["A", "B", "C", "D"] {
+        header.extend((1..=harmonics).map(|h| format!("{}{}", letter, h)));
+    }
+    writer.write_record(&header).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (region, efa) in [("EC", ec_efa), ("MC", mc_efa)] {
+        let mut record = vec![format!("{}_{}", filename, region)];
+        for k in 0..4 {
+            record.extend((0..harmonics).map(|h| {
+                efa.coefficients.get(h).map_or_else(|| "NA".to_string(), |c| format!("{:.8}", c[k]))
+            }));
+        }
+        writer.write_record(&record).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the Fourier power per harmonic of the EC and MC outlines to CSV
+///
+/// # Arguments
+/// * `ec_efa` - Descriptors of the EC outline
+/// * `mc_efa` - Descriptors of the MC outline
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - Region (EC or MC)
+/// - Harmonic
+/// - Power ((A² + B² + C² + D²) / 2)
+/// - Cumulative_Power (fraction of the total power up to this harmonic)
+pub fn write_efa_power_csv<P: AsRef<Path>>(
+    ec_efa: &EllipticFourier,
+    mc_efa: &EllipticFourier,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("EFA").join("Power").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    writer.write_record(&[
+        "Region",
+        "Harmonic",
+        "Power",
+        "Cumulative_Power",
+    ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (region, efa) in [("EC", ec_efa), ("MC", mc_efa)] {
+        let power = efa.harmonic_power();
+        let cumulative = efa.cumulative_power();
+        for (i, (p, c)) in power.iter().zip(cumulative.iter()).enumerate() {
+            writer.write_record(&[
+                region.to_string(),
+                (i + 1).to_string(),
+                format!("{:.8}", p),
+                format!("{:.6}", c),
+            ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+        }
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the EC and MC convexity defects to CSV
+///
+/// # Arguments
+/// * `ec_hull` - Convex hull metrics of the whole leaf
+/// * `mc_hull` - Convex hull metrics of the MC region
+/// * `ec_shorter_dimension` - Shorter dimension of the whole leaf, for relative depths
+/// * `mc_shorter_dimension` - Shorter dimension of the MC region, for relative depths
+/// * `orientation` - Orientation transform, to report points in the analysed image
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - Region (EC or MC)
+/// - Defect_Index
+/// - Start_Index, End_Index, Farthest_Index (contour point indices)
+/// - Start_X, Start_Y, End_X, End_Y, Farthest_X, Farthest_Y (in the analysed image)
+/// - Depth (pixels), Relative_Depth (percent of the region's shorter dimension)
+pub fn write_convexity_defects_csv<P: AsRef<Path>>(
+    ec_hull: &ConvexHullMetrics,
+    mc_hull: &ConvexHullMetrics,
+    ec_shorter_dimension: f64,
+    mc_shorter_dimension: f64,
+    orientation: &OrientationTransform,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("ConvexHull").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    writer.write_record(&[
+        "Region",
+        "Defect_Index",
+        "Start_Index",
+        "End_Index",
+        "Farthest_Index",
+        "Start_X",
+        "Start_Y",
+        "End_X",
+        "End_Y",
+        "Farthest_X",
+        "Farthest_Y",
+        "Depth",
+        "Relative_Depth",
+    ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (region, hull, shorter_dimension) in [
+        ("EC", ec_hull, ec_shorter_dimension),
+        ("MC", mc_hull, mc_shorter_dimension),
+    ] {
+        for (i, defect) in hull.defects.iter().enumerate() {
+            let relative_depth = if shorter_dimension > 0.0 {
+                defect.depth / shorter_dimension * 100.0
+            } else {
+                0.0
+            };
+            
+            let start = orientation.to_original(defect.start);
+            let end = orientation.to_original(defect.end);
+            let farthest = orientation.to_original(defect.farthest);
+            
+            writer.write_record(&[
+                region.to_string(),
+                i.to_string(),
+                defect.start_index.to_string(),
+                defect.end_index.to_string(),
+                defect.farthest_index.to_string(),
+                format!("{:.2}", start.0),
+                format!("{:.2}", start.1),
+                format!("{:.2}", end.0),
+                format!("{:.2}", end.1),
+                format!("{:.2}", farthest.0),
+                format!("{:.2}", farthest.1),
+                format!("{:.3}", defect.depth),
+                format!("{:.3}", relative_depth),
+            ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+        }
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the marginal teeth to CSV
+///
+/// # Arguments
+/// * `teeth` - Teeth detected on the EC margin
+/// * `orientation` - Orientation transform, to report points in the analysed image
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - Tooth_Index (contour order)
+/// - Apex_X, Apex_Y, Sinus_X, Sinus_Y (in the analysed image; sinus NA for a single tooth)
+/// - Base_Start_X, Base_Start_Y, Base_End_X, Base_End_Y (ends of the base line, in the analysed image)
+/// - Height, Width (pixels), Area (pixels)
+/// - Spacing (margin arc length to the next apex, pixels)
+pub fn write_teeth_csv<P: AsRef<Path>>(
+    teeth: &TeethMetrics,
+    orientation: &OrientationTransform,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("Teeth").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    writer.write_record(&[
+        "Tooth_Index",
+        "Apex_X",
+        "Apex_Y",
+        "Sinus_X",
+        "Sinus_Y",
+        "Base_Start_X",
+        "Base_Start_Y",
+        "Base_End_X",
+        "Base_End_Y",
+        "Height",
+        "Width",
+        "Area",
+        "Spacing",
+    ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (i, tooth) in teeth.teeth.iter().enumerate() {
+        let apex = orientation.to_original((tooth.apex.0 as f64, tooth.apex.1 as f64));
+        let sinus = tooth.sinus.map(|(x, y)| orientation.to_original((x as f64, y as f64)));
+        let base_start = orientation.to_original((tooth.base.0.0 as f64, tooth.base.0.1 as f64));
+        let base_end = orientation.to_original((tooth.base.1.0 as f64, tooth.base.1.1 as f64));
+        let sinus_value = |value: fn((f64, f64)) -> f64| {
+            sinus.map_or_else(|| "NA".to_string(), |point| format!("{:.0}", value(point)))
+        };
+        
+        writer.write_record(&[
+            i.to_string(),
+            format!("{:.0}", apex.0),
+            format!("{:.0}", apex.1),
+            sinus_value(|point| point.0),
+            sinus_value(|point| point.1),
+            format!("{:.0}", base_start.0),
+            format!("{:.0}", base_start.1),
+            format!("{:.0}", base_end.0),
+            format!("{:.0}", base_end.1),
+            format!("{:.2}", tooth.height),
+            format!("{:.2}", tooth.width),
+            tooth.area.to_string(),
+            format!("{:.2}", tooth.spacing),
+        ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Write the lobes segmented from the golden regions to CSV
+///
+/// # Arguments
+/// * `lobes` - Lobes of the MC leaf
+/// * `orientation` - Orientation transform, to report points in the analysed image
+/// * `output_dir` - Base output directory
+/// * `filename` - Name of the input file (without extension)
+///
+/// # Output Columns
+/// - Lobe_Index (contour order)
+/// - Start_Index, End_Index (MC contour span; End_Index may exceed the contour length when wrapping)
+/// - Apex_X, Apex_Y, Sinus_X, Sinus_Y (in the analysed image; sinus NA for a single lobe)
+/// - Base_Start_X, Base_Start_Y, Base_End_X, Base_End_Y (ends of the base line, in the analysed image)
+/// - Area (pixels), Length, Width, Sinus_Depth (pixels)
+pub fn write_lobes_csv<P: AsRef<Path>>(
+    lobes: &LobeMetrics,
+    orientation: &OrientationTransform,
+    output_dir: P,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.as_ref().join("Lobes").join(format!("{}.csv", filename));
+    
+    // Create directory if it doesn't exist
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| LeafComplexError::Io(e))?;
+    }
+    
+    // Create CSV writer
+    let mut writer = Writer::from_path(&output_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write header
+    writer.write_record(&[
+        "Lobe_Index",
+        "Start_Index",
+        "End_Index",
+        "Apex_X",
+        "Apex_Y",
+        "Sinus_X",
+        "Sinus_Y",
+        "Base_Start_X",
+        "Base_Start_Y",
+        "Base_End_X",
+        "Base_End_Y",
+        "Area",
+        "Length",
+        "Width",
+        "Sinus_Depth",
+    ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    // Write data
+    for (i, lobe) in lobes.lobes.iter().enumerate() {
+        let apex = orientation.to_original((lobe.apex.0 as f64, lobe.apex.1 as f64));
+        let sinus = lobe.sinus.map(|(x, y)| orientation.to_original((x as f64, y as f64)));
+        let base_start = orientation.to_original((lobe.base.0.0 as f64, lobe.base.0.1 as f64));
+        let base_end = orientation.to_original((lobe.base.1.0 as f64, lobe.base.1.1 as f64));
+        let sinus_value = |value: fn((f64, f64)) -> f64| {
+            sinus.map_or_else(|| "NA".to_string(), |point| format!("{:.0}", value(point)))
+        };
+        
+        writer.write_record(&[
+            i.to_string(),
+            lobe.start_index.to_string(),
+            lobe.end_index.to_string(),
+            format!("{:.0}", apex.0),
+            format!("{:.0}", apex.1),
+            sinus_value(|point| point.0),
+            sinus_value(|point| point.1),
+            format!("{:.0}", base_start.0),
+            format!("{:.0}", base_start.1),
+            format!("{:.0}", base_end.0),
+            format!("{:.0}", base_end.1),
+            lobe.area.to_string(),
+            format!("{:.2}", lobe.length),
+            format!("{:.2}", lobe.width),
+            format!("{:.2}", lobe.sinus_depth),
+        ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    }
+    
+    // Flush writer
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
+
+/// Per-leaf metrics written as one row of the summary CSV
+#[derive(Debug, Clone, Copy)]
+pub struct SummaryRow<'a> {
+    /// Name of the input file (without extension)
+    pub filename: &'a str,
+    /// Subfolder name for organization
+    pub subfolder: &'a str,
+    /// Spectral entropy from MC analysis
+    pub mc_spectral_entropy: f64,
+    /// Entropy of the EC signal from the estimator selected by `ec_entropy_method`
+    pub ec_entropy: f64,
+    /// Biological length from EC contour
+    pub ec_length: f64,
+    /// Biological length from MC contour
+    pub mc_length: f64,
+    /// Biological width from EC contour
+    pub ec_width: f64,
+    /// Biological width from MC contour
+    pub mc_width: f64,
+    /// Shape index from EC analysis
+    pub ec_shape_index: f64,
+    /// Shape index from MC analysis
+    pub mc_shape_index: f64,
+    /// Number of contour points
+    pub outline_count: u32,
+    /// Number of harmonic chains detected
+    pub harmonic_chain_count: usize,
+    /// Margin-to-margin geodesic descriptors of the MC leaf
+    pub geodesic_metrics: &'a GeodesicMetrics,
+    /// Foreground policy and effective thresholds used for the leaf mask
+    pub alpha_thresholds: &'a AlphaThresholds,
+    /// Holes enclosed by the leaf
+    pub hole_metrics: &'a HoleMetrics,
+    /// Pattern spectrum of the whole leaf, if computed
+    pub ec_granulometry: Option<&'a PatternSpectrum>,
+    /// Pattern spectrum of the MC region, if computed
+    pub mc_granulometry: Option<&'a PatternSpectrum>,
+    /// Pruned MC skeleton, if computed
+    pub skeleton: Option<&'a Skeleton>,
+    /// Convex hull metrics of the whole leaf
+    pub ec_hull: &'a ConvexHullMetrics,
+    /// Convex hull metrics of the MC region
+    pub mc_hull: &'a ConvexHullMetrics,
+    /// Feret diameters and minimum bounding rectangle of the whole leaf
+    pub ec_feret: &'a FeretDiameters,
+    /// Feret diameters and minimum bounding rectangle of the MC region
+    pub mc_feret: &'a FeretDiameters,
+    /// Orientation transform, to report points in the analysed image
+    pub orientation: &'a OrientationTransform,
+    /// Petiole detected on the mask, if any
+    pub petiole: Option<&'a Petiole>,
+    /// Apex, apex/base angles and position of maximum width
+    pub architecture: &'a LeafArchitecture,
+    /// Marginal teeth of the EC margin
+    pub teeth: &'a TeethMetrics,
+    /// Lobes of the MC leaf
+    pub lobes: &'a LobeMetrics,
+    /// Dissection and compactness indices of the whole leaf
+    pub ec_indices: &'a ShapeIndices,
+    /// Dissection and compactness indices of the MC region
+    pub mc_indices: &'a ShapeIndices,
+    /// Fractal dimensions of the EC margin, if computed
+    pub ec_fractal: Option<&'a FractalMetrics>,
+    /// Fractal dimensions of the MC margin, if computed
+    pub mc_fractal: Option<&'a FractalMetrics>,
+    /// Estimator reported in the EC column
+    pub ec_entropy_method: EntropyMethod,
+    /// All entropy estimators of the EC signal
+    pub ec_entropies: &'a EntropyMetrics,
+}
+
+/// Create summary CSV with aggregate metrics
+///
+/// # Arguments
+/// * `output_dir` - Base output directory
+/// * `row` - Metrics of the analysed leaf
 ///
 /// # Output Columns
 /// - ID
 /// - Subfolder
 /// - MC (Spectral entropy from margin complexity)
-/// - EC (Approximate entropy from edge complexity)
+/// - EC (Edge complexity entropy from the estimator selected by `EC_Entropy_Method`)
 /// - EC_Length, MC_Length
 /// - EC_Width, MC_Width
 /// - EC_ShapeIndex, MC_ShapeIndex
 /// - Outline_Count
 /// - Harmonic_Chain_Count
+/// - Geodesic_Diameter, Mean_Tortuosity
+/// - Eccentricity_Mean, Eccentricity_Std, Eccentricity_Min, Eccentricity_Max
+/// - Alpha_Policy, Alpha_Threshold_Low, Alpha_Threshold_High
+/// - Hole_Count, Hole_Area, Largest_Hole_Area, Percent_Area_Missing
+/// - EC_Granulometry_Mean_Scale, EC_Granulometry_Entropy (NA when not computed)
+/// - MC_Granulometry_Mean_Scale, MC_Granulometry_Entropy (NA when not computed)
+/// - Skeleton_Length, Skeleton_Branch_Count, Skeleton_Endpoint_Count, Skeleton_Junction_Count,
+///   Skeleton_Max_Inscribed_Radius, Skeleton_Mean_Inscribed_Radius (NA when not computed)
+/// - EC_Solidity, EC_Convexity, EC_Defect_Count, EC_Max_Defect_Depth, EC_Mean_Defect_Depth
+/// - MC_Solidity, MC_Convexity, MC_Defect_Count, MC_Max_Defect_Depth, MC_Mean_Defect_Depth
+/// - EC_Max_Feret, EC_Max_Feret_Angle, EC_Min_Feret, EC_Min_Feret_Angle,
+///   EC_Min_Rect_Length, EC_Min_Rect_Width, EC_Min_Rect_Angle
+/// - MC_Max_Feret, MC_Max_Feret_Angle, MC_Min_Feret, MC_Min_Feret_Angle,
+///   MC_Min_Rect_Length, MC_Min_Rect_Width, MC_Min_Rect_Angle
+/// - Orientation_Method, Orientation_Angle, Orientation_Base_X, Orientation_Base_Y
+///   (base is NA when orientation normalisation is off)
+/// - Petiole_Length, Petiole_Width, Petiole_Base_X, Petiole_Base_Y (NA when no petiole was detected)
+/// - Apex_X, Apex_Y, Base_Apex_Length, Apex_Angle, Base_Angle, Max_Width_Position
+/// - Tooth_Count, Teeth_Per_Cm, Mean_Tooth_Area_Over_Perimeter, Mean_Tooth_Height,
+///   Mean_Tooth_Width, Mean_Tooth_Spacing
+/// - Lobe_Count, Lobe_Area_Fraction, Mean_Lobe_Area, Median_Lobe_Area, Max_Lobe_Area,
+///   Lobe_Area_CV, Mean_Sinus_Depth
+/// - EC_Dissection_Index, EC_Normalized_Dissection_Index, EC_Perimeter_Area_Ratio,
+///   EC_Form_Factor, EC_Roundness, EC_Aspect_Ratio
+/// - MC_Dissection_Index, MC_Normalized_Dissection_Index, MC_Perimeter_Area_Ratio,
+///   MC_Form_Factor, MC_Roundness, MC_Aspect_Ratio
+/// - EC_Box_Dimension, EC_Box_R2, EC_Minkowski_Dimension, EC_Minkowski_R2 (NA when not computed)
+/// - MC_Box_Dimension, MC_Box_R2, MC_Minkowski_Dimension, MC_Minkowski_R2 (NA when not computed)
+/// - EC_Entropy_Method, EC_Approximate_Entropy, EC_Sample_Entropy, EC_Permutation_Entropy,
+///   EC_Multiscale_Entropy
 pub fn create_summary<P: AsRef<Path>>(
     output_dir: P,
-    filename: &str,
-    subfolder: &str,
-    mc_spectral_entropy: f64,
-    ec_approximate_entropy: f64,
-    ec_length: f64,
-    mc_length: f64,
-    ec_width: f64,
-    mc_width: f64,
-    ec_shape_index: f64,
-    mc_shape_index: f64,
-    outline_count: u32,
-    harmonic_chain_count: usize,
+    row: &SummaryRow,
 ) -> Result<()> {
+    let SummaryRow {
+        filename, subfolder, mc_spectral_entropy, ec_entropy,
+        ec_length, mc_length, ec_width, mc_width, ec_shape_index, mc_shape_index,
+        outline_count, harmonic_chain_count, geodesic_metrics, alpha_thresholds, hole_metrics,
+        ec_granulometry, mc_granulometry, skeleton, ec_hull, mc_hull, ec_feret, mc_feret,
+        orientation, petiole, architecture, teeth, lobes, ec_indices, mc_indices,
+        ec_fractal, mc_fractal, ec_entropy_method, ec_entropies,
+    } = *row;
+    
     // Summary goes directly in output directory
     let summary_path = output_dir.as_ref().join("summary.csv");
     
@@ -190,17 +843,135 @@ pub fn create_summary<P: AsRef<Path>>(
             "MC_ShapeIndex",
             "Outline_Count",
             "Harmonic_Chain_Count",
+            "Geodesic_Diameter",
+            "Mean_Tortuosity",
+            "Eccentricity_Mean",
+            "Eccentricity_Std",
+            "Eccentricity_Min",
+            "Eccentricity_Max",
+            "Alpha_Policy",
+            "Alpha_Threshold_Low",
+            "Alpha_Threshold_High",
+            "Hole_Count",
+            "Hole_Area",
+            "Largest_Hole_Area",
+            "Percent_Area_Missing",
+            "EC_Granulometry_Mean_Scale",
+            "EC_Granulometry_Entropy",
+            "MC_Granulometry_Mean_Scale",
+            "MC_Granulometry_Entropy",
+            "Skeleton_Length",
+            "Skeleton_Branch_Count",
+            "Skeleton_Endpoint_Count",
+            "Skeleton_Junction_Count",
+            "Skeleton_Max_Inscribed_Radius",
+            "Skeleton_Mean_Inscribed_Radius",
+            "EC_Solidity",
+            "EC_Convexity",
+            "EC_Defect_Count",
+            "EC_Max_Defect_Depth",
+            "EC_Mean_Defect_Depth",
+            "MC_Solidity",
+            "MC_Convexity",
+            "MC_Defect_Count",
+            "MC_Max_Defect_Depth",
+            "MC_Mean_Defect_Depth",
+            "EC_Max_Feret",
+            "EC_Max_Feret_Angle",
+            "EC_Min_Feret",
+            "EC_Min_Feret_Angle",
+            "EC_Min_Rect_Length",
+            "EC_Min_Rect_Width",
+            "EC_Min_Rect_Angle",
+            "MC_Max_Feret",
+            "MC_Max_Feret_Angle",
+            "MC_Min_Feret",
+            "MC_Min_Feret_Angle",
+            "MC_Min_Rect_Length",
+            "MC_Min_Rect_Width",
+            "MC_Min_Rect_Angle",
+            "Orientation_Method",
+            "Orientation_Angle",
+            "Orientation_Base_X",
+            "Orientation_Base_Y",
+            "Petiole_Length",
+            "Petiole_Width",
+            "Petiole_Base_X",
+            "Petiole_Base_Y",
+            "Apex_X",
+            "Apex_Y",
+            "Base_Apex_Length",
+            "Apex_Angle",
+            "Base_Angle",
+            "Max_Width_Position",
+            "Tooth_Count",
+            "Teeth_Per_Cm",
+            "Mean_Tooth_Area_Over_Perimeter",
+            "Mean_Tooth_Height",
+            "Mean_Tooth_Width",
+            "Mean_Tooth_Spacing",
+            "Lobe_Count",
+            "Lobe_Area_Fraction",
+            "Mean_Lobe_Area",
+            "Median_Lobe_Area",
+            "Max_Lobe_Area",
+            "Lobe_Area_CV",
+            "Mean_Sinus_Depth",
+            "EC_Dissection_Index",
+            "EC_Normalized_Dissection_Index",
+            "EC_Perimeter_Area_Ratio",
+            "EC_Form_Factor",
+            "EC_Roundness",
+            "EC_Aspect_Ratio",
+            "MC_Dissection_Index",
+            "MC_Normalized_Dissection_Index",
+            "MC_Perimeter_Area_Ratio",
+            "MC_Form_Factor",
+            "MC_Roundness",
+            "MC_Aspect_Ratio",
+            "EC_Box_Dimension",
+            "EC_Box_R2",
+            "EC_Minkowski_Dimension",
+            "EC_Minkowski_R2",
+            "MC_Box_Dimension",
+            "MC_Box_R2",
+            "MC_Minkowski_Dimension",
+            "MC_Minkowski_R2",
+            "EC_Entropy_Method",
+            "EC_Approximate_Entropy",
+            "EC_Sample_Entropy",
+            "EC_Permutation_Entropy",
+            "EC_Multiscale_Entropy",
         ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
         
         writer
     };
     
+    let granulometry_value = |spectrum: Option<&PatternSpectrum>, value: fn(&PatternSpectrum) -> f64| {
+        spectrum.map_or_else(|| "NA".to_string(), |s| format!("{:.4}", value(s)))
+    };
+    
+    let fractal_value = |fractal: Option<&FractalMetrics>, fit: fn(&FractalMetrics) -> &FractalFit, value: fn(&FractalFit) -> f64| {
+        fractal.map_or_else(|| "NA".to_string(), |f| format!("{:.4}", value(fit(f))))
+    };
+    
+    let skeleton_value = |value: fn(&Skeleton) -> String| skeleton.map_or_else(|| "NA".to_string(), value);
+    
+    let orientation_value = |value: f64| if orientation.is_identity() { "NA".to_string() } else { format!("{:.1}", value) };
+    
+    let petiole_value = |value: fn(&Petiole) -> String| petiole.map_or_else(|| "NA".to_string(), value);
+    let apex = orientation.to_original(architecture.apex);
+    let petiole_base = petiole.map(|p| orientation.to_original((p.base.0 as f64, p.base.1 as f64)));
+    let petiole_base_value = |value: fn((f64, f64)) -> f64| {
+        petiole_base.map_or_else(|| "NA".to_string(), |base| format!("{:.1}", value(base)))
+    };
+    
     // Write data row
     writer.write_record(&[
         filename,
         subfolder,
         &format!("{:.6}", mc_spectral_entropy),
-        &format!("{:.6}", ec_approximate_entropy),
+        &format!("{:.6}", ec_entropy),
         &format!("{:.1}", ec_length),
         &format!("{:.1}", mc_length),
         &format!("{:.1}", ec_width),
@@ -209,6 +980,105 @@ pub fn create_summary<P: AsRef<Path>>(
         &format!("{:.3}", mc_shape_index),
         &outline_count.to_string(),
         &harmonic_chain_count.to_string(),
+        &format!("{:.1}", geodesic_metrics.diameter),
+        &format!("{:.4}", geodesic_metrics.mean_tortuosity),
+        &format!("{:.1}", geodesic_metrics.eccentricity_mean),
+        &format!("{:.1}", geodesic_metrics.eccentricity_std),
+        &format!("{:.1}", geodesic_metrics.eccentricity_min),
+        &format!("{:.1}", geodesic_metrics.eccentricity_max),
+        alpha_thresholds.policy.name(),
+        &alpha_thresholds.low.to_string(),
+        &alpha_thresholds.high.to_string(),
+        &hole_metrics.hole_count.to_string(),
+        &hole_metrics.total_hole_area.to_string(),
+        &hole_metrics.largest_hole_area.to_string(),
+        &format!("{:.3}", hole_metrics.percent_area_missing),
+        &granulometry_value(ec_granulometry, |s| s.mean_scale),
+        &granulometry_value(ec_granulometry, |s| s.entropy),
+        &granulometry_value(mc_granulometry, |s| s.mean_scale),
+        &granulometry_value(mc_granulometry, |s| s.entropy),
+        &skeleton_value(|s| format!("{:.1}", s.length)),
+        &skeleton_value(|s| s.branch_count.to_string()),
+        &skeleton_value(|s| s.endpoint_count.to_string()),
+        &skeleton_value(|s| s.junction_count.to_string()),
+        &skeleton_value(|s| format!("{:.1}", s.max_inscribed_radius)),
+        &skeleton_value(|s| format!("{:.1}", s.mean_inscribed_radius)),
+        &format!("{:.4}", ec_hull.solidity),
+        &format!("{:.4}", ec_hull.convexity),
+        &ec_hull.defects.len().to_string(),
+        &format!("{:.1}", ec_hull.max_defect_depth()),
+        &format!("{:.1}", ec_hull.mean_defect_depth()),
+        &format!("{:.4}", mc_hull.solidity),
+        &format!("{:.4}", mc_hull.convexity),
+        &mc_hull.defects.len().to_string(),
+        &format!("{:.1}", mc_hull.max_defect_depth()),
+        &format!("{:.1}", mc_hull.mean_defect_depth()),
+        &format!("{:.1}", ec_feret.max_feret),
+        &format!("{:.1}", ec_feret.max_feret_angle),
+        &format!("{:.1}", ec_feret.min_feret),
+        &format!("{:.1}", ec_feret.min_feret_angle),
+        &format!("{:.1}", ec_feret.min_rect_length),
+        &format!("{:.1}", ec_feret.min_rect_width),
+        &format!("{:.1}", ec_feret.min_rect_angle),
+        &format!("{:.1}", mc_feret.max_feret),
+        &format!("{:.1}", mc_feret.max_feret_angle),
+        &format!("{:.1}", mc_feret.min_feret),
+        &format!("{:.1}", mc_feret.min_feret_angle),
+        &format!("{:.1}", mc_feret.min_rect_length),
+        &format!("{:.1}", mc_feret.min_rect_width),
+        &format!("{:.1}", mc_feret.min_rect_angle),
+        orientation.method.name(),
+        &format!("{:.2}", orientation.angle_degrees),
+        &orientation_value(orientation.base.0),
+        &orientation_value(orientation.base.1),
+        &petiole_value(|p| format!("{:.1}", p.length)),
+        &petiole_value(|p| format!("{:.1}", p.width)),
+        &petiole_base_value(|base| base.0),
+        &petiole_base_value(|base| base.1),
+        &format!("{:.1}", apex.0),
+        &format!("{:.1}", apex.1),
+        &format!("{:.1}", architecture.length),
+        &format!("{:.1}", architecture.apex_angle),
+        &format!("{:.1}", architecture.base_angle),
+        &format!("{:.3}", architecture.max_width_position),
+        &teeth.teeth.len().to_string(),
+        &format!("{:.3}", teeth.teeth_per_cm),
+        &format!("{:.4}", teeth.mean_area_over_perimeter),
+        &format!("{:.2}", teeth.mean_height),
+        &format!("{:.2}", teeth.mean_width),
+        &format!("{:.2}", teeth.mean_spacing),
+        &lobes.lobes.len().to_string(),
+        &format!("{:.4}", lobes.area_fraction),
+        &format!("{:.1}", lobes.mean_area),
+        &format!("{:.1}", lobes.median_area),
+        &lobes.max_area.to_string(),
+        &format!("{:.4}", lobes.area_cv),
+        &format!("{:.2}", lobes.mean_sinus_depth),
+        &format!("{:.4}", ec_indices.dissection_index),
+        &format!("{:.4}", ec_indices.normalized_dissection_index),
+        &format!("{:.6}", ec_indices.perimeter_area_ratio),
+        &format!("{:.4}", ec_indices.form_factor),
+        &format!("{:.4}", ec_indices.roundness),
+        &format!("{:.4}", ec_indices.aspect_ratio),
+        &format!("{:.4}", mc_indices.dissection_index),
+        &format!("{:.4}", mc_indices.normalized_dissection_index),
+        &format!("{:.6}", mc_indices.perimeter_area_ratio),
+        &format!("{:.4}", mc_indices.form_factor),
+        &format!("{:.4}", mc_indices.roundness),
+        &format!("{:.4}", mc_indices.aspect_ratio),
+        &fractal_value(ec_fractal, |f| &f.box_counting, |fit| fit.dimension),
+        &fractal_value(ec_fractal, |f| &f.box_counting, |fit| fit.r_squared),
+        &fractal_value(ec_fractal, |f| &f.minkowski, |fit| fit.dimension),
+        &fractal_value(ec_fractal, |f| &f.minkowski, |fit| fit.r_squared),
+        &fractal_value(mc_fractal, |f| &f.box_counting, |fit| fit.dimension),
+        &fractal_value(mc_fractal, |f| &f.box_counting, |fit| fit.r_squared),
+        &fractal_value(mc_fractal, |f| &f.minkowski, |fit| fit.dimension),
+        &fractal_value(mc_fractal, |f| &f.minkowski, |fit| fit.r_squared),
+        ec_entropy_method.name(),
+        &format!("{:.6}", ec_entropies.approximate),
+        &format!("{:.6}", ec_entropies.sample),
+        &format!("{:.6}", ec_entropies.permutation),
+        &format!("{:.6}", ec_entropies.multiscale),
     ]).map_err(|e| LeafComplexError::CsvOutput(e))?;
     
     // Flush writer
@@ -216,3 +1086,91 @@ pub fn create_summary<P: AsRef<Path>>(
     
     Ok(())
 }
+
+/// Render the EC marking: leaf pixels in their original colour, marked pixels
+/// (opened or outside the MC leaf) in the marked colour
+///
+/// # Arguments
+/// * `original` - Processed input image
+/// * `mask` - Leaf mask of the same image
+/// * `marked_color` - RGB color for marked pixels
+pub fn render_marked_image(original: &RgbaImage, mask: &LeafMask, marked_color: [u8; 3]) -> RgbaImage {
+    let (width, height) = mask.dimensions();
+    
+    RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = *original.get_pixel(x, y);
+        if mask.is_ec_marked(x, y) {
+            Rgba([marked_color[0], marked_color[1], marked_color[2], pixel[3]])
+        } else if mask.is_leaf(x, y) {
+            pixel
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+/// Render the Thornfiddle image: MC leaf in its original colour with the
+/// lobe regions in the golden colour
+///
+/// # Arguments
+/// * `original` - Processed input image
+/// * `mask` - Leaf mask of the same image
+/// * `golden_color` - RGB color for lobe pixels
+pub fn render_thornfiddle_image(original: &RgbaImage, mask: &LeafMask, golden_color: [u8; 3]) -> RgbaImage {
+    let (width, height) = mask.dimensions();
+    
+    RgbaImage::from_fn(width, height, |x, y| {
+        let pixel = *original.get_pixel(x, y);
+        if mask.is_lobe(x, y) {
+            Rgba([golden_color[0], golden_color[1], golden_color[2], pixel[3]])
+        } else if mask.is_mc(x, y) {
+            pixel
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+/// Render the skeleton over the MC leaf
+///
+/// # Arguments
+/// * `original` - Processed input image
+/// * `mask` - Leaf mask of the same image
+/// * `skeleton` - Skeleton pixels
+/// * `skeleton_color` - RGB color of skeleton pixels
+pub fn render_skeleton_image(
+    original: &RgbaImage, 
+    mask: &LeafMask, 
+    skeleton: &BinaryMask, 
+    skeleton_color: [u8; 3],
+) -> RgbaImage {
+    let (width, height) = mask.dimensions();
+    
+    RgbaImage::from_fn(width, height, |x, y| {
+        if skeleton.get(x, y) {
+            Rgba([skeleton_color[0], skeleton_color[1], skeleton_color[2], 255])
+        } else if mask.is_mc(x, y) {
+            *original.get_pixel(x, y)
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
+
+/// Render the pixels having any of the given layer bits as an opaque overlay
+///
+/// # Arguments
+/// * `mask` - Leaf mask
+/// * `layer` - Layer bits to render (e.g. `EC_OPENED | MC_REMOVED`)
+/// * `color` - RGB color of the overlay
+pub fn render_layer_overlay(mask: &LeafMask, layer: u8, color: [u8; 3]) -> RgbaImage {
+    let (width, height) = mask.dimensions();
+    
+    RgbaImage::from_fn(width, height, |x, y| {
+        if mask.has(x, y, layer) {
+            Rgba([color[0], color[1], color[2], 255])
+        } else {
+            Rgba([0, 0, 0, 0])
+        }
+    })
+}
@@ -1,8 +1,10 @@
 // src/path_algorithms.rs - Path analysis algorithms for geodesic calculations
 
-use image::RgbaImage;
 use bresenham::Bresenham;
-use std::collections::{VecDeque, HashMap};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque, HashMap};
+
+use crate::leaf_mask::{BinaryMask, LeafMask};
 
 /// Trace a straight line path between two points using Bresenham's algorithm
 ///
@@ -26,19 +28,19 @@ pub fn trace_straight_line(
     line.map(|(x, y)| (x as u32, y as u32)).collect()
 }
 
-/// Check if a straight line path crosses any transparent pixels
+/// Check if a straight line path leaves the region
 ///
 /// # Arguments
 /// * `line_points` - Points along the line to check
-/// * `image` - Image to check transparency against
+/// * `region` - Region the line should stay within
 ///
 /// # Returns
-/// true if any pixel along the path is transparent (excluding endpoints)
+/// true if any pixel along the path is outside the region (excluding endpoints)
 pub fn check_straight_line_transparency(
     line_points: &[(u32, u32)],
-    image: &RgbaImage,
+    region: &BinaryMask,
 ) -> bool {
-    let (width, height) = image.dimensions();
+    let (width, height) = region.dimensions();
     
     // Skip start and end points in the check
     if line_points.len() <= 2 {
@@ -48,13 +50,9 @@ pub fn check_straight_line_transparency(
     for i in 1..(line_points.len() - 1) {
         let (x, y) = line_points[i];
         
-        if x < width && y < height {
-            let pixel = image.get_pixel(x, y);
-            
-            // If any pixel along the path is transparent, return true
-            if pixel[3] == 0 {
-                return true;
-            }
+        // If any pixel along the path is outside the region, return true
+        if x < width && y < height && !region.get(x, y) {
+            return true;
         }
     }
     
@@ -81,35 +79,34 @@ pub fn calculate_straight_path_length(
 
 /// Calculate the geodesic path (Diego path) that stays within the leaf
 ///
-/// Uses BFS to find the shortest path through non-transparent pixels.
-/// If a straight line doesn't cross transparency, returns the straight line.
+/// Uses BFS to find the shortest path through region pixels.
+/// If a straight line doesn't leave the region, returns the straight line.
 ///
 /// # Arguments
 /// * `reference_point` - Starting point (reference point)
 /// * `margin_point` - Target point (marginal/contour point)
-/// * `image` - Image to navigate through
+/// * `region` - Region to navigate through
 ///
 /// # Returns
 /// Vector of pixel coordinates forming the geodesic path
 pub fn calculate_diego_path(
     reference_point: (u32, u32),
     margin_point: (u32, u32),
-    image: &RgbaImage
+    region: &BinaryMask
 ) -> Vec<(u32, u32)> {
-    // First, check if the straight line path crosses transparency
+    // First, check if the straight line path leaves the region
     let straight_line = trace_straight_line(reference_point, margin_point);
     
-    if !check_straight_line_transparency(&straight_line, image) {
+    if !check_straight_line_transparency(&straight_line, region) {
         // No transparency issues, use straight line
         return straight_line;
     }
     
-    // Find the last non-transparent point on the straight line
+    // Find the last region point on the straight line
     let mut path = Vec::new();
     
     for &point in &straight_line {
-        let pixel = image.get_pixel(point.0, point.1);
-        if pixel[3] == 0 {
+        if !region.get(point.0, point.1) {
             break;
         }
         path.push(point);
@@ -124,7 +121,7 @@ pub fn calculate_diego_path(
     let start_point = path[path.len() - 1];
     
     // BFS to find the shortest path to the margin point
-    let (width, height) = image.dimensions();
+    let (width, height) = region.dimensions();
     let mut queue = VecDeque::new();
     let mut visited = HashMap::new(); // maps point -> previous point for path reconstruction
     
@@ -168,9 +165,8 @@ pub fn calculate_diego_path(
                 continue;
             }
             
-            // Skip transparent pixels
-            let pixel = image.get_pixel(next.0, next.1);
-            if pixel[3] == 0 {
+            // Skip pixels outside the region
+            if !region.get(next.0, next.1) {
                 continue;
             }
             
@@ -241,26 +237,242 @@ pub fn calculate_diego_path_length(path: &[(u32, u32)]) -> f64 {
 ///
 /// # Arguments
 /// * `path` - Vector of pixel coordinates forming the path
-/// * `marked_image` - Image with marked regions (pink pixels)
-/// * `pink_color` - RGB color of the marked regions
+/// * `mask` - Leaf mask with the EC marked layers
 ///
 /// # Returns
 /// Count of pink pixels crossed by the path
 pub fn calculate_diego_path_pink(
     path: &[(u32, u32)],
-    marked_image: &RgbaImage,
-    pink_color: [u8; 3]
+    mask: &LeafMask,
 ) -> u32 {
     let mut pink_count = 0;
     
     for &(x, y) in path {
-        let pixel = marked_image.get_pixel(x, y);
-        
-        // Check if pixel has the pink color
-        if pixel[0] == pink_color[0] && pixel[1] == pink_color[1] && pixel[2] == pink_color[2] {
+        // Check if pixel is marked for EC
+        if mask.is_ec_marked(x, y) {
             pink_count += 1;
         }
     }
     
     pink_count
 }
+
+
+/// Geodesic shape descriptors measured between pairs of margin points
+///
+/// Unlike the Diego path features these do not depend on a reference point.
+#[derive(Debug, Clone, Default)]
+pub struct GeodesicMetrics {
+    /// Longest shortest path inside the leaf between two margin points
+    pub diameter: f64,
+    /// Mean ratio of geodesic to Euclidean distance over sampled margin pairs
+    pub mean_tortuosity: f64,
+    /// Mean geodesic eccentricity of the sampled margin points
+    pub eccentricity_mean: f64,
+    /// Standard deviation of the geodesic eccentricity
+    pub eccentricity_std: f64,
+    /// Smallest geodesic eccentricity (geodesic radius)
+    pub eccentricity_min: f64,
+    /// Largest geodesic eccentricity
+    pub eccentricity_max: f64,
+}
+
+/// Priority queue entry for Dijkstra (ordered so the smallest distance pops first)
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct DistanceNode {
+    distance: f64,
+    index: usize,
+}
+
+impl Eq for DistanceNode {}
+
+impl Ord for DistanceNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.distance.partial_cmp(&self.distance).unwrap_or(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for DistanceNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Calculate geodesic distances from a source pixel to every pixel of the leaf
+///
+/// Runs Dijkstra on the 8-connected pixel grid (unit cost for cardinal steps,
+/// √2 for diagonal steps) restricted to region pixels.
+///
+/// # Arguments
+/// * `source` - Starting pixel
+/// * `region` - Region to navigate through
+///
+/// # Returns
+/// Row-major distance map; unreachable pixels are `f64::INFINITY`
+pub fn calculate_geodesic_distance_map(
+    source: (u32, u32),
+    region: &BinaryMask,
+) -> Vec<f64> {
+    calculate_geodesic_shortest_path_tree(source, region).0
+}
+
+/// Calculate the geodesic shortest-path tree rooted at a source pixel
+///
+/// Same search as `calculate_geodesic_distance_map`, but additionally records
+/// the predecessor of every reached pixel so shortest paths can be walked back
+/// to the source.
+///
+/// # Arguments
+/// * `source` - Starting pixel
+/// * `region` - Region to navigate through
+///
+/// # Returns
+/// Row-major `(distances, predecessors)`; unreachable pixels have distance
+/// `f64::INFINITY`, and the source and unreachable pixels have predecessor `usize::MAX`
+pub fn calculate_geodesic_shortest_path_tree(
+    source: (u32, u32),
+    region: &BinaryMask,
+) -> (Vec<f64>, Vec<usize>) {
+    let (width, height) = region.dimensions();
+    let mut distances = vec![f64::INFINITY; (width * height) as usize];
+    let mut predecessors = vec![usize::MAX; (width * height) as usize];
+    
+    if source.0 >= width || source.1 >= height || !region.get(source.0, source.1) {
+        return (distances, predecessors);
+    }
+    
+    let directions = [
+        (0, 1, 1.0), (1, 0, 1.0), (0, -1, 1.0), (-1, 0, 1.0),
+        (1, 1, std::f64::consts::SQRT_2), (1, -1, std::f64::consts::SQRT_2),
+        (-1, 1, std::f64::consts::SQRT_2), (-1, -1, std::f64::consts::SQRT_2),
+    ];
+    
+    let source_index = (source.1 * width + source.0) as usize;
+    distances[source_index] = 0.0;
+    
+    let mut heap = BinaryHeap::new();
+    heap.push(DistanceNode { distance: 0.0, index: source_index });
+    
+    while let Some(DistanceNode { distance, index }) = heap.pop() {
+        if distance > distances[index] {
+            continue;
+        }
+        
+        let x = (index as u32 % width) as i32;
+        let y = (index as u32 / width) as i32;
+        
+        for &(dx, dy, cost) in &directions {
+            let nx = x + dx;
+            let ny = y + dy;
+            
+            if !region.get_or_false(nx, ny) {
+                continue;
+            }
+            
+            let next_index = (ny as u32 * width + nx as u32) as usize;
+            let next_distance = distance + cost;
+            
+            if next_distance < distances[next_index] {
+                distances[next_index] = next_distance;
+                predecessors[next_index] = index;
+                heap.push(DistanceNode { distance: next_distance, index: next_index });
+            }
+        }
+    }
+    
+    (distances, predecessors)
+}
+
+/// Calculate margin-to-margin geodesic metrics
+///
+/// Samples `sample_count` evenly spaced contour points, computes a geodesic
+/// distance map from each of them and derives the geodesic diameter, mean
+/// tortuosity over all sampled pairs and the eccentricity distribution.
+/// The eccentricity of a sampled point is its largest geodesic distance to
+/// any contour point. A final sweep from the farthest point found refines
+/// the diameter estimate.
+///
+/// # Arguments
+/// * `region` - Region to navigate through (MC region for margin metrics)
+/// * `contour` - Traced contour of the same region
+/// * `sample_count` - Number of margin points to sample
+///
+/// # Returns
+/// Geodesic metrics (all zero if the contour is too short)
+pub fn calculate_geodesic_metrics(
+    region: &BinaryMask,
+    contour: &[(u32, u32)],
+    sample_count: usize,
+) -> GeodesicMetrics {
+    if contour.len() < 2 || sample_count < 2 {
+        return GeodesicMetrics::default();
+    }
+    
+    let (width, _) = region.dimensions();
+    let sample_count = sample_count.min(contour.len());
+    let samples: Vec<(u32, u32)> = (0..sample_count)
+        .map(|i| contour[i * contour.len() / sample_count])
+        .collect();
+    
+    let mut eccentricities = Vec::with_capacity(sample_count);
+    let mut tortuosity_sum = 0.0;
+    let mut tortuosity_count = 0usize;
+    let mut diameter = 0.0;
+    let mut farthest_point = samples[0];
+    
+    for (i, &source) in samples.iter().enumerate() {
+        let distances = calculate_geodesic_distance_map(source, region);
+        
+        // Eccentricity over the full margin
+        let mut eccentricity: f64 = 0.0;
+        let mut eccentric_point = source;
+        for &point in contour {
+            let d = distances[(point.1 * width + point.0) as usize];
+            if d.is_finite() && d > eccentricity {
+                eccentricity = d;
+                eccentric_point = point;
+            }
+        }
+        eccentricities.push(eccentricity);
+        
+        if eccentricity > diameter {
+            diameter = eccentricity;
+            farthest_point = eccentric_point;
+        }
+        
+        // Tortuosity over the remaining sampled pairs
+        for &target in &samples[(i + 1)..] {
+            let geodesic = distances[(target.1 * width + target.0) as usize];
+            let euclidean = calculate_straight_path_length(source, target);
+            
+            if geodesic.is_finite() && euclidean > 0.0 {
+                tortuosity_sum += geodesic / euclidean;
+                tortuosity_count += 1;
+            }
+        }
+    }
+    
+    // Refine the diameter with one more sweep from the farthest point found
+    let distances = calculate_geodesic_distance_map(farthest_point, region);
+    for &point in contour {
+        let d = distances[(point.1 * width + point.0) as usize];
+        if d.is_finite() && d > diameter {
+            diameter = d;
+        }
+    }
+    
+    let n = eccentricities.len() as f64;
+    let eccentricity_mean = eccentricities.iter().sum::<f64>() / n;
+    let eccentricity_variance = eccentricities.iter()
+        .map(|&e| (e - eccentricity_mean).powi(2))
+        .sum::<f64>() / n;
+    
+    GeodesicMetrics {
+        diameter,
+        mean_tortuosity: if tortuosity_count > 0 { tortuosity_sum / tortuosity_count as f64 } else { 0.0 },
+        eccentricity_mean,
+        eccentricity_std: eccentricity_variance.sqrt(),
+        eccentricity_min: eccentricities.iter().cloned().fold(f64::INFINITY, f64::min),
+        eccentricity_max: eccentricities.iter().cloned().fold(0.0, f64::max),
+    }
+}
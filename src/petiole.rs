@@ -0,0 +1,158 @@
+// src/petiole.rs - Mask-level petiole detection from local width (distance transform)
+
+use crate::connected_components::{label_components, ComponentStats};
+use crate::errors::Result;
+use crate::leaf_mask::{BinaryMask, LeafMask, LEAF, PETIOLE};
+use crate::morphology::{apply_opening, distance_to_background};
+use crate::path_algorithms::calculate_geodesic_distance_map;
+
+/// Petiole detected on the leaf mask
+#[derive(Debug, Clone)]
+pub struct Petiole {
+    /// Petiole pixels (cut from the blade at the base)
+    pub mask: BinaryMask,
+    /// Petiole pixel where it joins the blade (blade base)
+    pub base: (u32, u32),
+    /// Petiole pixel farthest from the base along the petiole
+    pub tip: (u32, u32),
+    /// Geodesic length from base to tip
+    pub length: f64,
+    /// Mean width (area / length)
+    pub width: f64,
+    /// Number of petiole pixels
+    pub area: usize,
+}
+
+/// Thin component measured as a petiole candidate
+struct Candidate<'a> {
+    stats: &'a ComponentStats,
+    base: (u32, u32),
+    tip: (u32, u32),
+    length: f64,
+}
+
+/// Detect the petiole as the longest thin, elongated protrusion of the leaf
+///
+/// The local width of the leaf is read from the distance transform: an opening
+/// with a disc of `max_width_ratio` times the largest inscribed diameter keeps
+/// the blade and removes every part narrower than that disc. Each removed part
+/// attached to the blade is a candidate; its length is the geodesic distance
+/// from the attachment to its far end and its width is area / length. The
+/// longest candidate at least `min_elongation` times longer than wide is the
+/// petiole (teeth and narrow sinus margins are short).
+///
+/// # Arguments
+/// * `region` - Leaf region
+/// * `max_width_ratio` - Widest petiole relative to the largest inscribed diameter of the leaf
+/// * `min_elongation` - Minimum length / width of the petiole
+///
+/// # Returns
+/// The petiole, or None if no thin elongated protrusion is found
+pub fn detect_petiole(region: &BinaryMask, max_width_ratio: f64, min_elongation: f64) -> Result<Option<Petiole>> {
+    let (width, height) = region.dimensions();
+    let max_radius = distance_to_background(region).into_iter().fold(0.0, f64::max);
+    if max_radius <= 0.0 {
+        return Ok(None);
+    }
+    
+    let diameter = ((max_width_ratio * 2.0 * max_radius).round() as u32).max(3);
+    let blade = apply_opening(region, diameter)?;
+    let thin = BinaryMask::from_fn(width, height, |x, y| region.get(x, y) && !blade.get(x, y));
+    let labels = label_components(&thin);
+    
+    let mut best: Option<Candidate> = None;
+    
+    for stats in &labels.stats {
+        // Candidate cropped to its bounding box plus a one-pixel border
+        let (min_x, min_y, max_x, max_y) = stats.bounding_box;
+        let (x0, y0) = (min_x.saturating_sub(1), min_y.saturating_sub(1));
+        let crop_width = (max_x + 2).min(width) - x0;
+        let crop_height = (max_y + 2).min(height) - y0;
+        let candidate = BinaryMask::from_fn(crop_width, crop_height, |x, y| {
+            labels.label(x + x0, y + y0) == stats.label
+        });
+        
+        // Attachment to the blade: candidate pixels with an 8-neighbour in the blade
+        let mut contact = Vec::new();
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                if !candidate.get(x - x0, y - y0) {
+                    continue;
+                }
+                let touches_blade = (-1..=1).any(|dy| (-1..=1).any(|dx| {
+                    blade.get_or_false(x as i32 + dx, y as i32 + dy)
+                }));
+                if touches_blade {
+                    contact.push((x, y));
+                }
+            }
+        }
+        
+        if contact.is_empty() {
+            continue;
+        }
+        
+        let n = contact.len() as f64;
+        let cx = contact.iter().map(|p| p.0 as f64).sum::<f64>() / n;
+        let cy = contact.iter().map(|p| p.1 as f64).sum::<f64>() / n;
+        let base = *contact.iter()
+            .min_by(|a, b| {
+                let da = (a.0 as f64 - cx).powi(2) + (a.1 as f64 - cy).powi(2);
+                let db = (b.0 as f64 - cx).powi(2) + (b.1 as f64 - cy).powi(2);
+                da.total_cmp(&db)
+            })
+            .unwrap(); // Safe because contact is non-empty
+        
+        let distances = calculate_geodesic_distance_map((base.0 - x0, base.1 - y0), &candidate);
+        let (tip_index, length) = distances.iter()
+            .enumerate()
+            .filter(|(_, d)| d.is_finite())
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(i, &d)| (i, d))
+            .unwrap_or((0, 0.0));
+        
+        if length <= 0.0 {
+            continue;
+        }
+        
+        let petiole_width = stats.area as f64 / length;
+        if length / petiole_width < min_elongation {
+            continue;
+        }
+        
+        if best.as_ref().is_none_or(|candidate| length > candidate.length) {
+            let tip = (tip_index as u32 % crop_width + x0, tip_index as u32 / crop_width + y0);
+            best = Some(Candidate { stats, base, tip, length });
+        }
+    }
+    
+    Ok(best.map(|candidate| Petiole {
+        mask: labels.mask_where(|s| s.label == candidate.stats.label),
+        base: candidate.base,
+        tip: candidate.tip,
+        length: candidate.length,
+        width: candidate.stats.area as f64 / candidate.length,
+        area: candidate.stats.area,
+    }))
+}
+
+/// Record the petiole in the leaf mask
+///
+/// # Arguments
+/// * `mask` - Leaf mask to update
+/// * `petiole` - Petiole pixels
+/// * `remove` - Also clear `LEAF`, so every later stage analyses the blade only
+pub fn mark_petiole(mask: &mut LeafMask, petiole: &BinaryMask, remove: bool) {
+    let (width, height) = mask.dimensions();
+    
+    for y in 0..height {
+        for x in 0..width {
+            if petiole.get(x, y) {
+                mask.set(x, y, PETIOLE);
+                if remove {
+                    mask.clear(x, y, LEAF);
+                }
+            }
+        }
+    }
+}
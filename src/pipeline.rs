@@ -2,27 +2,83 @@
 
 use std::path::PathBuf;
 
-use crate::config::Config;
+use crate::config::{Config, OrientationMethod, SignalStart};
+use crate::connected_components::label_components;
+use crate::contour::LeafContour;
 use crate::errors::{LeafComplexError, Result};
-use crate::feature_extraction::generate_features;
+use crate::feature_extraction::{
+    generate_features, resample_features, rotate_features, signal_start_index, MarginalPointFeatures,
+};
+use crate::efa::{elliptic_fourier, normalize_elliptic_fourier};
+use crate::entropy::calculate_entropy_metrics;
+use crate::fractal::{calculate_fractal_dimensions, fractal_scales};
+use crate::granulometry::{calculate_pattern_spectrum, granulometry_diameters};
+use crate::holes::{analyze_holes, mark_holes};
 use crate::image_io::{InputImage, save_image};
 use crate::image_utils::resize_image;
+use crate::leaf_mask::{LeafMask, LEAF};
 use crate::morphology::{
-    apply_opening, mark_opened_regions, trace_contour, 
-    create_mc_with_com_component, create_thornfiddle_image
+    apply_opening, mark_opened_regions, 
+    create_mc_with_com_component, mark_lobe_regions, resample_contour
+};
+use crate::output::{
+    write_ec_csv, write_mc_csv, write_idsc_csv, write_granulometry_csv, write_convexity_defects_csv, 
+    write_teeth_csv, write_lobes_csv, write_fractal_csv, 
+    write_efa_csv, write_efa_power_csv, 
+    create_summary, SummaryRow,
+    render_marked_image, render_thornfiddle_image, render_skeleton_image
 };
-use crate::output::{write_ec_csv, write_mc_csv, create_summary};
+use crate::orientation::{normalize_orientation, OrientationTransform};
+use crate::path_algorithms::calculate_geodesic_metrics;
+use crate::petiole::{detect_petiole, mark_petiole};
+use crate::shape_context::calculate_inner_distance_shape_context;
+use crate::lobes::segment_lobes;
+use crate::skeleton::extract_skeleton;
+use crate::teeth::detect_teeth;
 use crate::point_analysis::{get_reference_point, get_mc_reference_point};
 use crate::shape_analysis::{
-    analyze_shape_comprehensive, calculate_length_width_shape_index, 
-    calculate_length_width_shape_index_with_shorter, calculate_dynamic_opening_percentage
+    analyze_shape_from_contour, calculate_length_width_shape_index_from_contour, 
+    get_shorter_dimension, calculate_dynamic_opening_percentage, calculate_convex_hull_metrics,
+    calculate_feret_diameters, calculate_leaf_architecture, lowest_contour_point, calculate_shape_indices,
+    calculate_area
 };
 use crate::thornfiddle;
 
+/// Align the start of per-point features and resample them along arc length when configured
+///
+/// # Arguments
+/// * `features` - Features of every contour pixel
+/// * `contour` - Contour the features were measured on
+/// * `config` - Signal start and resampling settings
+/// * `petiole_base` - Detected petiole base, if any
+/// * `width` - Image width
+///
+/// # Returns
+/// Features, their marginal points, and the circumference in the same index
+/// units (samples when resampled, pixels otherwise) for harmonic enhancement
+fn normalise_signals(
+    features: Vec<MarginalPointFeatures>,
+    contour: &LeafContour,
+    config: &Config,
+    petiole_base: Option<(u32, u32)>,
+    width: u32,
+) -> (Vec<MarginalPointFeatures>, Vec<(u32, u32)>, f64) {
+    let start = signal_start_index(&features, &contour.pixels, config.signal_start, petiole_base, width);
+    let (features, points) = rotate_features(features, &contour.pixels, start);
+    
+    match config.signal_sample_count_for(contour.perimeter()) {
+        Some(samples) => {
+            let (resampled, points) = resample_features(&features, &points, samples);
+            (resampled, points, samples as f64)
+        }
+        None => (features, points, contour.perimeter()),
+    }
+}
+
 /// Calculate adaptive opening kernel size based on pixel density
 ///
 /// # Arguments
-/// * `image` - Image to analyze
+/// * `mask` - Leaf mask to analyze
 /// * `max_density` - Density threshold for max opening
 /// * `max_percentage` - Maximum opening percentage at high density
 /// * `min_percentage` - Minimum opening percentage at low density
@@ -30,24 +86,16 @@ use crate::thornfiddle;
 /// # Returns
 /// Calculated kernel size in pixels
 fn calculate_adaptive_opening_kernel_size(
-    image: &image::RgbaImage,
+    mask: &LeafMask,
     max_density: f64,
     max_percentage: f64,
     min_percentage: f64,
 ) -> u32 {
-    let (width, height) = image.dimensions();
+    let (width, height) = mask.dimensions();
     let total_pixels = (width * height) as f64;
     
-    // Count non-transparent pixels
-    let mut non_transparent_count = 0;
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            if pixel[3] > 0 {
-                non_transparent_count += 1;
-            }
-        }
-    }
+    // Count leaf pixels
+    let non_transparent_count = mask.count(LEAF);
     
     // Calculate percentage of non-transparent pixels
     let non_transparent_percentage = (non_transparent_count as f64 / total_pixels) * 100.0;
@@ -74,15 +122,15 @@ fn calculate_adaptive_opening_kernel_size(
 /// Process a single image through the complete EC/MC analysis pipeline
 ///
 /// # Pipeline Steps
-/// 1. Resize image (if configured)
+/// 1. Resize image (if configured) and normalise orientation (if configured)
 /// 2. Apply adaptive morphological opening for EC region marking
-/// 3. Create MC image by removing small components
+/// 3. Create MC region by removing small components
 /// 4. Calculate shape metrics (EC and MC)
-/// 5. Create Thornfiddle image with golden lobes
+/// 5. Mark Thornfiddle golden lobes
 /// 6. Calculate reference points (separate for EC and MC)
 /// 7. Extract contours and generate features
 /// 8. Apply filtering (petiole, threshold)
-/// 9. Calculate harmonic enhancements
+/// 9. Calculate harmonic enhancements and geodesic descriptors
 /// 10. Compute entropy metrics
 /// 11. Write output CSVs
 ///
@@ -112,37 +160,107 @@ pub fn process_image(
         image
     };
     
+    // Normalise orientation (base at the bottom centre) before any mask-based analysis
+    let (processed_image, orientation) = if config.orientation_method == OrientationMethod::None {
+        let (width, height) = processed_image.dimensions();
+        (processed_image, OrientationTransform::identity(width, height))
+    } else {
+        let thresholds = config.alpha_policy().resolve(&processed_image);
+        let region = LeafMask::from_image(&processed_image, &thresholds).region(true);
+        let petiole_base = if config.orientation_method == OrientationMethod::Petiole {
+            detect_petiole(&region, config.petiole_max_width_ratio, config.petiole_min_elongation)?
+                .map(|petiole| petiole.base)
+        } else {
+            None
+        };
+        normalize_orientation(&processed_image, &region, config.orientation_method, petiole_base)
+    };
+    
+    if debug && !orientation.is_identity() {
+        println!("Orientation ({}): base=({:.1}, {:.1}), apex=({:.1}, {:.1}), rotated {:.2} deg clockwise, canvas {}x{}", 
+                 orientation.method.name(), orientation.base.0, orientation.base.1,
+                 orientation.apex.0, orientation.apex.1, orientation.angle_degrees,
+                 orientation.width, orientation.height);
+    }
+    
+    // All analysis state lives in the layered leaf mask; opacity is decided only here
+    let alpha_thresholds = config.alpha_policy().resolve(&processed_image);
+    let mut mask = LeafMask::from_image(&processed_image, &alpha_thresholds);
+    
+    if debug {
+        println!("Alpha policy: {} (low={}, high={})", 
+                 alpha_thresholds.policy.name(), alpha_thresholds.low, alpha_thresholds.high);
+    }
+    
+    // Holes are measured on the leaf as imaged, before optional filling
+    let (hole_metrics, holes) = analyze_holes(&mask.region(true));
+    mark_holes(&mut mask, &holes, config.fill_holes);
+    
+    if debug {
+        println!("Holes: count={}, area={}, largest={}, missing={:.3}%, filled={}", 
+                 hole_metrics.hole_count, hole_metrics.total_hole_area, hole_metrics.largest_hole_area,
+                 hole_metrics.percent_area_missing, config.fill_holes);
+        for (i, contour) in hole_metrics.inner_contours.iter().enumerate() {
+            println!("Inner contour {}: {} points", i + 1, contour.len());
+        }
+    }
+    
+    // Petiole is detected (and optionally cut) before any region is derived from the leaf
+    let petiole = if config.enable_petiole_detection {
+        let petiole = detect_petiole(&mask.region(true), config.petiole_max_width_ratio, config.petiole_min_elongation)?;
+        if let Some(petiole) = &petiole {
+            mark_petiole(&mut mask, &petiole.mask, config.remove_petiole);
+        }
+        
+        if debug {
+            match &petiole {
+                Some(p) => println!("Petiole: base={:?}, tip={:?}, length={:.1}, width={:.1}, area={}, removed={}", 
+                                    p.base, p.tip, p.length, p.width, p.area, config.remove_petiole),
+                None => println!("Petiole: none detected"),
+            }
+        }
+        
+        petiole
+    } else {
+        None
+    };
+    
     // Step 2: Calculate adaptive opening kernel size
     let adaptive_opening_kernel_size = calculate_adaptive_opening_kernel_size(
-        &processed_image,
+        &mask,
         config.adaptive_opening_max_density,
         config.adaptive_opening_max_percentage,
         config.adaptive_opening_min_percentage,
     );
     
     // Apply morphological opening
-    let opened_image = apply_opening(&processed_image, adaptive_opening_kernel_size)?;
+    let leaf_region = mask.region(true);
+    
+    if debug {
+        let leaf_components = label_components(&leaf_region);
+        for stats in &leaf_components.stats {
+            println!("Leaf component {}: area={}, bbox={:?}, perimeter={}, border_contact={}", 
+                     stats.label, stats.area, stats.bounding_box, stats.perimeter, stats.border_contact);
+        }
+    }
+    let opened_region = apply_opening(&leaf_region, adaptive_opening_kernel_size)?;
     
     // Mark opened regions (pink)
-    let mut marked_image = mark_opened_regions(
-        &processed_image,
-        &opened_image,
-        config.marked_region_color_rgb,
-    );
+    mark_opened_regions(&mut mask, &opened_region);
 
-    // Step 3: Create MC image (formerly LMC)
-    let mc_image = create_mc_with_com_component(
-        &processed_image,
-        &mut marked_image, 
-        config.marked_region_color_rgb
+    // Step 3: Create MC region (formerly LMC)
+    let mc_region = create_mc_with_com_component(&mut mask);
+    
+    // Step 4: Extract contours (EC: whole leaf, MC: isolated component) and calculate shape metrics
+    let ec_contour = LeafContour::extract(
+        &leaf_region, Some(&processed_image), alpha_thresholds.low, config.contour_method,
+    );
+    let mc_contour = LeafContour::extract(
+        &mc_region, Some(&processed_image), alpha_thresholds.low, config.contour_method,
     );
     
-    // Step 4: Calculate shape metrics
     println!("Calculating EC shape metrics...");
-    let (ec_length, ec_width, ec_shape_index) = calculate_length_width_shape_index(
-        &processed_image, 
-        config.marked_region_color_rgb
-    );
+    let (ec_length, ec_width, ec_shape_index) = calculate_length_width_shape_index_from_contour(&ec_contour);
     
     if debug {
         println!("EC Shape: Length={:.1}, Width={:.1}, Index={:.3}", 
@@ -150,18 +268,15 @@ pub fn process_image(
     }
     
     println!("Calculating MC shape metrics...");
-    let (mc_length, mc_width, mc_shape_index, mc_shorter_dimension) = 
-        calculate_length_width_shape_index_with_shorter(
-            &mc_image, 
-            config.marked_region_color_rgb
-        );
+    let (mc_length, mc_width, mc_shape_index) = calculate_length_width_shape_index_from_contour(&mc_contour);
+    let mc_shorter_dimension = get_shorter_dimension(mc_length, mc_width);
     
     if debug {
         println!("MC Shape: Length={:.1}, Width={:.1}, Index={:.3}, Shorter={:.1}", 
                  mc_length, mc_width, mc_shape_index, mc_shorter_dimension);
     }
     
-    // Step 5: Calculate dynamic opening percentage and create Thornfiddle image
+    // Step 5: Calculate dynamic opening percentage and mark Thornfiddle lobes
     let dynamic_opening_percentage = calculate_dynamic_opening_percentage(
         mc_shape_index,
         config.thornfiddle_max_opening_percentage,
@@ -175,67 +290,119 @@ pub fn process_image(
     println!("Dynamic thornfiddle: MC Shape Index {:.3} -> {:.1}% -> {} px kernel", 
              mc_shape_index, dynamic_opening_percentage, dynamic_kernel_size);
     
-    let thornfiddle_image = create_thornfiddle_image(
-        &mc_image,
-        dynamic_kernel_size,
-        config.thornfiddle_marked_color_rgb,
-    )?;
+    mark_lobe_regions(&mut mask, dynamic_kernel_size)?;
     
     // Calculate comprehensive shape metrics
     let (area, ec_circularity, _, _, outline_count, _) = 
-        analyze_shape_comprehensive(&processed_image, config.marked_region_color_rgb);
+        analyze_shape_from_contour(&leaf_region, &ec_contour);
     
     if debug {
         println!("Shape metrics: Area={}, Outline={}, EC_Circularity={:.6}", 
                  area, outline_count, ec_circularity);
     }
     
+    // Convex hull, solidity, convexity, convexity defects and Feret diameters
+    let ec_shorter_dimension = get_shorter_dimension(ec_length, ec_width);
+    let ec_hull = calculate_convex_hull_metrics(
+        &ec_contour.polygon,
+        config.convexity_defect_min_depth_percentage / 100.0 * ec_shorter_dimension,
+    );
+    let mc_hull = calculate_convex_hull_metrics(
+        &mc_contour.polygon,
+        config.convexity_defect_min_depth_percentage / 100.0 * mc_shorter_dimension,
+    );
+    
+    let ec_feret = calculate_feret_diameters(&ec_contour.polygon);
+    let mc_feret = calculate_feret_diameters(&mc_contour.polygon);
+    
+    // Dissection index and compactness family
+    let ec_indices = calculate_shape_indices(area, &ec_hull, &ec_feret);
+    let mc_indices = calculate_shape_indices(calculate_area(&mc_region), &mc_hull, &mc_feret);
+    
+    // Apex, apex/base angles and position of maximum width along the base-apex axis
+    let leaf_base = petiole.as_ref()
+        .map(|p| (p.base.0 as f64, p.base.1 as f64))
+        .unwrap_or_else(|| lowest_contour_point(&ec_contour.polygon));
+    let architecture = calculate_leaf_architecture(&ec_contour.polygon, leaf_base, config.leaf_angle_fraction);
+    
+    // Individual marginal teeth (pink components of the adaptive opening)
+    let teeth = detect_teeth(&mask, &ec_contour.pixels, config.tooth_min_area, config.pixels_per_mm);
+    
+    // Individual lobes (golden components of the Thornfiddle opening)
+    let lobes = segment_lobes(&mask, &mc_contour.pixels, config.lobe_min_area_percentage);
+    
+    if debug {
+        println!("EC hull: Solidity={:.4}, Convexity={:.4}, Defects={}, Max depth={:.1}", 
+                 ec_hull.solidity, ec_hull.convexity, ec_hull.defects.len(), ec_hull.max_defect_depth());
+        println!("MC hull: Solidity={:.4}, Convexity={:.4}, Defects={}, Max depth={:.1}", 
+                 mc_hull.solidity, mc_hull.convexity, mc_hull.defects.len(), mc_hull.max_defect_depth());
+        println!("EC Feret: Max={:.1} ({:.1} deg), Min={:.1} ({:.1} deg), Rect={:.1}x{:.1} ({:.1} deg)", 
+                 ec_feret.max_feret, ec_feret.max_feret_angle, ec_feret.min_feret, ec_feret.min_feret_angle,
+                 ec_feret.min_rect_length, ec_feret.min_rect_width, ec_feret.min_rect_angle);
+        println!("MC Feret: Max={:.1} ({:.1} deg), Min={:.1} ({:.1} deg), Rect={:.1}x{:.1} ({:.1} deg)", 
+                 mc_feret.max_feret, mc_feret.max_feret_angle, mc_feret.min_feret, mc_feret.min_feret_angle,
+                 mc_feret.min_rect_length, mc_feret.min_rect_width, mc_feret.min_rect_angle);
+        println!("Leaf architecture: base=({:.1}, {:.1}), apex=({:.1}, {:.1}), length={:.1}, apex angle={:.1}, base angle={:.1}, max width={:.1} at {:.2}", 
+                 architecture.base.0, architecture.base.1, architecture.apex.0, architecture.apex.1,
+                 architecture.length, architecture.apex_angle, architecture.base_angle,
+                 architecture.max_width, architecture.max_width_position);
+        println!("Teeth: count={}, per cm={:.3}, mean height={:.2}, mean width={:.2}, mean spacing={:.2}", 
+                 teeth.teeth.len(), teeth.teeth_per_cm, teeth.mean_height, teeth.mean_width, teeth.mean_spacing);
+        for (name, indices) in [("EC", &ec_indices), ("MC", &mc_indices)] {
+            println!("{} indices: DI={:.3}, normalised DI={:.3}, P/A={:.5}, form factor={:.3}, roundness={:.3}, aspect ratio={:.3}", 
+                     name, indices.dissection_index, indices.normalized_dissection_index, indices.perimeter_area_ratio,
+                     indices.form_factor, indices.roundness, indices.aspect_ratio);
+        }
+        println!("Lobes: count={}, area fraction={:.4}, mean area={:.1}, area CV={:.4}, mean sinus depth={:.2}", 
+                 lobes.lobes.len(), lobes.area_fraction, lobes.mean_area, lobes.area_cv, lobes.mean_sinus_depth);
+    }
+    
     // Save debug images if requested
     if debug {
         let debug_dir = PathBuf::from(&config.output_base_dir).join("debug");
         std::fs::create_dir_all(&debug_dir).map_err(|e| LeafComplexError::Io(e))?;
         
+        let marked_image = render_marked_image(&processed_image, &mask, config.marked_region_color_rgb);
+        let thornfiddle_image = render_thornfiddle_image(&processed_image, &mask, config.thornfiddle_marked_color_rgb);
+        
         save_image(&marked_image, debug_dir.join(format!("{}_marked.png", filename)))?;
         save_image(&thornfiddle_image, debug_dir.join(format!("{}_thornfiddle.png", filename)))?;
     }
     
     // Step 6: Calculate reference points (separate for EC and MC)
-    let ec_reference_point = get_reference_point(
-        &processed_image,
-        &marked_image,
-        &config.reference_point_choice,
-        config.marked_region_color_rgb,
-    )?;
+    let ec_reference_point = get_reference_point(&mask, &config.reference_point_choice)?;
     
-    let mc_reference_point = get_mc_reference_point(
-        &mc_image,
-        &marked_image,
-        &config.reference_point_choice,
-        config.marked_region_color_rgb,
-    )?;
+    let mc_reference_point = get_mc_reference_point(&mask, &config.reference_point_choice)?;
     
     if debug {
-        println!("EC reference point: {:?}", ec_reference_point);
-        println!("MC reference point: {:?}", mc_reference_point);
+        let to_original = |(x, y): (u32, u32)| orientation.to_original((x as f64, y as f64));
+        println!("EC reference point: {:?} (original {:.1?})", ec_reference_point, to_original(ec_reference_point));
+        println!("MC reference point: {:?} (original {:.1?})", mc_reference_point, to_original(mc_reference_point));
     }
     
-    // Step 7: EC Analysis (pink regions are OPAQUE)
-    let ec_contour = trace_contour(
-        &marked_image,
-        true, // is_pink_opaque = true for EC
-        config.marked_region_color_rgb,
-    );
-    
+    // Step 7: EC Analysis (marked regions are part of the leaf)
     // Generate initial EC features
-    let initial_ec_features = generate_features(
+    let contour_ec_features = generate_features(
         ec_reference_point,
-        &ec_contour,
-        &processed_image,
-        Some(&marked_image),
-        config.marked_region_color_rgb,
+        &ec_contour.pixels,
+        &mask,
         true, // is_ec = true
     )?;
     
+    // The petiole base comes from the mask-level detector (the signal heuristic on
+    // the EC contour is the fallback) and is shared with MC; the signal starts at
+    // the contour point nearest to it
+    let petiole_base = match config.signal_start {
+        SignalStart::Petiole | SignalStart::Apex => petiole.as_ref()
+            .map(|p| p.base)
+            .or_else(|| thornfiddle::detect_petiole_base(&contour_ec_features, &ec_contour.pixels, 1.0)),
+        SignalStart::Leftmost | SignalStart::MaxGeodesic => None,
+    };
+    let image_width = mask.dimensions().0;
+    
+    let (initial_ec_features, ec_signal_points, ec_circumference) = 
+        normalise_signals(contour_ec_features, &ec_contour, config, petiole_base, image_width);
+    
     // Apply petiole filtering to EC features
     let (ec_features, petiole_info) = thornfiddle::filter_petiole_from_ec_features(
         &initial_ec_features,
@@ -247,14 +414,12 @@ pub fn process_image(
     );
     
     // Calculate harmonic values for EC
-    let ec_circumference = thornfiddle::calculate_leaf_circumference(&ec_contour);
     let ec_harmonic_result = thornfiddle::calculate_thornfiddle_path_harmonic(
         &ec_features,
         ec_circumference,
-        &thornfiddle_image,
+        &mask,
         ec_reference_point,
-        &ec_contour,
-        config.thornfiddle_marked_color_rgb,
+        &ec_signal_points,
         config.thornfiddle_pixel_threshold,
         config.harmonic_min_chain_length,
         config.harmonic_strength_multiplier,
@@ -272,38 +437,33 @@ pub fn process_image(
     }
     
     if debug {
-        println!("EC contour points: {}", ec_contour.len());
+        println!("EC contour points: {}, signal samples: {}", ec_contour.pixels.len(), ec_signal_points.len());
+        println!("Signal start: {:?}, petiole base: {:?}, first EC point: {:?}", 
+                 config.signal_start, petiole_base, ec_signal_points.first());
         if let Some(ref indices) = petiole_info {
             println!("Petiole detected: {} points", indices.len());
         }
         println!("EC harmonic chains: {}", ec_harmonic_result.valid_chain_count);
     }
     
-    // Step 8: MC Analysis (pink regions are TRANSPARENT)
-    let mc_contour = trace_contour(
-        &mc_image,
-        false, // is_pink_opaque = false for MC
-        config.marked_region_color_rgb,
-    );
-    
-    let mc_features = generate_features(
+    // Step 8: MC Analysis (removed regions are outside the leaf)
+    let contour_mc_features = generate_features(
         mc_reference_point,
-        &mc_contour,
-        &mc_image,
-        None, // No marked image needed for MC
-        config.marked_region_color_rgb,
+        &mc_contour.pixels,
+        &mask,
         false, // is_ec = false
     )?;
     
+    let (mc_features, mc_signal_points, mc_circumference) = 
+        normalise_signals(contour_mc_features, &mc_contour, config, petiole_base, image_width);
+    
     // Calculate harmonic values for MC
-    let mc_circumference = thornfiddle::calculate_leaf_circumference(&mc_contour);
     let mc_harmonic_result = thornfiddle::calculate_thornfiddle_path_harmonic(
         &mc_features,
         mc_circumference,
-        &thornfiddle_image,
+        &mask,
         mc_reference_point,
-        &mc_contour,
-        config.thornfiddle_marked_color_rgb,
+        &mc_signal_points,
         config.thornfiddle_pixel_threshold,
         config.harmonic_min_chain_length,
         config.harmonic_strength_multiplier,
@@ -321,10 +481,22 @@ pub fn process_image(
     }
     
     if debug {
-        println!("MC contour points: {}", mc_contour.len());
+        println!("MC contour points: {}, signal samples: {}", mc_contour.pixels.len(), mc_signal_points.len());
         println!("MC harmonic chains: {}", mc_harmonic_result.valid_chain_count);
     }
     
+    // Margin-to-margin geodesic descriptors (reference-point independent)
+    let geodesic_metrics = calculate_geodesic_metrics(
+        &mc_region,
+        &mc_contour.pixels,
+        config.geodesic_sample_count,
+    );
+    
+    if debug {
+        println!("MC geodesic diameter: {:.1}, mean tortuosity: {:.4}", 
+                 geodesic_metrics.diameter, geodesic_metrics.mean_tortuosity);
+    }
+    
     // Step 9: Calculate entropy metrics
     let mc_spectral_entropy = thornfiddle::calculate_spectral_entropy_from_harmonic_thornfiddle_path(
         &mc_features_final,
@@ -334,28 +506,156 @@ pub fn process_image(
         config.spectral_entropy_sigmoid_c,
     ).0; // We only need the entropy value, not the smoothed path
     
-    let ec_approximate_entropy = thornfiddle::calculate_approximate_entropy_from_pink_path(
-        &ec_features_final,
+    let ec_entropies = calculate_entropy_metrics(
+        &thornfiddle::extract_pink_path_signal(&ec_features_final),
         config.approximate_entropy_m,
         config.approximate_entropy_r,
+        config.permutation_entropy_order,
+        config.permutation_entropy_delay,
+        config.multiscale_entropy_scales,
     );
+    let ec_entropy = ec_entropies.value(config.ec_entropy_method);
     
     if debug {
         println!("MC Spectral Entropy: {:.6}", mc_spectral_entropy);
-        println!("EC Approximate Entropy: {:.6}", ec_approximate_entropy);
+        println!("EC Entropy ({}): {:.6}", config.ec_entropy_method.name(), ec_entropy);
+        println!("EC entropies: approximate={:.6}, sample={:.6}, permutation={:.6}, multiscale={:.6}", 
+                 ec_entropies.approximate, ec_entropies.sample, ec_entropies.permutation, ec_entropies.multiscale);
     }
     
     // Step 10: Write output CSVs
     write_ec_csv(&ec_features_final, &config.output_base_dir, &filename)?;
     write_mc_csv(&mc_features_final, &config.output_base_dir, &filename)?;
+    write_convexity_defects_csv(
+        &ec_hull, &mc_hull, ec_shorter_dimension, mc_shorter_dimension, &orientation, 
+        &config.output_base_dir, &filename,
+    )?;
+    write_teeth_csv(&teeth, &orientation, &config.output_base_dir, &filename)?;
+    write_lobes_csv(&lobes, &orientation, &config.output_base_dir, &filename)?;
+    
+    if config.enable_inner_distance_shape_context {
+        let idsc = calculate_inner_distance_shape_context(
+            &mc_region,
+            &mc_contour.pixels,
+            config.idsc_sample_count,
+            config.idsc_distance_bins,
+            config.idsc_angle_bins,
+        );
+        write_idsc_csv(&idsc, &orientation, &config.output_base_dir, &filename)?;
+    }
+    
+    // Multi-scale openings (pattern spectra) of the whole leaf and the MC region
+    let (ec_granulometry, mc_granulometry) = if config.enable_granulometry {
+        let ec_diameters = granulometry_diameters(
+            ec_shorter_dimension,
+            config.granulometry_min_percentage,
+            config.granulometry_max_percentage,
+            config.granulometry_scales,
+        );
+        let mc_diameters = granulometry_diameters(
+            mc_shorter_dimension,
+            config.granulometry_min_percentage,
+            config.granulometry_max_percentage,
+            config.granulometry_scales,
+        );
+        
+        let ec_spectrum = calculate_pattern_spectrum(&leaf_region, &ec_diameters, ec_shorter_dimension)?;
+        let mc_spectrum = calculate_pattern_spectrum(&mc_region, &mc_diameters, mc_shorter_dimension)?;
+        write_granulometry_csv(&ec_spectrum, &mc_spectrum, &config.output_base_dir, &filename)?;
+        
+        if debug {
+            println!("EC granulometry: {} scales, mean scale {:.2}%, entropy {:.4}", 
+                     ec_spectrum.diameters.len(), ec_spectrum.mean_scale, ec_spectrum.entropy);
+            println!("MC granulometry: {} scales, mean scale {:.2}%, entropy {:.4}", 
+                     mc_spectrum.diameters.len(), mc_spectrum.mean_scale, mc_spectrum.entropy);
+        }
+        
+        (Some(ec_spectrum), Some(mc_spectrum))
+    } else {
+        (None, None)
+    };
+    
+    // Fractal dimensions of the EC and MC contours
+    let (ec_fractal, mc_fractal) = if config.enable_fractal_dimension {
+        let ec_scales = fractal_scales(config.fractal_max_percentage / 100.0 * ec_shorter_dimension, config.fractal_scales);
+        let mc_scales = fractal_scales(config.fractal_max_percentage / 100.0 * mc_shorter_dimension, config.fractal_scales);
+        
+        let ec_fractal = calculate_fractal_dimensions(&ec_contour.pixels, &ec_scales);
+        let mc_fractal = calculate_fractal_dimensions(&mc_contour.pixels, &mc_scales);
+        if config.export_fractal_data {
+            write_fractal_csv(&ec_fractal, &mc_fractal, &config.output_base_dir, &filename)?;
+        }
+        
+        if debug {
+            for (name, fractal) in [("EC", &ec_fractal), ("MC", &mc_fractal)] {
+                println!("{} fractal dimension: box={:.4} (R2={:.4}), Minkowski={:.4} (R2={:.4}), {} scales", 
+                         name, fractal.box_counting.dimension, fractal.box_counting.r_squared,
+                         fractal.minkowski.dimension, fractal.minkowski.r_squared, fractal.box_counting.scales.len());
+            }
+        }
+        
+        (Some(ec_fractal), Some(mc_fractal))
+    } else {
+        (None, None)
+    };
+    
+    // Elliptic Fourier descriptors of the EC and MC outlines
+    if config.enable_efa {
+        let outline = |contour: &LeafContour| -> Vec<(f64, f64)> {
+            if config.efa_sample_count > 0 {
+                resample_contour(&contour.pixels, config.efa_sample_count)
+                    .into_iter()
+                    .map(|(x, y)| (x as f64, y as f64))
+                    .collect()
+            } else {
+                contour.polygon.clone()
+            }
+        };
+        let descriptors = |contour: &LeafContour| {
+            let efa = elliptic_fourier(&outline(contour), config.efa_harmonics);
+            if config.efa_normalize { normalize_elliptic_fourier(&efa) } else { efa }
+        };
+        
+        let ec_efa = descriptors(&ec_contour);
+        let mc_efa = descriptors(&mc_contour);
+        write_efa_csv(&ec_efa, &mc_efa, &config.output_base_dir, &filename)?;
+        write_efa_power_csv(&ec_efa, &mc_efa, &config.output_base_dir, &filename)?;
+        
+        if debug {
+            for (name, efa) in [("EC", &ec_efa), ("MC", &mc_efa)] {
+                let harmonics_99 = efa.cumulative_power().iter().position(|&c| c >= 0.99).map_or(0, |i| i + 1);
+                println!("{} EFA: {} harmonics, size={:.2}, rotation={:.3} rad, 99% power at harmonic {}", 
+                         name, efa.coefficients.len(), efa.size, efa.rotation, harmonics_99);
+            }
+        }
+    }
+    
+    // Medial axis of the MC leaf
+    let skeleton = if config.enable_skeleton {
+        let skeleton = extract_skeleton(&mc_region, config.skeleton_prune_ratio);
+        
+        let skeleton_dir = PathBuf::from(&config.output_base_dir).join("Skeleton");
+        std::fs::create_dir_all(&skeleton_dir).map_err(|e| LeafComplexError::Io(e))?;
+        let skeleton_image = render_skeleton_image(&processed_image, &mask, &skeleton.mask, config.skeleton_color_rgb);
+        save_image(&skeleton_image, skeleton_dir.join(format!("{}.png", filename)))?;
+        
+        if debug {
+            println!("MC skeleton: length={:.1}, branches={}, endpoints={}, junctions={}, max radius={:.1}, pruned={}", 
+                     skeleton.length, skeleton.branch_count, skeleton.endpoint_count, 
+                     skeleton.junction_count, skeleton.max_inscribed_radius, skeleton.pruned_branch_count);
+        }
+        
+        Some(skeleton)
+    } else {
+        None
+    };
     
     // Step 11: Create summary
-    create_summary(
-        &config.output_base_dir,
-        &filename,
+    create_summary(&config.output_base_dir, &SummaryRow {
+        filename: &filename,
         subfolder,
         mc_spectral_entropy,
-        ec_approximate_entropy,
+        ec_entropy,
         ec_length,
         mc_length,
         ec_width,
@@ -363,8 +663,29 @@ pub fn process_image(
         ec_shape_index,
         mc_shape_index,
         outline_count,
-        mc_harmonic_result.valid_chain_count,
-    )?;
+        harmonic_chain_count: mc_harmonic_result.valid_chain_count,
+        geodesic_metrics: &geodesic_metrics,
+        alpha_thresholds: &alpha_thresholds,
+        hole_metrics: &hole_metrics,
+        ec_granulometry: ec_granulometry.as_ref(),
+        mc_granulometry: mc_granulometry.as_ref(),
+        skeleton: skeleton.as_ref(),
+        ec_hull: &ec_hull,
+        mc_hull: &mc_hull,
+        ec_feret: &ec_feret,
+        mc_feret: &mc_feret,
+        orientation: &orientation,
+        petiole: petiole.as_ref(),
+        architecture: &architecture,
+        teeth: &teeth,
+        lobes: &lobes,
+        ec_indices: &ec_indices,
+        mc_indices: &mc_indices,
+        ec_fractal: ec_fractal.as_ref(),
+        mc_fractal: mc_fractal.as_ref(),
+        ec_entropy_method: config.ec_entropy_method,
+        ec_entropies: &ec_entropies,
+    })?;
     
     if debug {
         println!("Analysis complete for: {}", filename);
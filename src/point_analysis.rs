@@ -1,26 +1,18 @@
-use image::RgbaImage;
-
 use crate::config::ReferencePointChoice;
 use crate::errors::{LeafComplexError, Result};
-use crate::image_utils::{is_non_transparent, has_rgb_color};
+use crate::leaf_mask::{BinaryMask, LeafMask};
 
 /// Calculate the Emerge Point (EP)
-pub fn calculate_emerge_point(
-    image: &RgbaImage,
-    marked_color: [u8; 3],
-) -> Result<(u32, u32)> {
-    let (width, height) = image.dimensions();
+pub fn calculate_emerge_point(mask: &LeafMask) -> Result<(u32, u32)> {
+    let (width, height) = mask.dimensions();
     
-    // First, find maximum Y among non-transparent, non-marked pixels
+    // First, find maximum Y among leaf pixels not marked for EC
     let mut max_y = 0;
     let mut max_y_points = Vec::new();
     
     for y in 0..height {
         for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            
-            // Check if pixel is non-transparent and not marked with the special color
-            if is_non_transparent(pixel) && !has_rgb_color(pixel, marked_color) {
+            if mask.is_leaf(x, y) && !mask.is_ec_marked(x, y) {
                 if y > max_y {
                     max_y = y;
                     max_y_points.clear();
@@ -74,61 +66,54 @@ pub fn calculate_emerge_point(
     }
 }
 
-/// Calculate the Center of Mass (COM) for any image
-pub fn calculate_center_of_mass(image: &RgbaImage) -> Result<(u32, u32)> {
-    let (width, height) = image.dimensions();
+/// Calculate the Center of Mass (COM) for any region
+pub fn calculate_center_of_mass(region: &BinaryMask) -> Result<(u32, u32)> {
+    let (width, height) = region.dimensions();
     let mut sum_x = 0.0;
     let mut sum_y = 0.0;
-    let mut sum_alpha = 0.0;
+    let mut count = 0.0;
     
     for y in 0..height {
         for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            let alpha = pixel[3] as f32;
-            
-            if alpha > 0.0 {
-                sum_x += x as f32 * alpha;
-                sum_y += y as f32 * alpha;
-                sum_alpha += alpha;
+            if region.get(x, y) {
+                sum_x += x as f64;
+                sum_y += y as f64;
+                count += 1.0;
             }
         }
     }
     
-    if sum_alpha <= 0.0 {
+    if count <= 0.0 {
         return Err(LeafComplexError::NoValidPoints);
     }
     
     // Calculate COM
-    let com_x = sum_x / sum_alpha;
-    let com_y = sum_y / sum_alpha;
+    let com_x = sum_x / count;
+    let com_y = sum_y / count;
     
     // Round to nearest integer
     Ok((com_x.round() as u32, com_y.round() as u32))
 }
 
-/// Get the reference point based on the configuration choice for any image
+/// Get the reference point based on the configuration choice for the whole leaf
 pub fn get_reference_point(
-    image: &RgbaImage,
-    marked_image: &RgbaImage,
+    mask: &LeafMask,
     reference_point_choice: &ReferencePointChoice,
-    marked_color: [u8; 3],
 ) -> Result<(u32, u32)> {
     match reference_point_choice {
-        ReferencePointChoice::Ep => calculate_emerge_point(marked_image, marked_color),
-        ReferencePointChoice::Com => calculate_center_of_mass(image),
+        ReferencePointChoice::Ep => calculate_emerge_point(mask),
+        ReferencePointChoice::Com => calculate_center_of_mass(&mask.region(true)),
     }
 }
 
 /// Get the reference point specifically for LMC analysis
-/// This uses the LMC image for COM calculation if needed
+/// This uses the LMC region for COM calculation if needed
 pub fn get_mc_reference_point(
-    lmc_image: &RgbaImage,
-    marked_image: &RgbaImage,
+    mask: &LeafMask,
     reference_point_choice: &ReferencePointChoice,
-    marked_color: [u8; 3],
 ) -> Result<(u32, u32)> {
     match reference_point_choice {
-        ReferencePointChoice::Ep => calculate_emerge_point(marked_image, marked_color),
-        ReferencePointChoice::Com => calculate_center_of_mass(lmc_image), // Use LMC image for COM
+        ReferencePointChoice::Ep => calculate_emerge_point(mask),
+        ReferencePointChoice::Com => calculate_center_of_mass(&mask.region(false)), // Use LMC region for COM
     }
 }
\ No newline at end of file
@@ -0,0 +1,125 @@
+// src/protrusion.rs - Base line, apex and sinus of margin protrusions (teeth and lobes)
+
+use crate::connected_components::ComponentLabels;
+use crate::leaf_mask::BinaryMask;
+
+/// Distance from a point to the line through two points (to the point itself if they coincide)
+pub(crate) fn distance_to_line(p: (u32, u32), a: (u32, u32), b: (u32, u32)) -> f64 {
+    let (px, py) = (p.0 as f64, p.1 as f64);
+    let (ax, ay) = (a.0 as f64, a.1 as f64);
+    let (bx, by) = (b.0 as f64, b.1 as f64);
+    let length = (bx - ax).hypot(by - ay);
+    if length <= 0.0 {
+        return (px - ax).hypot(py - ay);
+    }
+    ((bx - ax) * (ay - py) - (ax - px) * (by - ay)).abs() / length
+}
+
+/// Farthest pair of points (ends of the base line)
+fn farthest_pair(points: &[(u32, u32)]) -> ((u32, u32), (u32, u32)) {
+    let mut best = (points[0], points[0]);
+    let mut best_distance = 0.0;
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            let distance = (a.0 as f64 - b.0 as f64).hypot(a.1 as f64 - b.1 as f64);
+            if distance > best_distance {
+                best_distance = distance;
+                best = (a, b);
+            }
+        }
+    }
+    best
+}
+
+/// Base line and apex of a protrusion of the margin (tooth or lobe component)
+#[derive(Debug, Clone)]
+pub(crate) struct Protrusion {
+    /// Contour indices of the component's margin pixels, in contour order
+    pub contour_indices: Vec<usize>,
+    /// Contour index of the apex
+    pub apex_index: usize,
+    /// Ends of the base line (where the component meets the opened blade)
+    pub base: ((u32, u32), (u32, u32)),
+    /// Distance from the apex to the base line
+    pub height: f64,
+    /// Length of the base line
+    pub width: f64,
+}
+
+/// Measure the base line and apex of one component protruding from the blade
+///
+/// The base is the line between the farthest pair of component pixels with an
+/// edge neighbour in the blade; the apex is the component's margin pixel
+/// farthest from that line.
+///
+/// # Returns
+/// None if the component does not touch the blade or the contour
+pub(crate) fn measure_protrusion(
+    labels: &ComponentLabels,
+    label: u32,
+    blade: &BinaryMask,
+    contour: &[(u32, u32)],
+) -> Option<Protrusion> {
+    let stats = &labels.stats[label as usize - 1];
+    let (min_x, min_y, max_x, max_y) = stats.bounding_box;
+    
+    let mut contact = Vec::new();
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            if labels.label(x, y) != label {
+                continue;
+            }
+            let touches_blade = [(0, -1), (1, 0), (0, 1), (-1, 0)]
+                .iter()
+                .any(|&(dx, dy)| blade.get_or_false(x as i32 + dx, y as i32 + dy));
+            if touches_blade {
+                contact.push((x, y));
+            }
+        }
+    }
+    
+    if contact.is_empty() {
+        return None;
+    }
+    
+    let contour_indices: Vec<usize> = contour.iter()
+        .enumerate()
+        .filter(|(_, &(x, y))| labels.label(x, y) == label)
+        .map(|(i, _)| i)
+        .collect();
+    
+    let base = farthest_pair(&contact);
+    let (apex_index, height) = contour_indices.iter()
+        .map(|&i| (i, distance_to_line(contour[i], base.0, base.1)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    
+    Some(Protrusion {
+        contour_indices,
+        apex_index,
+        base,
+        height,
+        width: (base.0.0 as f64 - base.1.0 as f64).hypot(base.0.1 as f64 - base.1.1 as f64),
+    })
+}
+
+/// Deepest margin point between two apices
+///
+/// # Arguments
+/// * `contour` - Closed contour pixels
+/// * `start` - Contour index of the first apex
+/// * `end` - Contour index of the next apex (walking forward, wrapping past the end)
+///
+/// # Returns
+/// The contour point strictly between the apices farthest from the chord joining
+/// them, with that distance; None if the apices are adjacent
+pub(crate) fn deepest_sinus(contour: &[(u32, u32)], start: usize, end: usize) -> Option<((u32, u32), f64)> {
+    let n = contour.len();
+    let (a, b) = (contour[start], contour[end]);
+    let gap = (end + n - start) % n;
+    (1..gap)
+        .map(|offset| {
+            let p = contour[(start + offset) % n];
+            (p, distance_to_line(p, a, b))
+        })
+        .max_by(|p, q| p.1.total_cmp(&q.1))
+}
@@ -0,0 +1,240 @@
+// src/rotation_diagnostics.rs - Rotation invariance diagnostics for summary metrics
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use csv::{Reader, Writer};
+use image::{imageops, Rgba, RgbaImage};
+use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
+
+use crate::config::Config;
+use crate::errors::{LeafComplexError, Result};
+use crate::image_io::InputImage;
+use crate::pipeline::process_image;
+
+/// Default rotation angles in degrees
+pub const DEFAULT_ROTATION_ANGLES: [f64; 8] = [0.0, 45.0, 90.0, 135.0, 180.0, 225.0, 270.0, 315.0];
+
+/// Spread of one summary metric over the rotated copies of an image
+#[derive(Debug, Clone)]
+pub struct RotationMetricSpread {
+    /// Summary column name
+    pub metric: String,
+    /// Value at each angle, in angle order
+    pub values: Vec<f64>,
+    /// Mean over all angles
+    pub mean: f64,
+    /// Population variance over all angles
+    pub variance: f64,
+    /// Standard deviation over all angles
+    pub std_dev: f64,
+    /// Coefficient of variation (std / |mean|, 0 for a zero mean)
+    pub coefficient_of_variation: f64,
+}
+
+/// Rotate an image clockwise about its centre without clipping
+///
+/// The image is first centred on a transparent square canvas of its diagonal,
+/// so every angle (including 0) is analysed at the same canvas size and leaf scale.
+///
+/// # Arguments
+/// * `image` - Input image
+/// * `angle_degrees` - Clockwise rotation in degrees
+///
+/// # Returns
+/// Rotated image on the padded canvas
+pub fn rotate_image_padded(image: &RgbaImage, angle_degrees: f64) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let side = ((width as f64).hypot(height as f64).ceil() as u32).max(1);
+    
+    let mut canvas = RgbaImage::from_pixel(side, side, Rgba([0, 0, 0, 0]));
+    imageops::overlay(
+        &mut canvas,
+        image,
+        ((side - width) / 2) as i64,
+        ((side - height) / 2) as i64,
+    );
+    
+    if angle_degrees.rem_euclid(360.0) == 0.0 {
+        return canvas;
+    }
+    
+    rotate_about_center(
+        &canvas,
+        angle_degrees.to_radians() as f32,
+        Interpolation::Bilinear,
+        Rgba([0, 0, 0, 0]),
+    )
+}
+
+/// Summarise the spread of every numeric summary metric over the angles
+///
+/// # Arguments
+/// * `headers` - Summary column names
+/// * `rows` - One summary row per angle
+///
+/// # Returns
+/// Spread of each column that is numeric at every angle
+pub fn summarise_rotation_spread(headers: &[String], rows: &[Vec<String>]) -> Vec<RotationMetricSpread> {
+    if rows.is_empty() {
+        return Vec::new();
+    }
+    
+    headers.iter()
+        .enumerate()
+        .filter_map(|(column, metric)| {
+            let values: Option<Vec<f64>> = rows.iter()
+                .map(|row| row.get(column).and_then(|value| value.parse::<f64>().ok()))
+                .collect();
+            let values = values?;
+            
+            let n = values.len() as f64;
+            let mean = values.iter().sum::<f64>() / n;
+            let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+            let std_dev = variance.sqrt();
+            let coefficient_of_variation = if mean.abs() > 1e-12 { std_dev / mean.abs() } else { 0.0 };
+            
+            Some(RotationMetricSpread {
+                metric: metric.clone(),
+                values,
+                mean,
+                variance,
+                std_dev,
+                coefficient_of_variation,
+            })
+        })
+        .collect()
+}
+
+/// Read the header and the last summary row written for an image
+fn read_summary_row(summary_path: &Path, filename: &str) -> Result<(Vec<String>, Vec<String>)> {
+    let mut reader = Reader::from_path(summary_path)
+        .map_err(|e| LeafComplexError::CsvOutput(e))?;
+    
+    let headers: Vec<String> = reader.headers()
+        .map_err(|e| LeafComplexError::CsvOutput(e))?
+        .iter()
+        .map(String::from)
+        .collect();
+    
+    let mut row = None;
+    for record in reader.records() {
+        let record = record.map_err(|e| LeafComplexError::CsvOutput(e))?;
+        if record.get(0) == Some(filename) {
+            row = Some(record.iter().map(String::from).collect());
+        }
+    }
+    
+    let row = row.ok_or_else(|| LeafComplexError::Other(format!(
+        "No summary row for {} in {}", filename, summary_path.display()
+    )))?;
+    
+    Ok((headers, row))
+}
+
+/// Run the full pipeline on rotated copies of an image and report metric spread
+///
+/// Each angle is analysed into its own output folder
+/// (`<output>/rotation_diagnostics/<angle>deg`); the spread of every numeric
+/// summary column is appended to `<output>/rotation_diagnostics/rotation_diagnostics.csv`.
+///
+/// # Output Columns
+/// ID, Metric, Mean, Variance, Std, CV, then one `Angle_<degrees>` column per angle
+///
+/// # Arguments
+/// * `input_image` - Loaded input image with metadata
+/// * `config` - Configuration parameters
+/// * `angles` - Clockwise rotation angles in degrees
+/// * `debug` - Enable debug output for every rotated run
+///
+/// # Returns
+/// Spread of every numeric summary metric
+pub fn run_rotation_diagnostics(
+    input_image: InputImage,
+    config: &Config,
+    angles: &[f64],
+    debug: bool,
+) -> Result<Vec<RotationMetricSpread>> {
+    let InputImage { image, path, filename } = input_image;
+    let diagnostics_dir = PathBuf::from(&config.output_base_dir).join("rotation_diagnostics");
+    
+    let mut headers = Vec::new();
+    let mut rows = Vec::with_capacity(angles.len());
+    
+    for &angle in angles {
+        let angle_dir = diagnostics_dir.join(format!("{}deg", angle));
+        fs::create_dir_all(&angle_dir).map_err(|e| LeafComplexError::Io(e))?;
+        
+        let mut angle_config = config.clone();
+        angle_config.output_base_dir = angle_dir.to_string_lossy().into_owned();
+        
+        println!("Rotation diagnostics: {} at {} degrees", filename, angle);
+        let rotated = InputImage {
+            image: rotate_image_padded(&image, angle),
+            path: path.clone(),
+            filename: filename.clone(),
+        };
+        process_image(rotated, &angle_config, debug)?;
+        
+        let (angle_headers, row) = read_summary_row(&angle_dir.join("summary.csv"), &filename)?;
+        headers = angle_headers;
+        rows.push(row);
+    }
+    
+    let spread = summarise_rotation_spread(&headers, &rows);
+    write_rotation_diagnostics_csv(&spread, angles, &diagnostics_dir, &filename)?;
+    
+    for metric in &spread {
+        println!("  {}: mean={:.6}, variance={:.6}, CV={:.4}",
+                 metric.metric, metric.mean, metric.variance, metric.coefficient_of_variation);
+    }
+    
+    Ok(spread)
+}
+
+/// Append the metric spread of one image to the rotation diagnostics CSV
+fn write_rotation_diagnostics_csv(
+    spread: &[RotationMetricSpread],
+    angles: &[f64],
+    output_dir: &Path,
+    filename: &str,
+) -> Result<()> {
+    let output_path = output_dir.join("rotation_diagnostics.csv");
+    let file_exists = output_path.exists();
+    
+    let mut writer = if file_exists {
+        Writer::from_writer(fs::OpenOptions::new()
+            .append(true)
+            .open(&output_path)
+            .map_err(|e| LeafComplexError::Io(e))?)
+    } else {
+        let mut writer = Writer::from_path(&output_path)
+            .map_err(|e| LeafComplexError::CsvOutput(e))?;
+        
+        let mut header: Vec<String> = ["ID", "Metric", "Mean", "Variance", "Std", "CV"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        header.extend(angles.iter().map(|angle| format!("Angle_{}", angle)));
+        writer.write_record(&header).map_err(|e| LeafComplexError::CsvOutput(e))?;
+        
+        writer
+    };
+    
+    for metric in spread {
+        let mut record = vec![
+            filename.to_string(),
+            metric.metric.clone(),
+            format!("{:.6}", metric.mean),
+            format!("{:.6}", metric.variance),
+            format!("{:.6}", metric.std_dev),
+            format!("{:.6}", metric.coefficient_of_variation),
+        ];
+        record.extend(metric.values.iter().map(|value| format!("{:.6}", value)));
+        writer.write_record(&record).map_err(|e| LeafComplexError::CsvOutput(e))?;
+    }
+    
+    writer.flush().map_err(|e| LeafComplexError::CsvOutput(csv::Error::from(e)))?;
+    
+    Ok(())
+}
@@ -1,31 +1,20 @@
 // src/shape_analysis.rs - Added Shape Index calculation functions
 
-use image::RgbaImage;
-use crate::image_utils::is_non_transparent;
+use crate::config::ContourMethod;
+use crate::contour::{polygon_perimeter, polygon_signed_area, LeafContour};
+use crate::leaf_mask::BinaryMask;
 use crate::morphology::{trace_contour, smooth_contour};
 use std::f64::consts::PI;
 
-/// Calculate the area of non-transparent pixels in the image
-pub fn calculate_area(image: &RgbaImage) -> u32 {
-    let (width, height) = image.dimensions();
-    let mut non_transparent_count = 0;
-    
-    for y in 0..height {
-        for x in 0..width {
-            let pixel = image.get_pixel(x, y);
-            if is_non_transparent(pixel) {
-                non_transparent_count += 1;
-            }
-        }
-    }
-    
-    non_transparent_count
+/// Calculate the area (pixel count) of a region
+pub fn calculate_area(region: &BinaryMask) -> u32 {
+    region.count() as u32
 }
 
-/// Calculate the bounding box dimensions of non-transparent pixels
+/// Calculate the bounding box dimensions of a region
 /// Returns (width, height) of the bounding box
-pub fn calculate_bounding_box_dimensions(image: &RgbaImage) -> (u32, u32) {
-    let (img_width, img_height) = image.dimensions();
+pub fn calculate_bounding_box_dimensions(region: &BinaryMask) -> (u32, u32) {
+    let (img_width, img_height) = region.dimensions();
     
     let mut min_x = img_width;
     let mut max_x = 0;
@@ -33,11 +22,10 @@ pub fn calculate_bounding_box_dimensions(image: &RgbaImage) -> (u32, u32) {
     let mut max_y = 0;
     let mut found_pixels = false;
     
-    // Find the bounding box of all non-transparent pixels
+    // Find the bounding box of all region pixels
     for y in 0..img_height {
         for x in 0..img_width {
-            let pixel = image.get_pixel(x, y);
-            if is_non_transparent(pixel) {
+            if region.get(x, y) {
                 found_pixels = true;
                 min_x = min_x.min(x);
                 max_x = max_x.max(x);
@@ -63,10 +51,10 @@ pub fn calculate_outline_count_from_contour(contour: &[(u32, u32)]) -> u32 {
     contour.len() as u32
 }
 
-/// Calculate the outline count (number of contour points) from the original image
-pub fn calculate_outline_count(image: &RgbaImage, marked_color: [u8; 3]) -> u32 {
-    // Trace the contour of the original image (treating pink as opaque)
-    let contour = trace_contour(image, true, marked_color);
+/// Calculate the outline count (number of contour points) of a region
+pub fn calculate_outline_count(region: &BinaryMask) -> u32 {
+    // Trace the contour of the region
+    let contour = trace_contour(region);
     contour.len() as u32
 }
 
@@ -133,43 +121,24 @@ pub fn calculate_biological_dimensions(contour: &[(u32, u32)]) -> (f64, f64) {
     (max_length, width)
 }
 
-/// Fast biological dimensions (optimized version for better performance)
-/// Uses sampling for very large contours to reduce O(n²) complexity
+/// Fast biological dimensions of a pixel contour (exact, via the convex hull)
 pub fn calculate_biological_dimensions_fast(contour: &[(u32, u32)]) -> (f64, f64) {
+    let polygon: Vec<(f64, f64)> = contour.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    calculate_biological_dimensions_polygon(&polygon)
+}
+
+/// Fast biological dimensions of a sub-pixel boundary polygon
+/// Length is the exact maximum Feret diameter (rotating calipers on the convex hull);
+/// width is the span of the hull perpendicular to that length axis
+pub fn calculate_biological_dimensions_polygon(contour: &[(f64, f64)]) -> (f64, f64) {
     if contour.len() < 2 {
         return (0.0, 0.0);
     }
     
-    // For performance, sample fewer points if contour is very large
-    // This reduces complexity from O(n²) to O(s²) where s is sample size
-    let sample_step = if contour.len() > 500 { 
-        std::cmp::max(1, contour.len() / 250) 
-    } else { 
-        1 
-    };
-    
-    let mut max_length = 0.0;
-    let mut length_p1 = (0.0, 0.0);
-    let mut length_p2 = (0.0, 0.0);
-    
-    // Sample points for length calculation
-    for i in (0..contour.len()).step_by(sample_step) {
-        for j in ((i + contour.len()/4)..contour.len()).step_by(sample_step) {
-            let p1 = (contour[i].0 as f64, contour[i].1 as f64);
-            let p2 = (contour[j].0 as f64, contour[j].1 as f64);
-            
-            let distance = ((p2.0 - p1.0).powi(2) + (p2.1 - p1.1).powi(2)).sqrt();
-            
-            if distance > max_length {
-                max_length = distance;
-                length_p1 = p1;
-                length_p2 = p2;
-            }
-        }
-    }
+    let feret = calculate_feret_diameters(contour);
+    let (p1, p2) = feret.max_feret_points;
     
-    // Calculate perpendicular width using all points for accuracy
-    let length_vec = (length_p2.0 - length_p1.0, length_p2.1 - length_p1.1);
+    let length_vec = (p2.0 - p1.0, p2.1 - p1.1);
     let length_vec_normalized = {
         let len = (length_vec.0.powi(2) + length_vec.1.powi(2)).sqrt();
         if len > 0.0 {
@@ -179,25 +148,153 @@ pub fn calculate_biological_dimensions_fast(contour: &[(u32, u32)]) -> (f64, f64
         }
     };
     
+    // The hull holds the extreme points of the contour in every direction
+    let perp_vec = (-length_vec_normalized.1, length_vec_normalized.0);
     let mut min_width: f64 = 0.0;
     let mut max_width: f64 = 0.0;
     
-    // Use a smaller sample step for width calculation to maintain accuracy
-    let width_sample_step = std::cmp::max(1, sample_step / 2);
-    
-    for point in contour.iter().step_by(width_sample_step) {
-        let p = (point.0 as f64, point.1 as f64);
-        let to_point = (p.0 - length_p1.0, p.1 - length_p1.1);
-        let perp_vec = (-length_vec_normalized.1, length_vec_normalized.0);
+    for p in &feret.hull {
+        let to_point = (p.0 - p1.0, p.1 - p1.1);
         let perp_distance = to_point.0 * perp_vec.0 + to_point.1 * perp_vec.1;
         
         min_width = min_width.min(perp_distance);
         max_width = max_width.max(perp_distance);
     }
     
-    let width = max_width - min_width;
+    (feret.max_feret, max_width - min_width)
+}
+
+/// Leaf architecture descriptors measured along the base-apex axis
+///
+/// Angles follow the Leaf Architecture Working Group convention: the apex
+/// angle is taken at the apex between the two margin points on the line
+/// perpendicular to the axis at `fraction` of the length from the apex; the
+/// base angle likewise at the base, at `fraction` of the length from the base.
+#[derive(Debug, Clone, Default)]
+pub struct LeafArchitecture {
+    /// Blade base (petiole base, or the lowest contour point)
+    pub base: (f64, f64),
+    /// Contour point farthest from the base
+    pub apex: (f64, f64),
+    /// Base-apex distance
+    pub length: f64,
+    /// Apex angle in degrees
+    pub apex_angle: f64,
+    /// Base angle in degrees
+    pub base_angle: f64,
+    /// Largest width perpendicular to the axis
+    pub max_width: f64,
+    /// Distance of the widest section from the base, as a fraction of the length
+    pub max_width_position: f64,
+}
+
+/// Lowest contour point (largest y), closest to the contour's horizontal centre on ties
+pub fn lowest_contour_point(polygon: &[(f64, f64)]) -> (f64, f64) {
+    if polygon.is_empty() {
+        return (0.0, 0.0);
+    }
     
-    (max_length, width)
+    let center_x = polygon.iter().map(|p| p.0).sum::<f64>() / polygon.len() as f64;
+    polygon.iter()
+        .cloned()
+        .max_by(|a, b| a.1.total_cmp(&b.1).then((b.0 - center_x).abs().total_cmp(&(a.0 - center_x).abs())))
+        .unwrap() // Safe because polygon is non-empty
+}
+
+/// Outermost margin crossings of the line perpendicular to an axis
+///
+/// # Returns
+/// (smallest, largest) signed offset along `normal` of the polygon edges
+/// crossing the line at distance `t` from `origin` along `axis`, or None
+fn section_offsets(
+    polygon: &[(f64, f64)],
+    origin: (f64, f64),
+    axis: (f64, f64),
+    normal: (f64, f64),
+    t: f64,
+) -> Option<(f64, f64)> {
+    let project = |p: (f64, f64), v: (f64, f64)| (p.0 - origin.0) * v.0 + (p.1 - origin.1) * v.1;
+    let n = polygon.len();
+    let mut offsets: Option<(f64, f64)> = None;
+    
+    for i in 0..n {
+        let (p, q) = (polygon[i], polygon[(i + 1) % n]);
+        let (tp, tq) = (project(p, axis), project(q, axis));
+        if (tp <= t && t < tq) || (tq <= t && t < tp) {
+            let s = (t - tp) / (tq - tp);
+            let offset = project(p, normal) + s * (project(q, normal) - project(p, normal));
+            offsets = Some(match offsets {
+                Some((low, high)) => (low.min(offset), high.max(offset)),
+                None => (offset, offset),
+            });
+        }
+    }
+    
+    offsets
+}
+
+/// Measure apex, apex/base angles and the position of maximum width
+///
+/// The apex is the contour point farthest from the base; the axis runs from
+/// the base to the apex. Maximum width is searched on 100 sections
+/// perpendicular to the axis.
+///
+/// # Arguments
+/// * `polygon` - Leaf contour polygon
+/// * `base` - Blade base (e.g. the petiole base)
+/// * `fraction` - Fraction of the length at which the angle sections are taken (0.25 by convention)
+///
+/// # Returns
+/// Leaf architecture descriptors (zero for degenerate contours)
+pub fn calculate_leaf_architecture(polygon: &[(f64, f64)], base: (f64, f64), fraction: f64) -> LeafArchitecture {
+    let distance = |a: (f64, f64), b: (f64, f64)| ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+    
+    let apex = match polygon.iter().cloned().max_by(|a, b| distance(base, *a).total_cmp(&distance(base, *b))) {
+        Some(apex) => apex,
+        None => return LeafArchitecture { base, ..Default::default() },
+    };
+    let length = distance(base, apex);
+    if length <= 0.0 {
+        return LeafArchitecture { base, apex, ..Default::default() };
+    }
+    
+    let axis = ((apex.0 - base.0) / length, (apex.1 - base.1) / length);
+    let normal = (-axis.1, axis.0);
+    let point_at = |t: f64, offset: f64| {
+        (base.0 + axis.0 * t + normal.0 * offset, base.1 + axis.1 * t + normal.1 * offset)
+    };
+    
+    // Angle at a vertex between the two outermost margin points of a section
+    let angle_at = |vertex: (f64, f64), t: f64| {
+        section_offsets(polygon, base, axis, normal, t).map_or(0.0, |(low, high)| {
+            let (a, b) = (point_at(t, low), point_at(t, high));
+            let (u, v) = ((a.0 - vertex.0, a.1 - vertex.1), (b.0 - vertex.0, b.1 - vertex.1));
+            let cosine = (u.0 * v.0 + u.1 * v.1) / ((u.0.hypot(u.1)) * (v.0.hypot(v.1))).max(1e-12);
+            cosine.clamp(-1.0, 1.0).acos().to_degrees()
+        })
+    };
+    
+    let apex_angle = angle_at(apex, (1.0 - fraction) * length);
+    let base_angle = angle_at(base, fraction * length);
+    
+    let sections = 100;
+    let (max_width, max_width_position) = (1..sections)
+        .map(|i| i as f64 / sections as f64)
+        .filter_map(|position| {
+            section_offsets(polygon, base, axis, normal, position * length)
+                .map(|(low, high)| (high - low, position))
+        })
+        .fold((0.0, 0.0), |best, section| if section.0 > best.0 { section } else { best });
+    
+    LeafArchitecture {
+        base,
+        apex,
+        length,
+        apex_angle,
+        base_angle,
+        max_width,
+        max_width_position,
+    }
 }
 
 /// NEW: Calculate Shape Index from length and width
@@ -245,11 +342,11 @@ pub fn calculate_dynamic_opening_percentage(
     dynamic_percentage.max(min_percentage).min(max_percentage)
 }
 
-/// NEW: Calculate length, width, and shape index from image
+/// NEW: Calculate length, width, and shape index of a region
 /// Returns (length, width, shape_index)
-pub fn calculate_length_width_shape_index(image: &RgbaImage, marked_color: [u8; 3]) -> (f64, f64, f64) {
+pub fn calculate_length_width_shape_index(region: &BinaryMask) -> (f64, f64, f64) {
     // Trace contour
-    let contour = trace_contour(image, true, marked_color); // true = pink as opaque for LEC
+    let contour = trace_contour(region);
     
     // Calculate biological dimensions
     let (length, width) = calculate_biological_dimensions_fast(&contour);
@@ -272,19 +369,19 @@ pub fn get_shorter_dimension(length: f64, width: f64) -> f64 {
     length.min(width)
 }
 
-/// NEW: Calculate length, width, shape index, and shorter dimension from image
+/// NEW: Calculate length, width, shape index, and shorter dimension of a region
 /// Returns (length, width, shape_index, shorter_dimension)
-pub fn calculate_length_width_shape_index_with_shorter(image: &RgbaImage, marked_color: [u8; 3]) -> (f64, f64, f64, f64) {
-    let (length, width, shape_index) = calculate_length_width_shape_index(image, marked_color);
+pub fn calculate_length_width_shape_index_with_shorter(region: &BinaryMask) -> (f64, f64, f64, f64) {
+    let (length, width, shape_index) = calculate_length_width_shape_index(region);
     let shorter_dimension = get_shorter_dimension(length, width);
     (length, width, shape_index, shorter_dimension)
 }
 
-/// LEGACY: Calculate length, width, shape index, and longer dimension from image
+/// LEGACY: Calculate length, width, shape index, and longer dimension of a region
 /// Returns (length, width, shape_index, longer_dimension)
 /// DEPRECATED: Use calculate_length_width_shape_index_with_shorter instead
-pub fn calculate_length_width_shape_index_with_longer(image: &RgbaImage, marked_color: [u8; 3]) -> (f64, f64, f64, f64) {
-    let (length, width, shape_index) = calculate_length_width_shape_index(image, marked_color);
+pub fn calculate_length_width_shape_index_with_longer(region: &BinaryMask) -> (f64, f64, f64, f64) {
+    let (length, width, shape_index) = calculate_length_width_shape_index(region);
     let longer_dimension = get_longer_dimension(length, width);
     (length, width, shape_index, longer_dimension)
 }
@@ -359,13 +456,50 @@ pub fn calculate_circularity_from_contour(area: u32, contour: &[(u32, u32)]) ->
     calculate_circularity(area, perimeter)
 }
 
-/// Analyze shape of the image and return area and circularity
-pub fn analyze_shape(image: &RgbaImage, marked_color: [u8; 3]) -> (u32, f64) {
+/// Calculate circularity (4π * Area / Perimeter²) of a sub-pixel boundary polygon
+/// No digitization correction is needed: the polygon has no staircase artefacts
+pub fn calculate_circularity_from_polygon(polygon: &[(f64, f64)]) -> f64 {
+    let perimeter = polygon_perimeter(polygon);
+    if perimeter <= 0.0 {
+        return 0.0;
+    }
+    
+    (4.0 * PI * polygon_signed_area(polygon).abs()) / (perimeter * perimeter)
+}
+
+/// Length, width and shape index from an extracted contour
+/// Returns (length, width, shape_index)
+pub fn calculate_length_width_shape_index_from_contour(contour: &LeafContour) -> (f64, f64, f64) {
+    let (length, width) = calculate_biological_dimensions_polygon(&contour.polygon);
+    let shape_index = calculate_shape_index(length, width);
+    (length, width, shape_index)
+}
+
+/// Comprehensive shape analysis from an extracted contour
+/// Returns (area, circularity, length, width, outline_count, shape_index)
+/// Moore contours keep the smoothed, corrected circularity of `analyze_shape_comprehensive`;
+/// marching-squares contours use the polygon perimeter and area directly
+pub fn analyze_shape_from_contour(region: &BinaryMask, contour: &LeafContour) -> (u32, f64, f64, f64, u32, f64) {
+    let area = calculate_area(region);
+    let (length, width, shape_index) = calculate_length_width_shape_index_from_contour(contour);
+    
+    let circularity = match contour.method {
+        ContourMethod::Moore => calculate_circularity_from_contour(area, &contour.pixels),
+        ContourMethod::MarchingSquares => calculate_circularity_from_polygon(&contour.polygon),
+    };
+    
+    let outline_count = contour.polygon.len() as u32;
+    
+    (area, circularity, length, width, outline_count, shape_index)
+}
+
+/// Analyze shape of a region and return area and circularity
+pub fn analyze_shape(region: &BinaryMask) -> (u32, f64) {
     // Calculate area
-    let area = calculate_area(image);
+    let area = calculate_area(region);
     
     // Trace contour for perimeter calculation
-    let raw_contour = trace_contour(image, false, marked_color);
+    let raw_contour = trace_contour(region);
     
     // Calculate circularity from the contour
     let circularity = calculate_circularity_from_contour(area, &raw_contour);
@@ -377,12 +511,12 @@ pub fn analyze_shape(image: &RgbaImage, marked_color: [u8; 3]) -> (u32, f64) {
 /// Returns (area, circularity, length, width, outline_count, shape_index)
 /// Uses biological length/width instead of axis-aligned bounding box
 /// This function avoids redundant contour tracing by doing it only once
-pub fn analyze_shape_comprehensive(image: &RgbaImage, marked_color: [u8; 3]) -> (u32, f64, f64, f64, u32, f64) {
+pub fn analyze_shape_comprehensive(region: &BinaryMask) -> (u32, f64, f64, f64, u32, f64) {
     // Calculate area (fast - single pass through pixels)
-    let area = calculate_area(image);
+    let area = calculate_area(region);
     
     // Trace contour ONLY ONCE (expensive operation)
-    let raw_contour = trace_contour(image, true, marked_color); // Use true for pink as opaque
+    let raw_contour = trace_contour(region);
     
     // Calculate biological dimensions from the pre-computed contour
     let (length, width) = calculate_biological_dimensions_fast(&raw_contour);
@@ -401,7 +535,382 @@ pub fn analyze_shape_comprehensive(image: &RgbaImage, marked_color: [u8; 3]) ->
 
 /// LEGACY: Comprehensive shape analysis WITHOUT shape index (for backward compatibility)
 /// Returns (area, circularity, length, width, outline_count)
-pub fn analyze_shape_comprehensive_legacy(image: &RgbaImage, marked_color: [u8; 3]) -> (u32, f64, f64, f64, u32) {
-    let (area, circularity, length, width, outline_count, _shape_index) = analyze_shape_comprehensive(image, marked_color);
+pub fn analyze_shape_comprehensive_legacy(region: &BinaryMask) -> (u32, f64, f64, f64, u32) {
+    let (area, circularity, length, width, outline_count, _shape_index) = analyze_shape_comprehensive(region);
     (area, circularity, length, width, outline_count)
-}
\ No newline at end of file
+}
+
+/// Convexity defect: stretch of the contour between two consecutive hull vertices
+#[derive(Debug, Clone)]
+pub struct ConvexityDefect {
+    /// Contour index of the hull vertex opening the defect
+    pub start_index: usize,
+    /// Contour index of the hull vertex closing the defect
+    pub end_index: usize,
+    /// Contour index of the point deepest inside the hull edge
+    pub farthest_index: usize,
+    /// Hull vertex opening the defect
+    pub start: (f64, f64),
+    /// Hull vertex closing the defect
+    pub end: (f64, f64),
+    /// Point deepest inside the hull edge
+    pub farthest: (f64, f64),
+    /// Distance from the farthest point to the hull edge
+    pub depth: f64,
+}
+
+/// Convex hull of a contour with solidity, convexity and convexity defects
+#[derive(Debug, Clone, Default)]
+pub struct ConvexHullMetrics {
+    /// Contour indices of the hull vertices, in contour order
+    pub hull_indices: Vec<usize>,
+    /// Area enclosed by the contour polygon
+    pub area: f64,
+    /// Perimeter of the contour polygon
+    pub perimeter: f64,
+    /// Area of the convex hull
+    pub hull_area: f64,
+    /// Perimeter of the convex hull
+    pub hull_perimeter: f64,
+    /// Area / hull area (1.0 for a convex shape)
+    pub solidity: f64,
+    /// Hull perimeter / perimeter (1.0 for a convex shape)
+    pub convexity: f64,
+    /// Defects at least the minimum depth deep, in contour order
+    pub defects: Vec<ConvexityDefect>,
+}
+
+impl ConvexHullMetrics {
+    /// Depth of the deepest defect (0 without defects)
+    pub fn max_defect_depth(&self) -> f64 {
+        self.defects.iter().map(|d| d.depth).fold(0.0, f64::max)
+    }
+    
+    /// Mean defect depth (0 without defects)
+    pub fn mean_defect_depth(&self) -> f64 {
+        if self.defects.is_empty() {
+            return 0.0;
+        }
+        self.defects.iter().map(|d| d.depth).sum::<f64>() / self.defects.len() as f64
+    }
+}
+
+/// Cross product of (a - o) and (b - o)
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// Convex hull of a point set (Andrew's monotone chain)
+///
+/// Collinear and duplicate points are not hull vertices.
+///
+/// # Arguments
+/// * `points` - Point set (e.g. a contour polygon)
+///
+/// # Returns
+/// Indices of the hull vertices, walking the hull with positive shoelace area
+pub fn convex_hull_indices(points: &[(f64, f64)]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| {
+        points[a].0.total_cmp(&points[b].0).then(points[a].1.total_cmp(&points[b].1))
+    });
+    order.dedup_by(|a, b| points[*a] == points[*b]);
+    
+    if order.len() < 3 {
+        return order;
+    }
+    
+    let mut hull: Vec<usize> = Vec::with_capacity(2 * order.len());
+    for pass in [order.clone(), order.iter().rev().cloned().collect()] {
+        let chain_start = hull.len();
+        for i in pass {
+            while hull.len() >= chain_start + 2
+                && cross(points[hull[hull.len() - 2]], points[hull[hull.len() - 1]], points[i]) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(i);
+        }
+        // Last point of each chain is the first point of the other
+        hull.pop();
+    }
+    
+    hull
+}
+
+/// Convex hull metrics of a contour polygon
+///
+/// Hull vertices are visited in contour order; the contour points between two
+/// consecutive vertices form one candidate defect, whose depth is the largest
+/// distance to the hull edge joining them. Area and perimeter come from the same
+/// polygon as the hull, so solidity and convexity are 1.0 for convex shapes
+/// with either contour method.
+///
+/// # Arguments
+/// * `polygon` - Closed contour polygon (first point not repeated)
+/// * `min_defect_depth` - Minimum depth of a reported defect, in pixels
+///
+/// # Returns
+/// Hull, solidity, convexity and defects
+pub fn calculate_convex_hull_metrics(polygon: &[(f64, f64)], min_defect_depth: f64) -> ConvexHullMetrics {
+    // On a simple polygon the hull vertices appear in contour order
+    let mut hull_indices = convex_hull_indices(polygon);
+    hull_indices.sort_unstable();
+    if hull_indices.len() < 3 {
+        return ConvexHullMetrics { hull_indices, ..Default::default() };
+    }
+    
+    let hull: Vec<(f64, f64)> = hull_indices.iter().map(|&i| polygon[i]).collect();
+    let area = polygon_signed_area(polygon).abs();
+    let perimeter = polygon_perimeter(polygon);
+    let hull_area = polygon_signed_area(&hull).abs();
+    let hull_perimeter = polygon_perimeter(&hull);
+    
+    let n = polygon.len();
+    let mut defects = Vec::new();
+    
+    for (k, &start_index) in hull_indices.iter().enumerate() {
+        let end_index = hull_indices[(k + 1) % hull_indices.len()];
+        let (start, end) = (polygon[start_index], polygon[end_index]);
+        let edge_length = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+        if edge_length <= 0.0 {
+            continue;
+        }
+        
+        let gap = (end_index + n - start_index) % n;
+        let deepest = (1..gap)
+            .map(|offset| (start_index + offset) % n)
+            .map(|i| (i, cross(start, end, polygon[i]).abs() / edge_length))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        
+        if let Some((farthest_index, depth)) = deepest {
+            if depth >= min_defect_depth && depth > 0.0 {
+                defects.push(ConvexityDefect {
+                    start_index,
+                    end_index,
+                    farthest_index,
+                    start,
+                    end,
+                    farthest: polygon[farthest_index],
+                    depth,
+                });
+            }
+        }
+    }
+    
+    ConvexHullMetrics {
+        hull_indices,
+        area,
+        perimeter,
+        hull_area,
+        hull_perimeter,
+        solidity: if hull_area > 0.0 { area / hull_area } else { 0.0 },
+        convexity: if perimeter > 0.0 { hull_perimeter / perimeter } else { 0.0 },
+        defects,
+    }
+}
+
+/// Feret diameters and minimum-area bounding rectangle of a contour
+///
+/// Angles are in degrees in [0, 180), measured counter-clockwise from the image
+/// x axis as the image is displayed (y pointing down).
+#[derive(Debug, Clone, Default)]
+pub struct FeretDiameters {
+    /// Convex hull vertices with positive shoelace area
+    pub hull: Vec<(f64, f64)>,
+    /// Largest distance between two contour points
+    pub max_feret: f64,
+    /// Direction of the maximum Feret diameter
+    pub max_feret_angle: f64,
+    /// End points of the maximum Feret diameter
+    pub max_feret_points: ((f64, f64), (f64, f64)),
+    /// Smallest distance between two parallel supporting lines
+    pub min_feret: f64,
+    /// Direction in which the minimum Feret diameter is measured
+    pub min_feret_angle: f64,
+    /// Longer side of the minimum-area bounding rectangle
+    pub min_rect_length: f64,
+    /// Shorter side of the minimum-area bounding rectangle
+    pub min_rect_width: f64,
+    /// Direction of the longer side of the minimum-area bounding rectangle
+    pub min_rect_angle: f64,
+    /// Corners of the minimum-area bounding rectangle, in order
+    pub min_rect_corners: [(f64, f64); 4],
+}
+
+/// Direction angle of a vector in degrees in [0, 180), y axis pointing down
+fn axis_angle(v: (f64, f64)) -> f64 {
+    let angle = (-v.1).atan2(v.0).to_degrees().rem_euclid(180.0);
+    // Fold -0.0 and rounding up to 180 onto 0
+    if angle >= 180.0 || angle == 0.0 { 0.0 } else { angle }
+}
+
+/// Feret diameters and minimum-area bounding rectangle (rotating calipers)
+///
+/// The convex hull is walked edge by edge while three calipers (farthest point
+/// along the edge normal, and extreme points in both edge directions) advance
+/// monotonically, so all antipodal pairs and edge-aligned rectangles are
+/// visited in O(h) after the O(n log n) hull.
+///
+/// # Arguments
+/// * `polygon` - Contour points (pixel or sub-pixel)
+///
+/// # Returns
+/// Exact maximum and minimum Feret diameters and the minimum-area rectangle
+pub fn calculate_feret_diameters(polygon: &[(f64, f64)]) -> FeretDiameters {
+    let mut hull: Vec<(f64, f64)> = convex_hull_indices(polygon).into_iter().map(|i| polygon[i]).collect();
+    if polygon_signed_area(&hull) < 0.0 {
+        hull.reverse();
+    }
+    
+    let distance = |a: (f64, f64), b: (f64, f64)| ((b.0 - a.0).powi(2) + (b.1 - a.1).powi(2)).sqrt();
+    let dot = |p: (f64, f64), v: (f64, f64)| p.0 * v.0 + p.1 * v.1;
+    
+    let m = hull.len();
+    if m < 3 {
+        // Point or segment: the diameter is the segment, the width is zero
+        let (a, b) = match m {
+            0 => return FeretDiameters { hull, ..Default::default() },
+            1 => (hull[0], hull[0]),
+            _ => (hull[0], hull[1]),
+        };
+        let max_feret = distance(a, b);
+        let angle = axis_angle((b.0 - a.0, b.1 - a.1));
+        return FeretDiameters {
+            max_feret,
+            max_feret_angle: angle,
+            max_feret_points: (a, b),
+            min_feret_angle: (angle + 90.0).rem_euclid(180.0),
+            min_rect_length: max_feret,
+            min_rect_angle: angle,
+            min_rect_corners: [a, b, b, a],
+            hull,
+            ..Default::default()
+        };
+    }
+    
+    let next = |i: usize| (i + 1) % m;
+    let mut result = FeretDiameters {
+        min_feret: f64::INFINITY,
+        ..Default::default()
+    };
+    let mut min_rect_area = f64::INFINITY;
+    
+    // Calipers: j farthest along the inward normal, k and l extreme along the edge
+    let (mut j, mut k, mut l) = (0, 0, 0);
+    
+    for i in 0..m {
+        let (a, b) = (hull[i], hull[next(i)]);
+        let edge_length = distance(a, b);
+        let u = ((b.0 - a.0) / edge_length, (b.1 - a.1) / edge_length);
+        let n = (-u.1, u.0);
+        
+        if i == 0 {
+            k = next(i);
+        }
+        while dot(hull[next(k)], u) > dot(hull[k], u) {
+            k = next(k);
+        }
+        if i == 0 {
+            j = k;
+        }
+        while dot(hull[next(j)], n) > dot(hull[j], n) {
+            j = next(j);
+        }
+        if i == 0 {
+            l = j;
+        }
+        while dot(hull[next(l)], u) < dot(hull[l], u) {
+            l = next(l);
+        }
+        
+        // Antipodal pairs of this edge (both ends of a parallel opposite edge)
+        let mut antipodal = vec![j];
+        if (dot(hull[next(j)], n) - dot(hull[j], n)).abs() < 1e-9 {
+            antipodal.push(next(j));
+        }
+        for &p in &antipodal {
+            for q in [a, b] {
+                let d = distance(q, hull[p]);
+                if d > result.max_feret {
+                    result.max_feret = d;
+                    result.max_feret_points = (q, hull[p]);
+                }
+            }
+        }
+        
+        let width = dot(hull[j], n) - dot(a, n);
+        if width < result.min_feret {
+            result.min_feret = width;
+            result.min_feret_angle = axis_angle(n);
+        }
+        
+        let (low, high) = (dot(hull[l], u) - dot(a, u), dot(hull[k], u) - dot(a, u));
+        let extent = high - low;
+        if extent * width < min_rect_area {
+            min_rect_area = extent * width;
+            let corner = |s: f64, t: f64| (a.0 + u.0 * s + n.0 * t, a.1 + u.1 * s + n.1 * t);
+            result.min_rect_corners = [corner(low, 0.0), corner(high, 0.0), corner(high, width), corner(low, width)];
+            if extent >= width {
+                result.min_rect_length = extent;
+                result.min_rect_width = width;
+                result.min_rect_angle = axis_angle(u);
+            } else {
+                result.min_rect_length = width;
+                result.min_rect_width = extent;
+                result.min_rect_angle = axis_angle(n);
+            }
+        }
+    }
+    
+    let (p, q) = result.max_feret_points;
+    result.max_feret_angle = axis_angle((q.0 - p.0, q.1 - p.1));
+    result.hull = hull;
+    result
+}
+
+/// Scale-free dissection and compactness indices used in leaf ecology
+#[derive(Debug, Clone, Default)]
+pub struct ShapeIndices {
+    /// Perimeter / sqrt(area)
+    pub dissection_index: f64,
+    /// Perimeter / (2 * sqrt(π * area)); 1.0 for a circle
+    pub normalized_dissection_index: f64,
+    /// Perimeter / area (1 / pixels)
+    pub perimeter_area_ratio: f64,
+    /// 4π * area / perimeter²; 1.0 for a circle
+    pub form_factor: f64,
+    /// 4 * area / (π * max Feret²); 1.0 for a circle
+    pub roundness: f64,
+    /// Longer / shorter side of the minimum-area bounding rectangle
+    pub aspect_ratio: f64,
+}
+
+/// Dissection index, perimeter-to-area ratio, form factor, roundness and aspect ratio
+///
+/// Area is the pixel count of the region mask (`calculate_area`),
+/// perimeter that of its contour polygon. The polygon through the boundary
+/// pixel centres would underestimate the area by about half the perimeter,
+/// biasing every index on small or dissected leaves.
+///
+/// # Arguments
+/// * `area` - Number of region pixels
+/// * `hull` - Convex hull metrics of the region (contour perimeter)
+/// * `feret` - Feret diameters and minimum bounding rectangle of the region
+///
+/// # Returns
+/// The indices (all 0 for an empty region)
+pub fn calculate_shape_indices(area: u32, hull: &ConvexHullMetrics, feret: &FeretDiameters) -> ShapeIndices {
+    let (area, perimeter) = (area as f64, hull.perimeter);
+    if area <= 0.0 || perimeter <= 0.0 {
+        return ShapeIndices::default();
+    }
+    
+    ShapeIndices {
+        dissection_index: perimeter / area.sqrt(),
+        normalized_dissection_index: perimeter / (2.0 * (PI * area).sqrt()),
+        perimeter_area_ratio: perimeter / area,
+        form_factor: 4.0 * PI * area / (perimeter * perimeter),
+        roundness: if feret.max_feret > 0.0 { 4.0 * area / (PI * feret.max_feret * feret.max_feret) } else { 0.0 },
+        aspect_ratio: if feret.min_rect_width > 0.0 { feret.min_rect_length / feret.min_rect_width } else { 0.0 },
+    }
+}
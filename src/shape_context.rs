@@ -0,0 +1,247 @@
+// src/shape_context.rs - Inner-distance shape context (IDSC) descriptor
+
+use std::f64::consts::PI;
+
+use crate::leaf_mask::BinaryMask;
+use crate::path_algorithms::calculate_geodesic_shortest_path_tree;
+
+/// Inner distance bins span [1/8, 2] times the mean inner distance (log-spaced)
+const IDSC_MIN_RELATIVE_DISTANCE: f64 = 0.125;
+const IDSC_MAX_RELATIVE_DISTANCE: f64 = 2.0;
+
+/// Geodesic distance from the sample point used to measure the inner angle
+/// (avoids the 45° quantisation of the first pixel step)
+const INNER_ANGLE_PROBE_DISTANCE: f64 = 3.0;
+
+/// Inner-distance shape context of a single leaf
+///
+/// One log-polar histogram per sampled contour point. Histograms are
+/// normalised to sum to 1 and stored row-major as `distance_bin * angle_bins + angle_bin`.
+#[derive(Debug, Clone)]
+pub struct InnerDistanceShapeContext {
+    /// Sampled contour points in contour order
+    pub points: Vec<(u32, u32)>,
+    /// Histogram per sampled point (feature matrix rows)
+    pub histograms: Vec<Vec<f64>>,
+    /// Number of log inner-distance bins
+    pub distance_bins: usize,
+    /// Number of inner-angle bins
+    pub angle_bins: usize,
+    /// Mean inner distance used for scale normalisation
+    pub mean_inner_distance: f64,
+}
+
+/// Calculate the inner-distance shape context for a leaf
+///
+/// Samples `sample_count` evenly spaced contour points. For every ordered pair
+/// the inner distance (geodesic distance inside the leaf) and the inner angle
+/// (direction of the shortest path leaving the point, relative to the contour
+/// tangent) are binned into a log-polar histogram.
+///
+/// # Arguments
+/// * `region` - Region to navigate through (MC region)
+/// * `contour` - Traced contour of the same region
+/// * `sample_count` - Number of contour points to sample
+/// * `distance_bins` - Number of log inner-distance bins
+/// * `angle_bins` - Number of inner-angle bins
+///
+/// # Returns
+/// Descriptor (with no rows if the contour is too short)
+pub fn calculate_inner_distance_shape_context(
+    region: &BinaryMask,
+    contour: &[(u32, u32)],
+    sample_count: usize,
+    distance_bins: usize,
+    angle_bins: usize,
+) -> InnerDistanceShapeContext {
+    let mut descriptor = InnerDistanceShapeContext {
+        points: Vec::new(),
+        histograms: Vec::new(),
+        distance_bins,
+        angle_bins,
+        mean_inner_distance: 0.0,
+    };
+    
+    if contour.len() < 3 || sample_count < 3 || distance_bins == 0 || angle_bins == 0 {
+        return descriptor;
+    }
+    
+    let (width, _) = region.dimensions();
+    let n = sample_count.min(contour.len());
+    let points: Vec<(u32, u32)> = (0..n)
+        .map(|i| contour[i * contour.len() / n])
+        .collect();
+    
+    // Inner distance and inner angle for every ordered pair
+    let mut inner_distances = vec![vec![f64::INFINITY; n]; n];
+    let mut inner_angles = vec![vec![0.0; n]; n];
+    
+    for i in 0..n {
+        let source = points[i];
+        let (distances, predecessors) = calculate_geodesic_shortest_path_tree(source, region);
+        
+        // Contour tangent from central difference of neighbouring samples
+        let prev = points[(i + n - 1) % n];
+        let next = points[(i + 1) % n];
+        let tangent_angle = (next.1 as f64 - prev.1 as f64).atan2(next.0 as f64 - prev.0 as f64);
+        
+        for j in 0..n {
+            if i == j {
+                continue;
+            }
+            
+            let target_index = (points[j].1 * width + points[j].0) as usize;
+            let distance = distances[target_index];
+            if !distance.is_finite() {
+                continue;
+            }
+            
+            // Walk back towards the source until the probe distance is reached
+            let mut probe = target_index;
+            while predecessors[probe] != usize::MAX
+                && distances[predecessors[probe]] >= INNER_ANGLE_PROBE_DISTANCE
+            {
+                probe = predecessors[probe];
+            }
+            
+            let probe_x = (probe as u32 % width) as f64;
+            let probe_y = (probe as u32 / width) as f64;
+            let path_angle = (probe_y - source.1 as f64).atan2(probe_x - source.0 as f64);
+            
+            inner_distances[i][j] = distance;
+            inner_angles[i][j] = (path_angle - tangent_angle).rem_euclid(2.0 * PI);
+        }
+    }
+    
+    // Scale normalisation by the mean inner distance
+    let finite: Vec<f64> = inner_distances.iter()
+        .flatten()
+        .cloned()
+        .filter(|d| d.is_finite())
+        .collect();
+    
+    if finite.is_empty() {
+        return descriptor;
+    }
+    
+    let mean_inner_distance = finite.iter().sum::<f64>() / finite.len() as f64;
+    
+    let log_min = IDSC_MIN_RELATIVE_DISTANCE.ln();
+    let log_max = IDSC_MAX_RELATIVE_DISTANCE.ln();
+    let log_step = (log_max - log_min) / distance_bins as f64;
+    
+    let histograms: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            let mut histogram = vec![0.0; distance_bins * angle_bins];
+            
+            for j in 0..n {
+                let distance = inner_distances[i][j];
+                if i == j || !distance.is_finite() {
+                    continue;
+                }
+                
+                let relative = distance / mean_inner_distance;
+                if relative >= IDSC_MAX_RELATIVE_DISTANCE {
+                    continue;
+                }
+                
+                let distance_bin = if relative <= IDSC_MIN_RELATIVE_DISTANCE {
+                    0
+                } else {
+                    (((relative.ln() - log_min) / log_step) as usize).min(distance_bins - 1)
+                };
+                let angle_bin = ((inner_angles[i][j] / (2.0 * PI) * angle_bins as f64) as usize)
+                    .min(angle_bins - 1);
+                
+                histogram[distance_bin * angle_bins + angle_bin] += 1.0;
+            }
+            
+            let total: f64 = histogram.iter().sum();
+            if total > 0.0 {
+                for value in &mut histogram {
+                    *value /= total;
+                }
+            }
+            
+            histogram
+        })
+        .collect();
+    
+    descriptor.points = points;
+    descriptor.histograms = histograms;
+    descriptor.mean_inner_distance = mean_inner_distance;
+    descriptor
+}
+
+/// Chi-square cost between two normalised histograms
+fn chi_square_cost(h1: &[f64], h2: &[f64]) -> f64 {
+    0.5 * h1.iter()
+        .zip(h2.iter())
+        .filter(|(&a, &b)| a + b > 0.0)
+        .map(|(&a, &b)| (a - b).powi(2) / (a + b))
+        .sum::<f64>()
+}
+
+/// Calculate the IDSC matching distance between two leaves
+///
+/// Both descriptors are sampled in contour order, so points are matched in
+/// order and the best cyclic alignment of the start point is searched.
+/// The distance is the mean chi-square cost of the best alignment
+/// (0 = identical, larger = more dissimilar).
+///
+/// # Arguments
+/// * `a` - Descriptor of the first leaf
+/// * `b` - Descriptor of the second leaf
+///
+/// # Returns
+/// Matching distance, or `f64::INFINITY` if the descriptors are empty or use different binning
+pub fn inner_distance_shape_context_distance(
+    a: &InnerDistanceShapeContext,
+    b: &InnerDistanceShapeContext,
+) -> f64 {
+    if a.histograms.is_empty() || b.histograms.is_empty()
+        || a.distance_bins != b.distance_bins || a.angle_bins != b.angle_bins
+    {
+        return f64::INFINITY;
+    }
+    
+    let na = a.histograms.len();
+    let nb = b.histograms.len();
+    
+    // Pairwise cost matrix
+    let costs: Vec<Vec<f64>> = a.histograms.iter()
+        .map(|ha| b.histograms.iter().map(|hb| chi_square_cost(ha, hb)).collect())
+        .collect();
+    
+    (0..nb)
+        .map(|shift| {
+            (0..na)
+                .map(|i| costs[i][(i * nb / na + shift) % nb])
+                .sum::<f64>() / na as f64
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Calculate the pairwise IDSC distance matrix for a batch of leaves
+///
+/// # Arguments
+/// * `descriptors` - Descriptors of all leaves in the batch
+///
+/// # Returns
+/// Symmetric matrix with zeros on the diagonal, for retrieval and matching
+pub fn inner_distance_shape_context_distance_matrix(
+    descriptors: &[InnerDistanceShapeContext],
+) -> Vec<Vec<f64>> {
+    let n = descriptors.len();
+    let mut matrix = vec![vec![0.0; n]; n];
+    
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let distance = inner_distance_shape_context_distance(&descriptors[i], &descriptors[j]);
+            matrix[i][j] = distance;
+            matrix[j][i] = distance;
+        }
+    }
+    
+    matrix
+}
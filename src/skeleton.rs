@@ -0,0 +1,286 @@
+// src/skeleton.rs - Medial axis (skeleton) of the MC leaf with branch pruning and statistics
+
+use std::collections::HashMap;
+
+use crate::connected_components::label_components;
+use crate::holes::find_holes;
+use crate::leaf_mask::BinaryMask;
+use crate::morphology::distance_to_background;
+
+/// Ring of 8 neighbours in circular order, starting north (P2..P9 in Zhang-Suen)
+static RING: [(i32, i32); 8] = [
+    (0, -1),  // N
+    (1, -1),  // NE
+    (1, 0),   // E
+    (1, 1),   // SE
+    (0, 1),   // S
+    (-1, 1),  // SW
+    (-1, 0),  // W
+    (-1, -1), // NW
+];
+
+/// Pruned skeleton of a region with branch and inscribed-radius statistics
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    /// Skeleton pixels after pruning
+    pub mask: BinaryMask,
+    /// Skeleton length in pixels (diagonal steps count sqrt(2))
+    pub length: f64,
+    /// Number of branches (skeleton segments between endpoints and junctions)
+    pub branch_count: usize,
+    /// Number of endpoints (one per lobe tip, plus the petiole if present)
+    pub endpoint_count: usize,
+    /// Number of junctions (clusters of branching pixels)
+    pub junction_count: usize,
+    /// Largest inscribed radius along the axis
+    pub max_inscribed_radius: f64,
+    /// Mean inscribed radius along the axis
+    pub mean_inscribed_radius: f64,
+    /// Number of spurious branches removed by pruning
+    pub pruned_branch_count: usize,
+}
+
+/// Neighbours of a pixel in the ring, as booleans
+fn ring_values(mask: &BinaryMask, x: u32, y: u32) -> [bool; 8] {
+    let mut values = [false; 8];
+    for (value, &(dx, dy)) in values.iter_mut().zip(RING.iter()) {
+        *value = mask.get_or_false(x as i32 + dx, y as i32 + dy);
+    }
+    values
+}
+
+/// Number of background-to-foreground transitions around the ring
+///
+/// 1 for endpoints, 2 for pixels on a branch (including staircase corners),
+/// 3 or more for junctions.
+fn crossing_number(ring: &[bool; 8]) -> usize {
+    (0..8).filter(|&i| !ring[i] && ring[(i + 1) % 8]).count()
+}
+
+/// Thin a region to a one-pixel-wide 8-connected skeleton (Zhang-Suen)
+///
+/// # Arguments
+/// * `region` - Region to thin
+///
+/// # Returns
+/// Skeleton pixels (topology of the region is preserved)
+pub fn zhang_suen_thinning(region: &BinaryMask) -> BinaryMask {
+    let (width, height) = region.dimensions();
+    let mut skeleton = region.clone();
+    
+    loop {
+        let mut changed = false;
+        
+        for step in 0..2 {
+            let mut deletions = Vec::new();
+            
+            for y in 0..height {
+                for x in 0..width {
+                    if !skeleton.get(x, y) {
+                        continue;
+                    }
+                    
+                    let ring = ring_values(&skeleton, x, y);
+                    let neighbours = ring.iter().filter(|&&v| v).count();
+                    if !(2..=6).contains(&neighbours) || crossing_number(&ring) != 1 {
+                        continue;
+                    }
+                    
+                    // ring: 0 = P2 (N), 2 = P4 (E), 4 = P6 (S), 6 = P8 (W)
+                    let (p2, p4, p6, p8) = (ring[0], ring[2], ring[4], ring[6]);
+                    // Step 1: P2*P4*P6 = 0 and P4*P6*P8 = 0; step 2: P2*P4*P8 = 0 and P2*P6*P8 = 0
+                    let removable = if step == 0 {
+                        !(p4 && p6 && (p2 || p8))
+                    } else {
+                        !(p2 && p8 && (p4 || p6))
+                    };
+                    
+                    if removable {
+                        deletions.push((x, y));
+                    }
+                }
+            }
+            
+            changed |= !deletions.is_empty();
+            for (x, y) in deletions {
+                skeleton.set(x, y, false);
+            }
+        }
+        
+        if !changed {
+            break;
+        }
+    }
+    
+    skeleton
+}
+
+/// Terminal branch of a skeleton
+struct TerminalBranch {
+    /// Branch pixels, endpoint first, junction excluded
+    pixels: Vec<(u32, u32)>,
+    /// Junction the branch ends at, or None if it ends at another endpoint
+    junction: Option<(u32, u32)>,
+}
+
+/// Follow a terminal branch from an endpoint to the first junction
+///
+/// Four-connected steps are preferred so staircase corners are walked rather
+/// than skipped.
+fn trace_branch(skeleton: &BinaryMask, endpoint: (u32, u32)) -> TerminalBranch {
+    let (width, height) = skeleton.dimensions();
+    let max_length = (width * height) as usize;
+    let mut branch = vec![endpoint];
+    let mut current = endpoint;
+    
+    loop {
+        let ring = ring_values(skeleton, current.0, current.1);
+        if current != endpoint && crossing_number(&ring) >= 3 {
+            branch.pop();
+            return TerminalBranch { pixels: branch, junction: Some(current) };
+        }
+        
+        let next = [0, 2, 4, 6, 1, 3, 5, 7].iter()
+            .filter(|&&i| ring[i])
+            .map(|&i| ((current.0 as i32 + RING[i].0) as u32, (current.1 as i32 + RING[i].1) as u32))
+            .find(|pixel| !branch.iter().rev().take(3).any(|p| p == pixel));
+        
+        match next {
+            Some(pixel) if branch.len() <= max_length => {
+                branch.push(pixel);
+                current = pixel;
+            }
+            _ => return TerminalBranch { pixels: branch, junction: None },
+        }
+    }
+}
+
+/// Endpoints of a skeleton (pixels with a single neighbour run)
+fn find_endpoints(skeleton: &BinaryMask) -> Vec<(u32, u32)> {
+    let (width, height) = skeleton.dimensions();
+    let mut endpoints = Vec::new();
+    
+    for y in 0..height {
+        for x in 0..width {
+            if skeleton.get(x, y) && crossing_number(&ring_values(skeleton, x, y)) == 1 {
+                endpoints.push((x, y));
+            }
+        }
+    }
+    
+    endpoints
+}
+
+/// Skeleton length with diagonal steps counted once where no 4-connected path bridges them
+fn skeleton_length(skeleton: &BinaryMask) -> f64 {
+    let (width, height) = skeleton.dimensions();
+    let mut length = 0.0;
+    
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            if !skeleton.get(x as u32, y as u32) {
+                continue;
+            }
+            
+            for (dx, dy) in [(1, 0), (0, 1)] {
+                if skeleton.get_or_false(x + dx, y + dy) {
+                    length += 1.0;
+                }
+            }
+            for (dx, dy) in [(1, 1), (-1, 1)] {
+                let bridged = skeleton.get_or_false(x + dx, y) || skeleton.get_or_false(x, y + dy);
+                if skeleton.get_or_false(x + dx, y + dy) && !bridged {
+                    length += std::f64::consts::SQRT_2;
+                }
+            }
+        }
+    }
+    
+    length
+}
+
+/// Extract the pruned medial axis of a region
+///
+/// The region is thinned with Zhang-Suen. A terminal branch is spurious (a
+/// boundary irregularity rather than a lobe) when it protrudes less than
+/// `prune_ratio` times the inscribed radius at its junction beyond that
+/// inscribed disc. The shortest spurious branch of every junction is removed
+/// repeatedly until none is left; a path between two endpoints is never removed.
+///
+/// # Arguments
+/// * `region` - Region to skeletonise (MC region)
+/// * `prune_ratio` - Minimum protrusion of a kept branch, relative to the junction's inscribed radius
+///
+/// # Returns
+/// Pruned skeleton with statistics
+pub fn extract_skeleton(region: &BinaryMask, prune_ratio: f64) -> Skeleton {
+    let (width, height) = region.dimensions();
+    let radii = distance_to_background(region);
+    let radius = |(x, y): (u32, u32)| radii[(y * width + x) as usize];
+    
+    let mut mask = zhang_suen_thinning(region);
+    let mut pruned_branch_count = 0;
+    
+    loop {
+        // Shortest spurious branch at each junction (removing two branches of
+        // one junction at once could cut the main axis)
+        let mut spurious: HashMap<(u32, u32), Vec<(u32, u32)>> = HashMap::new();
+        for endpoint in find_endpoints(&mask) {
+            let TerminalBranch { pixels: branch, junction } = trace_branch(&mask, endpoint);
+            let junction = match junction {
+                Some(j) => j,
+                None => continue,
+            };
+            let protrusion = branch.len() as f64 + radius(endpoint) - radius(junction);
+            if protrusion < prune_ratio * radius(junction) 
+                && spurious.get(&junction).is_none_or(|kept| branch.len() < kept.len()) {
+                spurious.insert(junction, branch);
+            }
+        }
+        
+        if spurious.is_empty() {
+            break;
+        }
+        
+        for branch in spurious.into_values() {
+            for (x, y) in branch {
+                mask.set(x, y, false);
+            }
+            pruned_branch_count += 1;
+        }
+    }
+    
+    // Branches are the edges of the skeleton graph (nodes: endpoints and
+    // junction clusters); by Euler's formula edges = nodes - components + cycles
+    let junctions = BinaryMask::from_fn(width, height, |x, y| {
+        mask.get(x, y) && crossing_number(&ring_values(&mask, x, y)) >= 3
+    });
+    let endpoint_count = find_endpoints(&mask).len();
+    let junction_count = label_components(&junctions).stats.len();
+    let component_count = label_components(&mask).stats.len();
+    let cycle_count = find_holes(&mask).0.stats.iter().filter(|stats| !stats.touches_border()).count();
+    let branch_count = (endpoint_count + junction_count + cycle_count).saturating_sub(component_count);
+    
+    let axis_radii: Vec<f64> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .filter(|&(x, y)| mask.get(x, y))
+        .map(radius)
+        .collect();
+    let max_inscribed_radius = axis_radii.iter().cloned().fold(0.0, f64::max);
+    let mean_inscribed_radius = if axis_radii.is_empty() {
+        0.0
+    } else {
+        axis_radii.iter().sum::<f64>() / axis_radii.len() as f64
+    };
+    
+    Skeleton {
+        length: skeleton_length(&mask),
+        branch_count,
+        endpoint_count,
+        junction_count,
+        max_inscribed_radius,
+        mean_inscribed_radius,
+        pruned_branch_count,
+        mask,
+    }
+}
@@ -0,0 +1,144 @@
+// src/teeth.rs - Marginal tooth detection from the EC opening residues
+
+use crate::connected_components::{label_components, ComponentLabels};
+use crate::leaf_mask::{BinaryMask, LeafMask, EC_OPENED, PETIOLE};
+use crate::protrusion::{deepest_sinus, measure_protrusion};
+
+/// One marginal tooth (a component of the EC opening residue)
+#[derive(Debug, Clone)]
+pub struct Tooth {
+    /// Contour index of the apex
+    pub apex_index: usize,
+    /// Tooth pixel on the margin farthest from the base line
+    pub apex: (u32, u32),
+    /// Deepest margin point between this tooth and the next one (None for a single tooth)
+    pub sinus: Option<(u32, u32)>,
+    /// Ends of the base line (where the tooth meets the opened blade)
+    pub base: ((u32, u32), (u32, u32)),
+    /// Distance from the apex to the base line
+    pub height: f64,
+    /// Length of the base line
+    pub width: f64,
+    /// Number of tooth pixels
+    pub area: usize,
+    /// Margin arc length from this apex to the next one
+    pub spacing: f64,
+}
+
+/// Teeth of a leaf with per-leaf aggregates
+#[derive(Debug, Clone, Default)]
+pub struct TeethMetrics {
+    /// Teeth in contour order
+    pub teeth: Vec<Tooth>,
+    /// Margin length in pixels
+    pub perimeter: f64,
+    /// Number of teeth per centimetre of margin
+    pub teeth_per_cm: f64,
+    /// Mean tooth area divided by the margin length
+    pub mean_area_over_perimeter: f64,
+    /// Mean tooth height
+    pub mean_height: f64,
+    /// Mean tooth width
+    pub mean_width: f64,
+    /// Mean apex-to-apex arc length
+    pub mean_spacing: f64,
+}
+
+/// Measure one tooth component
+fn measure_tooth(
+    labels: &ComponentLabels,
+    label: u32,
+    area: usize,
+    blade: &BinaryMask,
+    contour: &[(u32, u32)],
+) -> Option<Tooth> {
+    let protrusion = measure_protrusion(labels, label, blade, contour)?;
+    
+    Some(Tooth {
+        apex_index: protrusion.apex_index,
+        apex: contour[protrusion.apex_index],
+        sinus: None,
+        base: protrusion.base,
+        height: protrusion.height,
+        width: protrusion.width,
+        area,
+        spacing: 0.0,
+    })
+}
+
+/// Detect marginal teeth as the components of the EC opening residue
+///
+/// Every component of the `EC_OPENED` layer (pink region) on the margin is a
+/// tooth, except pixels marked as `PETIOLE`. Its base is the line between the farthest pair of pixels where it
+/// meets the opened blade; the apex is its margin pixel farthest from that
+/// line. Teeth are ordered along the contour; the sinus after a tooth is the
+/// margin point deepest below the chord to the next apex, and the spacing is
+/// the margin arc length between the two apices.
+///
+/// # Arguments
+/// * `mask` - Leaf mask with the `EC_OPENED` layer marked
+/// * `contour` - EC contour pixels
+/// * `min_area` - Smallest tooth in pixels (smaller residues are noise)
+/// * `pixels_per_mm` - Image scale, for teeth per centimetre
+///
+/// # Returns
+/// Teeth in contour order with per-leaf aggregates
+pub fn detect_teeth(
+    mask: &LeafMask,
+    contour: &[(u32, u32)],
+    min_area: usize,
+    pixels_per_mm: f64,
+) -> TeethMetrics {
+    let (width, height) = mask.dimensions();
+    let residue = BinaryMask::from_fn(width, height, |x, y| {
+        mask.is_leaf(x, y) && mask.has(x, y, EC_OPENED) && !mask.has(x, y, PETIOLE)
+    });
+    let blade = BinaryMask::from_fn(width, height, |x, y| mask.is_leaf(x, y) && !mask.has(x, y, EC_OPENED));
+    let labels = label_components(&residue);
+    
+    let mut teeth: Vec<Tooth> = labels.stats.iter()
+        .filter(|stats| stats.area >= min_area)
+        .filter_map(|stats| measure_tooth(&labels, stats.label, stats.area, &blade, contour))
+        .collect();
+    teeth.sort_by_key(|tooth| tooth.apex_index);
+    
+    // Cumulative arc length along the closed contour
+    let n = contour.len();
+    let mut arc = Vec::with_capacity(n + 1);
+    arc.push(0.0);
+    for i in 0..n {
+        let (a, b) = (contour[i], contour[(i + 1) % n]);
+        let step = (a.0 as f64 - b.0 as f64).hypot(a.1 as f64 - b.1 as f64);
+        arc.push(arc[i] + step);
+    }
+    let perimeter = arc[n];
+    
+    let count = teeth.len();
+    for i in 0..count {
+        let (start, end) = (teeth[i].apex_index, teeth[(i + 1) % count].apex_index);
+        teeth[i].spacing = if count > 1 {
+            (arc[end] - arc[start]).rem_euclid(perimeter)
+        } else {
+            perimeter
+        };
+        
+        if count > 1 {
+            teeth[i].sinus = deepest_sinus(contour, start, end).map(|(sinus, _)| sinus);
+        }
+    }
+    
+    let mean = |value: fn(&Tooth) -> f64| {
+        if count == 0 { 0.0 } else { teeth.iter().map(value).sum::<f64>() / count as f64 }
+    };
+    let perimeter_cm = perimeter / pixels_per_mm / 10.0;
+    
+    TeethMetrics {
+        perimeter,
+        teeth_per_cm: if perimeter_cm > 0.0 { count as f64 / perimeter_cm } else { 0.0 },
+        mean_area_over_perimeter: if perimeter > 0.0 { mean(|t| t.area as f64) / perimeter } else { 0.0 },
+        mean_height: mean(|t| t.height),
+        mean_width: mean(|t| t.width),
+        mean_spacing: mean(|t| t.spacing),
+        teeth,
+    }
+}
@@ -6,11 +6,10 @@ use std::collections::HashSet;
 use rustfft::{FftPlanner, num_complex::Complex};
 use csv::Writer;
 use std::f64::consts::PI;
-use image::RgbaImage;
 
 use crate::errors::{LeafComplexError, Result};
 use crate::feature_extraction::MarginalPointFeatures;
-use crate::image_utils::has_rgb_color;
+use crate::leaf_mask::LeafMask;
 use crate::path_algorithms::trace_straight_line;
 
 /// Represents a chain of consecutive golden pixel crossings (lobes)
@@ -86,32 +85,26 @@ fn extract_contour_signature(contour: &[(u32, u32)], interpolation_points: usize
 }
 
 /// Simple smoothing filter to reduce noise
+///
+/// The signal is treated as circular (closed contour), so the window wraps
+/// around index 0 instead of shrinking at the ends.
 fn smooth_signal(signal: &[f64], window_size: usize) -> Vec<f64> {
     if signal.len() < 3 || window_size == 0 {
         return signal.to_vec();
     }
     
-    let mut smoothed = Vec::with_capacity(signal.len());
-    let half_window = window_size / 2;
-    
-    for i in 0..signal.len() {
-        let mut sum = 0.0;
-        let mut count = 0;
-        
-        // Calculate window bounds
-        let start = if i >= half_window { i - half_window } else { 0 };
-        let end = std::cmp::min(i + half_window + 1, signal.len());
-        
-        // Average over window
-        for j in start..end {
-            sum += signal[j];
-            count += 1;
-        }
-        
-        smoothed.push(sum / count as f64);
-    }
-    
-    smoothed
+    let n = signal.len();
+    let half_window = (window_size / 2).min((n - 1) / 2);
+    let count = 2 * half_window + 1;
+    
+    (0..n)
+        .map(|i| {
+            let sum: f64 = (0..count)
+                .map(|k| signal[(i + n + k - half_window) % n])
+                .sum();
+            sum / count as f64
+        })
+        .collect()
 }
 
 /// REVISED: Calculate spectral entropy from contour with continuous sigmoid scaling
@@ -184,14 +177,17 @@ pub fn detect_petiole_sequence(signal: &[f64], threshold: f64) -> Option<Vec<usi
     let mut current_sequence = Vec::new();
     let mut has_extreme_value = false;
     
-    // We'll wrap around the contour to handle sequences that cross the start/end boundary
-    let extended_signal: Vec<f64> = signal.iter()
-        .chain(signal.iter())
-        .cloned()
-        .collect();
+    // The signal is circular: start scanning just after a point below threshold
+    // so a sequence crossing the start/end boundary is found in one piece
+    let n = signal.len();
+    let scan_start = match signal.iter().position(|&value| value <= threshold) {
+        Some(below) => below + 1,
+        None => 0,
+    };
     
-    for i in 0..signal.len() {
-        let value = extended_signal[i];
+    for offset in 0..n {
+        let i = (scan_start + offset) % n;
+        let value = signal[i];
         
         if value > threshold {
             current_sequence.push(i);
@@ -225,6 +221,31 @@ pub fn detect_petiole_sequence(signal: &[f64], threshold: f64) -> Option<Vec<usi
     }
 }
 
+/// Locate the petiole base on a contour from the EC pink signal
+///
+/// The base is the middle point of the detected petiole sequence, which does
+/// not depend on where the contour starts or how the leaf is oriented.
+///
+/// # Arguments
+/// * `features` - EC features of every contour point
+/// * `marginal_points` - Contour the features were measured on
+/// * `threshold` - Pink count threshold of the petiole sequence
+///
+/// # Returns
+/// Contour pixel of the petiole base, or None when no petiole is detected
+pub fn detect_petiole_base(
+    features: &[MarginalPointFeatures],
+    marginal_points: &[(u32, u32)],
+    threshold: f64,
+) -> Option<(u32, u32)> {
+    let pink_signal = extract_pink_path_signal(features);
+    let sequence = detect_petiole_sequence(&pink_signal, threshold)?;
+    
+    sequence.get(sequence.len() / 2)
+        .and_then(|&i| marginal_points.get(i))
+        .copied()
+}
+
 /// Apply petiole filter to a signal
 pub fn apply_petiole_filter(signal: &[f64], petiole_indices: &[usize], remove_completely: bool) -> Vec<f64> {
     if petiole_indices.is_empty() {
@@ -455,7 +476,9 @@ fn calculate_power_spectrum_periodic(signal: &[f64]) -> Vec<f64> {
         return Vec::new();
     }
     
-    let mut fft_size = signal.len();
+    // No zero padding: the FFT covers exactly one period, so the power
+    // spectrum does not depend on where the closed contour starts
+    let fft_size = signal.len();
     
     // Convert to complex numbers (remove DC component)
     let mut complex_input: Vec<Complex<f64>> = signal
@@ -463,12 +486,6 @@ fn calculate_power_spectrum_periodic(signal: &[f64]) -> Vec<f64> {
         .map(|&x| Complex::new(x - mean, 0.0))
         .collect();
     
-    // Extend to next power of 2 for efficiency if needed
-    if !fft_size.is_power_of_two() {
-        fft_size = fft_size.next_power_of_two();
-        complex_input.resize(fft_size, Complex::new(0.0, 0.0));
-    }
-    
     // Perform FFT
     let mut planner = FftPlanner::new();
     let fft = planner.plan_fft_forward(fft_size);
@@ -582,83 +599,6 @@ pub fn calculate_spectral_entropy_from_thornfiddle_path(
 
 // (Removed duplicate legacy function definition)
 
-/// Calculate approximate entropy from Pink Path
-pub fn calculate_approximate_entropy_from_pink_path(
-    features: &[MarginalPointFeatures],
-    m: usize,
-    r: f64,
-) -> f64 {
-    if features.is_empty() {
-        return 0.0;
-    }
-    
-    let pink_signal = extract_pink_path_signal(features);
-    
-    if pink_signal.len() < 4 {
-        return 0.0;
-    }
-    
-    let mean = pink_signal.iter().sum::<f64>() / pink_signal.len() as f64;
-    let variance = pink_signal.iter()
-        .map(|&x| (x - mean).powi(2))
-        .sum::<f64>() / pink_signal.len() as f64;
-    let std_dev = variance.sqrt();
-    
-    let adaptive_r = if std_dev > 1e-6 {
-        r * std_dev
-    } else {
-        r
-    };
-    
-    calculate_approximate_entropy(&pink_signal, m, adaptive_r)
-}
-
-/// Calculate approximate entropy for a given signal
-pub fn calculate_approximate_entropy(signal: &[f64], m: usize, r: f64) -> f64 {
-    let n = signal.len();
-    if n <= m {
-        return 0.0;
-    }
-    
-    let phi_m = calculate_phi(signal, m, r);
-    let phi_m1 = calculate_phi(signal, m + 1, r);
-    
-    phi_m - phi_m1
-}
-
-/// Helper function to calculate phi for approximate entropy
-fn calculate_phi(signal: &[f64], m: usize, r: f64) -> f64 {
-    let n = signal.len();
-    let mut sum = 0.0;
-    
-    for i in 0..=(n - m) {
-        let mut matches = 0;
-        
-        for j in 0..=(n - m) {
-            let max_diff = calculate_max_distance(&signal[i..i+m], &signal[j..j+m]);
-            
-            if max_diff <= r {
-                matches += 1;
-            }
-        }
-        
-        let ratio = matches as f64 / (n - m + 1) as f64;
-        if ratio > 1e-12 {
-            sum += ratio.ln();
-        }
-    }
-    
-    sum / (n - m + 1) as f64
-}
-
-/// Calculate maximum distance between two pattern vectors
-fn calculate_max_distance(pattern1: &[f64], pattern2: &[f64]) -> f64 {
-    pattern1.iter()
-        .zip(pattern2.iter())
-        .map(|(&a, &b)| (a - b).abs())
-        .fold(0.0, |acc, diff| acc.max(diff))
-}
-
 /// Create Thornfiddle summary CSV with weighted chain metrics
 pub fn create_thornfiddle_summary<P: AsRef<Path>>(
     output_dir: P,
@@ -781,10 +721,9 @@ pub fn calculate_thornfiddle_path(feature: &MarginalPointFeatures) -> f64 {
 pub fn calculate_thornfiddle_path_harmonic(
     features: &[MarginalPointFeatures],
     leaf_circumference: f64,
-    thornfiddle_image: &RgbaImage,
+    mask: &LeafMask,
     reference_point: (u32, u32),
     contour_points: &[(u32, u32)],
-    golden_color: [u8; 3],
     pixel_threshold: u32,
     min_chain_length: usize,
     harmonic_strength_multiplier: f64,
@@ -806,10 +745,9 @@ pub fn calculate_thornfiddle_path_harmonic(
     // Step 1: Detect golden chains based on pixel crossings
     let golden_chains = detect_golden_chains(
         features,
-        thornfiddle_image,
+        mask,
         reference_point,
         contour_points,
-        golden_color,
         pixel_threshold,
     );
     
@@ -880,49 +818,44 @@ fn apply_principled_harmonic_enhancement(
     // Base frequency normalized by leaf circumference
     let base_frequency = 2.0 * PI / leaf_circumference;
     
+    // Chains may wrap around the start of the closed contour
+    let n = harmonic_values.len().min(features.len());
+    if n == 0 {
+        return;
+    }
+    let chain_index = |offset: usize| (chain.start_index + offset) % n;
+    
     // CORRECTED: Find the deepest point (point with longest geodesic path) in the segment
-    let mut deepest_point_idx = chain.start_index;
+    let mut deepest_offset = 0;
     let mut max_path_length = 0.0;
     
-    for i in chain.start_index..=chain.end_index {
-        if i < features.len() {
-            let path_length = features[i].diego_path_length;
-            if path_length > max_path_length {
-                max_path_length = path_length;
-                deepest_point_idx = i;
-            }
+    for offset in 0..chain.length {
+        let path_length = features[chain_index(offset)].diego_path_length;
+        if path_length > max_path_length {
+            max_path_length = path_length;
+            deepest_offset = offset;
         }
     }
     
-    println!("Chain enhancement: length={}, circumference_ratio={:.4}, harmonics={}, deepest_point_idx={}", 
-             segment_length, circumference_ratio, num_harmonics, deepest_point_idx);
+    println!("Chain enhancement: indices {}-{}, length={}, circumference_ratio={:.4}, harmonics={}, deepest_point_idx={}", 
+             chain.start_index, chain.end_index, segment_length, circumference_ratio, num_harmonics,
+             chain_index(deepest_offset));
     
     // Apply harmonic enhancement to each point in the chain
-    for i in chain.start_index..=chain.end_index {
-        if i >= harmonic_values.len() {
-            break;
-        }
+    for offset in 0..chain.length {
+        let i = chain_index(offset);
         
         // CORRECTED: Enhancement Intensity based on distance from deepest point
-        let distance_from_deepest = if deepest_point_idx >= chain.start_index && deepest_point_idx <= chain.end_index {
-            // Calculate relative distance from the deepest point within the segment
-            let dist_to_deepest = (i as i32 - deepest_point_idx as i32).abs() as f64;
-            let max_distance_in_segment = ((chain.end_index - chain.start_index) as f64 / 2.0).max(1.0);
-            
-            // W_pos: 1.0 at deepest point, decreasing linearly to 0.0 at segment ends
-            1.0 - (dist_to_deepest / max_distance_in_segment).min(1.0)
-        } else {
-            // Fallback to linear if deepest point detection fails
-            let position_in_chain = i - chain.start_index;
-            let chain_length = chain.end_index - chain.start_index + 1;
-            position_in_chain as f64 / chain_length as f64
-        };
+        // W_pos: 1.0 at deepest point, decreasing linearly to 0.0 at segment ends
+        let dist_to_deepest = (offset as f64 - deepest_offset as f64).abs();
+        let max_distance_in_segment = ((chain.length - 1) as f64 / 2.0).max(1.0);
+        let distance_from_deepest = 1.0 - (dist_to_deepest / max_distance_in_segment).min(1.0);
         
         // Calculate harmonic sum: Σ (1/k) * sin(2π * k * f_base * L_pos)
         let mut harmonic_sum = 0.0;
         for k in 1..=num_harmonics {
             let amplitude = 1.0 / k as f64; // Standard harmonic series decay
-            let phase = base_frequency * k as f64 * (i - chain.start_index) as f64;
+            let phase = base_frequency * k as f64 * offset as f64;
             harmonic_sum += amplitude * phase.sin();
         }
         
@@ -933,28 +866,24 @@ fn apply_principled_harmonic_enhancement(
         let base_value = harmonic_values[i];
         harmonic_values[i] = base_value + (base_value * enhancement);
         
-        if i == deepest_point_idx {
+        if offset == deepest_offset {
             println!("  Deepest point enhancement: W_pos={:.3}, enhancement={:.3}", 
                      distance_from_deepest, enhancement);
         }
     }
 }
 
-/// Count golden pixels crossed by a path
+/// Count golden (lobe) pixels crossed by a path
 fn count_golden_pixels_crossed(
     path: &[(u32, u32)],
-    thornfiddle_image: &RgbaImage,
-    golden_color: [u8; 3],
+    mask: &LeafMask,
 ) -> u32 {
     let mut golden_count = 0;
-    let (width, height) = thornfiddle_image.dimensions();
+    let (width, height) = mask.dimensions();
     
     for &(x, y) in path {
-        if x < width && y < height {
-            let pixel = thornfiddle_image.get_pixel(x, y);
-            if has_rgb_color(pixel, golden_color) {
-                golden_count += 1;
-            }
+        if x < width && y < height && mask.is_lobe(x, y) {
+            golden_count += 1;
         }
     }
     
@@ -962,74 +891,66 @@ fn count_golden_pixels_crossed(
 }
 
 /// Detect chains of consecutive golden pixel crossings
+///
+/// The contour is closed, so a chain running through the last point continues
+/// at index 0; such a chain has `end_index < start_index`.
 fn detect_golden_chains(
     features: &[MarginalPointFeatures],
-    thornfiddle_image: &RgbaImage,
+    mask: &LeafMask,
     reference_point: (u32, u32),
     contour_points: &[(u32, u32)],
-    golden_color: [u8; 3],
     pixel_threshold: u32,
 ) -> Vec<GoldenChain> {
+    let n = features.len().min(contour_points.len());
+    
+    // For MC we always trace the straight line to evaluate golden crossings
+    let golden_counts: Vec<u32> = contour_points[..n].iter()
+        .map(|&marginal_point| {
+            let path_to_check = trace_straight_line(reference_point, marginal_point);
+            count_golden_pixels_crossed(&path_to_check, mask)
+        })
+        .collect();
+    
+    let crosses_threshold = |i: usize| golden_counts[i] >= pixel_threshold;
+    
+    // Start scanning just after a point below threshold so no chain is split at index 0
+    let scan_start = match (0..n).find(|&i| !crosses_threshold(i)) {
+        Some(below) => below + 1,
+        None => 0,
+    };
+    
     let mut chains = Vec::new();
     let mut current_chain_start: Option<usize> = None;
     let mut chain_golden_counts = Vec::new();
     
-    for (i, _) in features.iter().enumerate() {
-        if i >= contour_points.len() {
-            break;
-        }
-        
-        let marginal_point = contour_points[i];
-        
-        // For MC we always trace the straight line to evaluate golden crossings
-        let path_to_check = trace_straight_line(reference_point, marginal_point);
+    for offset in 0..=n {
+        let i = (scan_start + offset) % n.max(1);
         
-        let golden_count = count_golden_pixels_crossed(&path_to_check, thornfiddle_image, golden_color);
-        let crosses_threshold = golden_count >= pixel_threshold;
-        
-        if crosses_threshold {
+        if offset < n && crosses_threshold(i) {
             if current_chain_start.is_none() {
                 current_chain_start = Some(i);
                 chain_golden_counts.clear();
             }
-            chain_golden_counts.push(golden_count);
+            chain_golden_counts.push(golden_counts[i]);
         } else if let Some(start) = current_chain_start {
-            if !chain_golden_counts.is_empty() {
-                let total_golden_pixels: u32 = chain_golden_counts.iter().sum();
-                let max_crossing_count = *chain_golden_counts.iter().max().unwrap_or(&0);
-                
-                chains.push(GoldenChain {
-                    start_index: start,
-                    end_index: i - 1,
-                    length: i - start,
-                    total_golden_pixels,
-                    max_crossing_count,
-                });
-                
-                println!("Golden chain detected: indices {}-{}, length {}, total golden pixels {}", 
-                         start, i - 1, i - start, total_golden_pixels);
-            }
-            current_chain_start = None;
-            chain_golden_counts.clear();
-        }
-    }
-    
-    // Handle chain that extends to end of contour
-    if let Some(start) = current_chain_start {
-        if !chain_golden_counts.is_empty() {
+            let length = chain_golden_counts.len();
+            let end = (start + length - 1) % n;
             let total_golden_pixels: u32 = chain_golden_counts.iter().sum();
             let max_crossing_count = *chain_golden_counts.iter().max().unwrap_or(&0);
             
             chains.push(GoldenChain {
                 start_index: start,
-                end_index: features.len() - 1,
-                length: features.len() - start,
+                end_index: end,
+                length,
                 total_golden_pixels,
                 max_crossing_count,
             });
             
-            println!("Golden chain detected (end): indices {}-{}, length {}, total golden pixels {}", 
-                     start, features.len() - 1, features.len() - start, total_golden_pixels);
+            println!("Golden chain detected: indices {}-{}, length {}, total golden pixels {}", 
+                     start, end, length, total_golden_pixels);
+            
+            current_chain_start = None;
+            chain_golden_counts.clear();
         }
     }
     
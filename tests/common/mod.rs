@@ -0,0 +1,71 @@
+// tests/common/mod.rs - Deterministic fixtures shared by the integration tests and benches
+//
+// Every test crate and bench includes this module but uses only part of it.
+#![allow(dead_code)]
+
+use leaf_complex_rust_lib::BinaryMask;
+
+/// Deterministic xorshift64 stream (seed must be non-zero)
+pub fn xorshift(seed: u64) -> impl Iterator<Item = u64> {
+    let mut state = seed;
+    std::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    })
+}
+
+/// Deterministic xorshift noise in [0, 1)
+pub fn noise(length: usize, seed: u64) -> Vec<f64> {
+    xorshift(seed)
+        .take(length)
+        .map(|value| (value % 1_000_000) as f64 / 1_000_000.0)
+        .collect()
+}
+
+/// EC-like signal: mostly zero with integer bursts where teeth are opened
+pub fn pink_like(length: usize, seed: u64) -> Vec<f64> {
+    noise(length, seed)
+        .iter()
+        .enumerate()
+        .map(|(i, &u)| {
+            let tooth = ((i as f64 / 37.0).sin() * 12.0).max(0.0);
+            if tooth > 3.0 { (tooth + u * 3.0).floor() } else { 0.0 }
+        })
+        .collect()
+}
+
+/// Smooth margin-like signal with noise
+pub fn smooth(length: usize, seed: u64) -> Vec<f64> {
+    noise(length, seed)
+        .iter()
+        .enumerate()
+        .map(|(i, &u)| (i as f64 / 150.0).sin() * 20.0 + (i as f64 / 11.0).cos() * 2.0 + u)
+        .collect()
+}
+
+/// Population standard deviation
+pub fn standard_deviation(signal: &[f64]) -> f64 {
+    let mean = signal.iter().sum::<f64>() / signal.len() as f64;
+    (signal.iter().map(|&x| (x - mean).powi(2)).sum::<f64>() / signal.len() as f64).sqrt()
+}
+
+/// Deterministic blobby mask: xorshift noise thresholded at `density`
+pub fn noise_mask(width: u32, height: u32, density: f64, seed: u64) -> BinaryMask {
+    let values = noise((width * height) as usize, seed).into_iter().map(|u| u < density).collect();
+    BinaryMask::from_vec(width, height, values)
+}
+
+/// Deterministic distinct integer points in [0, extent)², so collinear and tied points occur
+pub fn noise_points(count: usize, extent: u64, seed: u64) -> Vec<(f64, f64)> {
+    let mut values = xorshift(seed).map(|value| (value % extent) as f64);
+    let mut points: Vec<(f64, f64)> = Vec::with_capacity(count);
+    while points.len() < count {
+        let point = (values.next().unwrap(), values.next().unwrap());
+        if !points.contains(&point) {
+            points.push(point);
+        }
+    }
+    points
+}
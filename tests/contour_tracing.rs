@@ -0,0 +1,254 @@
+// tests/contour_tracing.rs - Regression corpus of synthetic masks for Moore contour tracing
+
+use leaf_complex_rust_lib::{
+    polygon_signed_area, trace_contour, trace_contour_from, trace_inner_contour, BinaryMask, ContourStart,
+};
+
+/// Build a mask from ASCII rows ('#' = region)
+fn mask(rows: &[&str]) -> BinaryMask {
+    let height = rows.len() as u32;
+    let width = rows[0].len() as u32;
+    BinaryMask::from_fn(width, height, |x, y| rows[y as usize].as_bytes()[x as usize] == b'#')
+}
+
+/// Consecutive contour pixels (including the closing pair) are 8-neighbours
+fn assert_closed(contour: &[(u32, u32)]) {
+    if contour.len() < 2 {
+        return;
+    }
+    for i in 0..contour.len() {
+        let a = contour[i];
+        let b = contour[(i + 1) % contour.len()];
+        let step = (a.0 as i32 - b.0 as i32).abs().max((a.1 as i32 - b.1 as i32).abs());
+        assert_eq!(step, 1, "gap between {:?} and {:?} in {:?}", a, b, contour);
+    }
+}
+
+/// Every region pixel on the image border appears in the contour
+fn assert_covers_outer_boundary(region: &BinaryMask, contour: &[(u32, u32)]) {
+    let (width, height) = region.dimensions();
+    for y in 0..height {
+        for x in 0..width {
+            if !region.get(x, y) {
+                continue;
+            }
+            let on_border = [(1, 0), (-1, 0), (0, 1), (0, -1)].iter().any(|&(dx, dy)| {
+                let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32
+            });
+            if on_border {
+                assert!(contour.contains(&(x, y)), "boundary pixel {:?} missing", (x, y));
+            }
+        }
+    }
+}
+
+fn signed_area(contour: &[(u32, u32)]) -> f64 {
+    let polygon: Vec<(f64, f64)> = contour.iter().map(|&(x, y)| (x as f64, y as f64)).collect();
+    polygon_signed_area(&polygon)
+}
+
+fn occurrences(contour: &[(u32, u32)], pixel: (u32, u32)) -> usize {
+    contour.iter().filter(|&&p| p == pixel).count()
+}
+
+#[test]
+fn empty_mask_has_no_contour() {
+    assert!(trace_contour(&mask(&["....", "...."])).is_empty());
+}
+
+#[test]
+fn single_pixel_is_its_own_contour() {
+    assert_eq!(trace_contour(&mask(&["...", ".#.", "..."])), vec![(1, 1)]);
+}
+
+#[test]
+fn square_is_traced_counter_clockwise_from_leftmost_pixel() {
+    let region = mask(&[
+        ".....",
+        ".###.",
+        ".###.",
+        ".###.",
+        ".....",
+    ]);
+    let contour = trace_contour(&region);
+    
+    assert_eq!(contour.len(), 8);
+    assert_eq!(contour[0], (1, 1));
+    // Counter-clockwise as displayed: down the left side first
+    assert_eq!(contour[1], (1, 2));
+    assert!(signed_area(&contour) < 0.0);
+    assert_closed(&contour);
+}
+
+#[test]
+fn square_touching_image_border() {
+    let region = mask(&["###", "###", "###"]);
+    let contour = trace_contour(&region);
+    
+    assert_eq!(contour.len(), 8);
+    assert_closed(&contour);
+    assert_covers_outer_boundary(&region, &contour);
+}
+
+#[test]
+fn one_pixel_line_is_walked_both_ways() {
+    let contour = trace_contour(&mask(&[".......", ".#####.", "......."]));
+    
+    assert_eq!(contour.len(), 8);
+    assert_closed(&contour);
+    for x in 2..5 {
+        assert_eq!(occurrences(&contour, (x, 1)), 2);
+    }
+    assert_eq!(occurrences(&contour, (1, 1)), 1);
+    assert_eq!(occurrences(&contour, (5, 1)), 1);
+}
+
+#[test]
+fn diagonal_chain_is_walked_both_ways() {
+    let contour = trace_contour(&mask(&[
+        "#...",
+        ".#..",
+        "..#.",
+        "...#",
+    ]));
+    
+    assert_eq!(contour.len(), 6);
+    assert_closed(&contour);
+    assert_eq!(occurrences(&contour, (1, 1)), 2);
+    assert_eq!(occurrences(&contour, (2, 2)), 2);
+}
+
+#[test]
+fn one_pixel_bridge_does_not_stop_tracing() {
+    let region = mask(&[
+        "...........",
+        ".###...###.",
+        ".#######.#.",
+        ".###...###.",
+        "...........",
+    ]);
+    let contour = trace_contour(&region);
+    
+    assert_closed(&contour);
+    // Both blocks are traced in full
+    for pixel in [(1, 1), (1, 3), (3, 1), (3, 3), (7, 1), (9, 1), (9, 3), (7, 3)] {
+        assert!(contour.contains(&pixel), "missing {:?}", pixel);
+    }
+    // Bridge pixels are visited on the way out and on the way back
+    for x in 4..7 {
+        assert_eq!(occurrences(&contour, (x, 2)), 2);
+    }
+    assert!(signed_area(&contour) < 0.0);
+}
+
+#[test]
+fn stem_at_start_pixel_does_not_end_tracing_early() {
+    // The leftmost pixel is the tip of a one-pixel stem; the old visited-set
+    // tracer returned to it and stopped before tracing the blade
+    let region = mask(&[
+        "........",
+        "....###.",
+        ".######.",
+        "....###.",
+        "........",
+    ]);
+    let contour = trace_contour(&region);
+    
+    assert_eq!(contour[0], (1, 2));
+    assert_closed(&contour);
+    for pixel in [(4, 1), (6, 1), (6, 2), (6, 3), (4, 3)] {
+        assert!(contour.contains(&pixel), "missing {:?}", pixel);
+    }
+    assert_eq!(occurrences(&contour, (2, 2)), 2);
+}
+
+#[test]
+fn ring_contour_excludes_hole_boundary() {
+    let region = mask(&[
+        ".......",
+        ".#####.",
+        ".#####.",
+        ".##.##.",
+        ".#####.",
+        ".#####.",
+        ".......",
+    ]);
+    let contour = trace_contour(&region);
+    
+    assert_eq!(contour.len(), 16);
+    assert_closed(&contour);
+    assert!(!contour.contains(&(3, 2)));
+    assert!(signed_area(&contour) < 0.0);
+    
+    // 8-connected tracing cuts the corners around a 4-connected hole
+    let mut inner = trace_inner_contour(&region, (3, 3));
+    assert_closed(&inner);
+    inner.sort_unstable();
+    assert_eq!(inner, vec![(2, 3), (3, 2), (3, 4), (4, 3)]);
+}
+
+#[test]
+fn concave_notch_is_followed() {
+    let region = mask(&[
+        ".......",
+        ".#...#.",
+        ".#...#.",
+        ".#####.",
+        ".......",
+    ]);
+    let contour = trace_contour(&region);
+    
+    // The one-pixel bottom row is walked out along the bottom and back along the notch
+    assert_closed(&contour);
+    assert_eq!(contour.len(), 14);
+    assert_eq!(occurrences(&contour, (1, 2)), 2);
+    assert_eq!(occurrences(&contour, (5, 2)), 2);
+    assert_eq!(occurrences(&contour, (3, 3)), 2);
+}
+
+#[test]
+fn start_policies_rotate_the_same_contour() {
+    let region = mask(&[
+        ".......",
+        "...#...",
+        "..###..",
+        ".#####.",
+        "..###..",
+        "...#...",
+        "...#...",
+        ".......",
+    ]);
+    let leftmost = trace_contour_from(&region, ContourStart::Leftmost);
+    let petiole = trace_contour_from(&region, ContourStart::PetioleBase);
+    let apex = trace_contour_from(&region, ContourStart::Apex);
+    let nearest = trace_contour_from(&region, ContourStart::NearestTo(6, 3));
+    
+    assert_eq!(leftmost[0], (1, 3));
+    assert_eq!(petiole[0], (3, 6));
+    assert_eq!(apex[0], (3, 1));
+    assert_eq!(nearest[0], (5, 3));
+    
+    // Same cyclic sequence regardless of the start
+    for contour in [&petiole, &apex, &nearest] {
+        assert_eq!(contour.len(), leftmost.len());
+        let offset = leftmost.iter().position(|&p| p == contour[0]).unwrap();
+        let mut rotated = leftmost.clone();
+        rotated.rotate_left(offset);
+        assert_eq!(&rotated, contour);
+    }
+}
+
+#[test]
+fn only_leftmost_component_is_traced() {
+    let region = mask(&[
+        "........",
+        ".##..##.",
+        ".##..##.",
+        "........",
+    ]);
+    let contour = trace_contour(&region);
+    
+    assert_eq!(contour.len(), 4);
+    assert!(contour.iter().all(|&(x, _)| x <= 2));
+}
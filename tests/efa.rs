@@ -0,0 +1,87 @@
+// tests/efa.rs - Elliptic Fourier normalisation for size, rotation, translation and start point
+
+use std::f64::consts::PI;
+
+use leaf_complex_rust_lib::efa::{elliptic_fourier, normalize_elliptic_fourier, EllipticFourier};
+
+const SAMPLES: usize = 360;
+const HARMONICS: usize = 8;
+const TOLERANCE: f64 = 1e-9;
+
+/// Ellipse sampled at `SAMPLES` equal parameter steps, starting `start` steps in
+fn ellipse(
+    center: (f64, f64),
+    semi_major: f64,
+    semi_minor: f64,
+    rotation_degrees: f64,
+    start: usize,
+) -> Vec<(f64, f64)> {
+    let (sin, cos) = rotation_degrees.to_radians().sin_cos();
+    (0..SAMPLES)
+        .map(|i| {
+            let t = 2.0 * PI * ((i + start) % SAMPLES) as f64 / SAMPLES as f64;
+            let (x, y) = (semi_major * t.cos(), semi_minor * t.sin());
+            (center.0 + x * cos - y * sin, center.1 + x * sin + y * cos)
+        })
+        .collect()
+}
+
+fn normalized(outline: &[(f64, f64)]) -> EllipticFourier {
+    normalize_elliptic_fourier(&elliptic_fourier(outline, HARMONICS))
+}
+
+fn assert_canonical_first_harmonic(efa: &EllipticFourier, context: &str) {
+    let [a1, b1, c1, _] = efa.coefficients[0];
+    assert!(efa.normalized, "{} not flagged as normalised", context);
+    assert!((a1 - 1.0).abs() < TOLERANCE, "{}: A1 = {}", context, a1);
+    assert!(b1.abs() < TOLERANCE, "{}: B1 = {}", context, b1);
+    assert!(c1.abs() < TOLERANCE, "{}: C1 = {}", context, c1);
+}
+
+fn assert_same_coefficients(actual: &EllipticFourier, expected: &EllipticFourier, context: &str) {
+    assert_eq!(actual.coefficients.len(), expected.coefficients.len(), "{}: harmonic count", context);
+    for (harmonic, (a, e)) in actual.coefficients.iter().zip(&expected.coefficients).enumerate() {
+        for k in 0..4 {
+            assert!(
+                (a[k] - e[k]).abs() < TOLERANCE,
+                "{}: harmonic {} coefficient {} is {}, expected {}", context, harmonic + 1, k, a[k], e[k],
+            );
+        }
+    }
+}
+
+#[test]
+fn circle_normalises_to_unit_first_harmonic() {
+    for (radius, start) in [(1.0, 0), (25.0, 90), (140.0, 217)] {
+        let efa = normalized(&ellipse((300.0, 200.0), radius, radius, 0.0, start));
+        let context = format!("circle of radius {} starting at {}", radius, start);
+
+        assert_canonical_first_harmonic(&efa, &context);
+        assert!((efa.coefficients[0][3].abs() - 1.0).abs() < TOLERANCE, "{}: D1 = {}", context, efa.coefficients[0][3]);
+        assert!(efa.coefficients[0].iter().all(|v| v.is_finite()), "{}: non-finite coefficients", context);
+    }
+}
+
+#[test]
+fn ellipse_normalisation_ignores_pose_and_start_point() {
+    let reference = normalized(&ellipse((0.0, 0.0), 40.0, 15.0, 0.0, 0));
+    assert_canonical_first_harmonic(&reference, "reference ellipse");
+    // Arc-length parametrisation adds higher harmonics, so D1 is not exactly 15/40
+    let d1 = reference.coefficients[0][3].abs();
+    assert!(d1 > 15.0 / 40.0 && d1 < 1.0, "D1 = {}", reference.coefficients[0][3]);
+
+    for (center, scale, rotation, start) in [
+        ((120.0, 80.0), 1.0, 30.0, 0),
+        ((-50.0, 310.0), 2.5, 0.0, 45),
+        ((17.0, -4.0), 0.5, 117.0, 200),
+        ((640.0, 480.0), 3.0, 251.0, 333),
+    ] {
+        let outline = ellipse(center, 40.0 * scale, 15.0 * scale, rotation, start);
+        let efa = normalized(&outline);
+        let context = format!("ellipse at {:?} scaled {} rotated {}° starting at {}", center, scale, rotation, start);
+
+        assert_canonical_first_harmonic(&efa, &context);
+        assert_same_coefficients(&efa, &reference, &context);
+        assert!((efa.size / reference.size - scale).abs() < TOLERANCE, "{}: size {}", context, efa.size);
+    }
+}
@@ -0,0 +1,128 @@
+// tests/entropy.rs - Range-counted approximate and sample entropy against the pairwise reference
+//
+// The k-d tree estimators must be bit-identical to the O(n²·m) pairwise
+// implementation they replace.
+
+mod common;
+
+use common::{noise, pink_like, smooth, standard_deviation};
+use leaf_complex_rust_lib::{calculate_approximate_entropy, calculate_sample_entropy};
+
+/// Pairwise approximate entropy (the original implementation)
+fn reference_approximate_entropy(signal: &[f64], m: usize, r: f64) -> f64 {
+    let n = signal.len();
+    if n <= m {
+        return 0.0;
+    }
+
+    reference_phi(signal, m, r) - reference_phi(signal, m + 1, r)
+}
+
+fn reference_phi(signal: &[f64], m: usize, r: f64) -> f64 {
+    let n = signal.len();
+    let mut sum = 0.0;
+
+    for i in 0..=(n - m) {
+        let mut matches = 0;
+
+        for j in 0..=(n - m) {
+            let max_diff = signal[i..i + m].iter()
+                .zip(signal[j..j + m].iter())
+                .map(|(&a, &b)| (a - b).abs())
+                .fold(0.0_f64, |acc, diff| acc.max(diff));
+
+            if max_diff <= r {
+                matches += 1;
+            }
+        }
+
+        let ratio = matches as f64 / (n - m + 1) as f64;
+        if ratio > 1e-12 {
+            sum += ratio.ln();
+        }
+    }
+
+    sum / (n - m + 1) as f64
+}
+
+/// Pairwise sample entropy (the original implementation)
+fn reference_sample_entropy(signal: &[f64], m: usize, r: f64) -> f64 {
+    let n = signal.len();
+    if m == 0 || n <= m {
+        return 0.0;
+    }
+
+    let templates = n - m;
+    let (mut matches_m, mut matches_m1) = (0u64, 0u64);
+
+    for i in 0..templates {
+        for j in (i + 1)..templates {
+            if (0..m).all(|k| (signal[i + k] - signal[j + k]).abs() <= r) {
+                matches_m += 1;
+                if (signal[i + m] - signal[j + m]).abs() <= r {
+                    matches_m1 += 1;
+                }
+            }
+        }
+    }
+
+    if matches_m == 0 {
+        return 0.0;
+    }
+    if matches_m1 == 0 {
+        let pairs = (templates as f64) * (templates as f64 - 1.0) / 2.0;
+        return if pairs > 0.0 { pairs.ln() } else { 0.0 };
+    }
+    (matches_m as f64 / matches_m1 as f64).ln()
+}
+
+fn assert_identical(signal: &[f64], m: usize, r: f64, context: &str) {
+    assert_eq!(
+        calculate_approximate_entropy(signal, m, r).to_bits(),
+        reference_approximate_entropy(signal, m, r).to_bits(),
+        "ApEn differs on {} (m={}, r={})", context, m, r,
+    );
+    assert_eq!(
+        calculate_sample_entropy(signal, m, r).to_bits(),
+        reference_sample_entropy(signal, m, r).to_bits(),
+        "SampEn differs on {} (m={}, r={})", context, m, r,
+    );
+}
+
+#[test]
+fn long_signals_match_pairwise_reference() {
+    for (name, signal) in [
+        ("pink", pink_like(1500, 7)),
+        ("smooth", smooth(1500, 11)),
+        ("noise", noise(1500, 13)),
+    ] {
+        let r = 0.2 * standard_deviation(&signal);
+        for m in 1..=3 {
+            assert_identical(&signal, m, r, name);
+        }
+    }
+}
+
+#[test]
+fn short_and_degenerate_signals_match_pairwise_reference() {
+    for seed in 1..200u64 {
+        let length = (seed % 40) as usize;
+        // Few distinct levels, so templates repeat and sit exactly on the tolerance
+        let sign = if seed % 3 == 0 { -0.0 } else { 0.5 };
+        let signal: Vec<f64> = noise(length, seed).iter().map(|u| (u * 4.0).floor() * sign).collect();
+
+        for m in 0..4 {
+            for r in [0.0, 0.25, 0.5, 1.0, -1.0] {
+                assert_identical(&signal, m, r, &format!("seed {}", seed));
+            }
+        }
+    }
+}
+
+#[test]
+fn constant_signal_matches_pairwise_reference() {
+    let signal = vec![3.0; 300];
+    for m in 1..=3 {
+        assert_identical(&signal, m, 0.2, "constant");
+    }
+}
@@ -0,0 +1,105 @@
+// tests/morphology.rs - Distance-transform erosion/dilation against a brute-force circular kernel scan
+
+mod common;
+
+use common::noise_mask;
+use leaf_complex_rust_lib::image_utils::create_circular_kernel;
+use leaf_complex_rust_lib::{dilate_mask, erode_mask, BinaryMask};
+
+/// Kernel offsets relative to the anchor pixel (kernel centre rounded down-right)
+fn kernel_offsets(diameter: u32) -> Vec<(i32, i32)> {
+    let kernel = create_circular_kernel(diameter);
+    let (k_width, k_height) = kernel.dimensions();
+    let (k_radius_x, k_radius_y) = ((k_width / 2) as i32, (k_height / 2) as i32);
+
+    let mut offsets = Vec::new();
+    for ky in 0..k_height {
+        for kx in 0..k_width {
+            if kernel.get_pixel(kx, ky)[0] > 0 {
+                offsets.push((kx as i32 - k_radius_x, ky as i32 - k_radius_y));
+            }
+        }
+    }
+    offsets
+}
+
+/// Value under every kernel offset of (x, y); out-of-bounds pixels are `outside`
+fn under_kernel<'a>(
+    mask: &'a BinaryMask,
+    offsets: &'a [(i32, i32)],
+    x: u32,
+    y: u32,
+    outside: bool,
+) -> impl Iterator<Item = bool> + 'a {
+    let (width, height) = mask.dimensions();
+    offsets.iter().map(move |&(dx, dy)| {
+        let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+        if nx < 0 || ny < 0 || nx >= width as i32 || ny >= height as i32 {
+            outside
+        } else {
+            mask.get(nx as u32, ny as u32)
+        }
+    })
+}
+
+/// Erosion by scanning the kernel; out-of-bounds counts as background
+fn brute_force_erode(mask: &BinaryMask, diameter: u32) -> BinaryMask {
+    let offsets = kernel_offsets(diameter);
+    let (width, height) = mask.dimensions();
+    BinaryMask::from_fn(width, height, |x, y| under_kernel(mask, &offsets, x, y, false).all(|set| set))
+}
+
+/// Dilation by scanning the kernel; out-of-bounds pixels are ignored
+fn brute_force_dilate(mask: &BinaryMask, diameter: u32) -> BinaryMask {
+    let offsets = kernel_offsets(diameter);
+    let (width, height) = mask.dimensions();
+    BinaryMask::from_fn(width, height, |x, y| under_kernel(mask, &offsets, x, y, false).any(|set| set))
+}
+
+/// Filled disc that runs over the left and top image border
+fn border_disc(width: u32, height: u32) -> BinaryMask {
+    BinaryMask::from_fn(width, height, |x, y| {
+        let (dx, dy) = (x as f64 - 3.0, y as f64 - 4.0);
+        dx * dx + dy * dy <= 64.0
+    })
+}
+
+fn assert_same(actual: &BinaryMask, expected: &BinaryMask, context: &str) {
+    let (width, height) = expected.dimensions();
+    for y in 0..height {
+        for x in 0..width {
+            assert_eq!(actual.get(x, y), expected.get(x, y), "{} differs at ({}, {})", context, x, y);
+        }
+    }
+}
+
+fn masks() -> Vec<(&'static str, BinaryMask)> {
+    vec![
+        ("sparse noise", noise_mask(23, 19, 0.3, 3)),
+        ("dense noise", noise_mask(23, 19, 0.85, 5)),
+        ("full frame", BinaryMask::from_fn(17, 12, |_, _| true)),
+        ("single pixel", BinaryMask::from_fn(9, 9, |x, y| (x, y) == (4, 4))),
+        ("corner pixel", BinaryMask::from_fn(9, 9, |x, y| (x, y) == (0, 0))),
+        ("border disc", border_disc(21, 18)),
+    ]
+}
+
+#[test]
+fn erosion_matches_kernel_scan() {
+    for (name, mask) in masks() {
+        for diameter in 1..=10 {
+            let context = format!("erosion of {} with diameter {}", name, diameter);
+            assert_same(&erode_mask(&mask, diameter), &brute_force_erode(&mask, diameter), &context);
+        }
+    }
+}
+
+#[test]
+fn dilation_matches_kernel_scan() {
+    for (name, mask) in masks() {
+        for diameter in 1..=10 {
+            let context = format!("dilation of {} with diameter {}", name, diameter);
+            assert_same(&dilate_mask(&mask, diameter), &brute_force_dilate(&mask, diameter), &context);
+        }
+    }
+}
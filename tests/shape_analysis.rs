@@ -0,0 +1,197 @@
+// tests/shape_analysis.rs - Convex hull and rotating-caliper Feret diameters against brute-force scans
+
+mod common;
+
+use common::noise_points;
+use leaf_complex_rust_lib::{
+    calculate_convex_hull_metrics, calculate_feret_diameters, calculate_shape_indices, convex_hull_indices,
+};
+
+const TOLERANCE: f64 = 1e-9;
+
+/// Outline of a `length` x `width` rectangle, `steps` points per side, long side along `angle_degrees`
+///
+/// Angles follow the library convention: counter-clockwise as displayed, y pointing down.
+fn rectangle(length: f64, width: f64, angle_degrees: f64, steps: usize) -> Vec<(f64, f64)> {
+    let (sin, cos) = angle_degrees.to_radians().sin_cos();
+    let (u, n) = ((cos, -sin), (sin, cos));
+    let corners = [(0.0, 0.0), (length, 0.0), (length, width), (0.0, width)];
+
+    (0..4)
+        .flat_map(|side| {
+            let (a, b) = (corners[side], corners[(side + 1) % 4]);
+            (0..steps).map(move |i| {
+                let t = i as f64 / steps as f64;
+                (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+            })
+        })
+        .map(|(s, t)| (50.0 + u.0 * s + n.0 * t, 70.0 + u.1 * s + n.1 * t))
+        .collect()
+}
+
+fn cross(o: (f64, f64), a: (f64, f64), b: (f64, f64)) -> f64 {
+    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+}
+
+/// True when `p` lies on the closed segment between `a` and `b`
+fn on_segment(p: (f64, f64), a: (f64, f64), b: (f64, f64)) -> bool {
+    cross(a, b, p) == 0.0
+        && p.0 >= a.0.min(b.0) && p.0 <= a.0.max(b.0)
+        && p.1 >= a.1.min(b.1) && p.1 <= a.1.max(b.1)
+}
+
+/// Hull vertices by definition: points outside every closed triangle and segment of the others
+fn brute_force_hull_vertices(points: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let n = points.len();
+    let covered = |p: usize| {
+        let others: Vec<usize> = (0..n).filter(|&i| i != p).collect();
+        others.iter().enumerate().any(|(ia, &a)| {
+            others[ia + 1..].iter().enumerate().any(|(ib, &b)| {
+                on_segment(points[p], points[a], points[b])
+                    || others[ia + ib + 2..].iter().any(|&c| {
+                        let signs = [
+                            cross(points[a], points[b], points[p]),
+                            cross(points[b], points[c], points[p]),
+                            cross(points[c], points[a], points[p]),
+                        ];
+                        cross(points[a], points[b], points[c]) != 0.0
+                            && (signs.iter().all(|&s| s >= 0.0) || signs.iter().all(|&s| s <= 0.0))
+                    })
+            })
+        })
+    };
+
+    let mut vertices: Vec<(f64, f64)> = (0..n).filter(|&p| !covered(p)).map(|p| points[p]).collect();
+    vertices.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+    vertices
+}
+
+/// Extent of the points along a unit direction
+fn extent(points: &[(f64, f64)], direction: (f64, f64)) -> f64 {
+    let projections = points.iter().map(|p| p.0 * direction.0 + p.1 * direction.1);
+    let (low, high) = projections.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| (lo.min(v), hi.max(v)));
+    high - low
+}
+
+/// Maximum pairwise distance, minimum width and minimum rectangle area over all point-pair directions
+///
+/// Every hull edge joins two of the points, so the optimal caliper directions are among the pairs.
+fn brute_force_feret(points: &[(f64, f64)]) -> (f64, f64, f64) {
+    let (mut max_feret, mut min_feret, mut min_area) = (0.0_f64, f64::INFINITY, f64::INFINITY);
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            let length = (b.0 - a.0).hypot(b.1 - a.1);
+            max_feret = max_feret.max(length);
+            let u = ((b.0 - a.0) / length, (b.1 - a.1) / length);
+            let width = extent(points, (-u.1, u.0));
+            min_feret = min_feret.min(width);
+            min_area = min_area.min(width * extent(points, u));
+        }
+    }
+    (max_feret, min_feret, min_area)
+}
+
+fn assert_close(actual: f64, expected: f64, tolerance: f64, context: &str) {
+    assert!((actual - expected).abs() <= tolerance, "{}: {} differs from {}", context, actual, expected);
+}
+
+/// Angles in [0, 180) compared modulo 180
+fn assert_angle(actual: f64, expected: f64, context: &str) {
+    let difference = (actual - expected).rem_euclid(180.0);
+    assert!(difference.min(180.0 - difference) < 1e-6, "{}: angle {} differs from {}", context, actual, expected);
+}
+
+#[test]
+fn convex_hull_matches_brute_force() {
+    for seed in 1..60u64 {
+        let points = noise_points(3 + (seed % 25) as usize, 12, seed);
+        let indices = convex_hull_indices(&points);
+        let hull: Vec<(f64, f64)> = indices.iter().map(|&i| points[i]).collect();
+
+        let mut vertices = hull.clone();
+        vertices.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        assert_eq!(vertices, brute_force_hull_vertices(&points), "hull vertices for seed {}", seed);
+
+        // Strictly convex walk with positive shoelace area
+        if hull.len() >= 3 {
+            for i in 0..hull.len() {
+                let turn = cross(hull[i], hull[(i + 1) % hull.len()], hull[(i + 2) % hull.len()]);
+                assert!(turn > 0.0, "hull of seed {} turns by {} at vertex {}", seed, turn, i);
+            }
+        }
+    }
+}
+
+#[test]
+fn feret_diameters_match_brute_force() {
+    for seed in 1..80u64 {
+        let points = noise_points(3 + (seed % 30) as usize, 1000, seed);
+        let feret = calculate_feret_diameters(&points);
+        let (max_feret, min_feret, min_area) = brute_force_feret(&points);
+        let context = format!("seed {}", seed);
+
+        assert_close(feret.max_feret, max_feret, TOLERANCE, &format!("max Feret of {}", context));
+        assert_close(feret.min_feret, min_feret, 1e-6, &format!("min Feret of {}", context));
+        assert_close(feret.min_rect_length * feret.min_rect_width, min_area, 1e-6 * min_area.max(1.0),
+                     &format!("min rectangle area of {}", context));
+        assert!(feret.min_rect_length >= feret.min_rect_width, "{}: rectangle sides not ordered", context);
+
+        let (p, q) = feret.max_feret_points;
+        assert_close((q.0 - p.0).hypot(q.1 - p.1), feret.max_feret, TOLERANCE, &format!("max Feret points of {}", context));
+    }
+}
+
+#[test]
+fn rectangle_feret_diameters() {
+    for (length, width, angle) in [(120.0, 40.0, 0.0), (40.0, 120.0, 0.0), (90.0, 30.0, 45.0), (64.0, 64.0, 0.0)] {
+        let feret = calculate_feret_diameters(&rectangle(length, width, angle, 16));
+        let (long, short) = if length >= width { (length, width) } else { (width, length) };
+        let context = format!("{} x {} rectangle at {}°", length, width, angle);
+
+        assert_close(feret.max_feret, length.hypot(width), 1e-6, &format!("max Feret of {}", context));
+        assert_close(feret.min_feret, short, 1e-6, &format!("min Feret of {}", context));
+        assert_close(feret.min_rect_length, long, 1e-6, &format!("rectangle length of {}", context));
+        assert_close(feret.min_rect_width, short, 1e-6, &format!("rectangle width of {}", context));
+
+        if length != width {
+            let long_axis = if length > width { angle } else { angle + 90.0 };
+            assert_angle(feret.min_rect_angle, long_axis, &format!("rectangle angle of {}", context));
+            assert_angle(feret.min_feret_angle, long_axis + 90.0, &format!("min Feret angle of {}", context));
+        }
+    }
+}
+
+#[test]
+fn shape_indices_of_circle_and_square() {
+    // Circle: every index is 1.0 except the dissection index, 2√π
+    let radius = 2000.0;
+    let circle: Vec<(f64, f64)> = (0..4096)
+        .map(|i| {
+            let t = 2.0 * std::f64::consts::PI * i as f64 / 4096.0;
+            (radius * t.cos(), radius * t.sin())
+        })
+        .collect();
+    let area = (std::f64::consts::PI * radius * radius).round() as u32;
+    let hull = calculate_convex_hull_metrics(&circle, 1.0);
+    let indices = calculate_shape_indices(area, &hull, &calculate_feret_diameters(&circle));
+
+    assert_close(indices.dissection_index, 2.0 * std::f64::consts::PI.sqrt(), 1e-5, "circle dissection index");
+    assert_close(indices.normalized_dissection_index, 1.0, 1e-5, "circle normalized dissection index");
+    assert_close(indices.form_factor, 1.0, 1e-5, "circle form factor");
+    assert_close(indices.roundness, 1.0, 1e-5, "circle roundness");
+    assert_close(indices.aspect_ratio, 1.0, 1e-3, "circle aspect ratio");
+
+    // Square of side s: P = 4s, A = s², max Feret = s√2
+    let side = 50.0;
+    let square = rectangle(side, side, 0.0, 10);
+    let hull = calculate_convex_hull_metrics(&square, 1.0);
+    let indices = calculate_shape_indices((side * side) as u32, &hull, &calculate_feret_diameters(&square));
+
+    assert_close(indices.dissection_index, 4.0, TOLERANCE, "square dissection index");
+    assert_close(indices.normalized_dissection_index, 2.0 / std::f64::consts::PI.sqrt(), TOLERANCE,
+                 "square normalized dissection index");
+    assert_close(indices.perimeter_area_ratio, 4.0 / side, TOLERANCE, "square perimeter-area ratio");
+    assert_close(indices.form_factor, std::f64::consts::PI / 4.0, TOLERANCE, "square form factor");
+    assert_close(indices.roundness, 2.0 / std::f64::consts::PI, 1e-9, "square roundness");
+    assert_close(indices.aspect_ratio, 1.0, 1e-9, "square aspect ratio");
+}